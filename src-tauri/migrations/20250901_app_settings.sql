@@ -0,0 +1,8 @@
+-- 2025-09-01_app_settings.sql
+-- Small JSON key/value store for app-level preferences (local API, backups, ...)
+
+CREATE TABLE IF NOT EXISTS app_setting (
+  key        TEXT PRIMARY KEY NOT NULL,
+  value      TEXT NOT NULL,              -- JSON
+  updated_at TIMESTAMP NOT NULL
+);
@@ -0,0 +1,23 @@
+-- 2025-09-02_op_journal.sql
+-- Undo/redo history: one op per user action, with before/after images of
+-- every row it touched. Undone ops stay until a new op replaces them (redo).
+
+CREATE TABLE IF NOT EXISTS op_journal (
+  id         INTEGER PRIMARY KEY AUTOINCREMENT,
+  label      TEXT NOT NULL,              -- "Edit task", "Add doc", ...
+  undone     INTEGER NOT NULL DEFAULT 0,
+  created_at TIMESTAMP NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS op_journal_row (
+  op_id    INTEGER NOT NULL,
+  seq      INTEGER NOT NULL,             -- order the rows were touched in
+  tbl      TEXT NOT NULL,
+  row_key  TEXT NOT NULL,                -- JSON object of primary-key columns
+  before   TEXT,                         -- JSON row image, NULL if the row did not exist
+  after    TEXT,                         -- JSON row image, NULL if the row was deleted
+  PRIMARY KEY (op_id, seq),
+  FOREIGN KEY (op_id) REFERENCES op_journal(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_op_journal_undone ON op_journal(undone, id);
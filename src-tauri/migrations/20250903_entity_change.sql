@@ -0,0 +1,15 @@
+-- 2025-09-03_entity_change.sql
+-- Append-only field history for tasks, ideas and projects. One row per field
+-- that changed in an update; values are JSON so types survive (NULL = unset).
+
+CREATE TABLE IF NOT EXISTS entity_change (
+  id          INTEGER PRIMARY KEY AUTOINCREMENT,
+  entity_kind TEXT NOT NULL,              -- task | idea | project
+  entity_id   TEXT NOT NULL,
+  field       TEXT NOT NULL,              -- column name, e.g. end_est_at
+  old_value   TEXT,
+  new_value   TEXT,
+  changed_at  TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_entity_change_entity ON entity_change(entity_kind, entity_id, id);
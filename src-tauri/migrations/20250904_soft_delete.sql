@@ -0,0 +1,14 @@
+-- 2025-09-04_soft_delete.sql
+-- Soft delete: a non-NULL deleted_at puts the row in the Trash and hides it
+-- from listings, search and metrics. Trashing a project stamps its ideas, docs
+-- and tasks with the same deleted_at so they come back together on restore.
+
+ALTER TABLE tasks   ADD COLUMN deleted_at TIMESTAMP;
+ALTER TABLE idea    ADD COLUMN deleted_at TIMESTAMP;
+ALTER TABLE doc     ADD COLUMN deleted_at TIMESTAMP;
+ALTER TABLE project ADD COLUMN deleted_at TIMESTAMP;
+
+CREATE INDEX IF NOT EXISTS idx_tasks_deleted   ON tasks(deleted_at);
+CREATE INDEX IF NOT EXISTS idx_idea_deleted    ON idea(deleted_at);
+CREATE INDEX IF NOT EXISTS idx_doc_deleted     ON doc(deleted_at);
+CREATE INDEX IF NOT EXISTS idx_project_deleted ON project(deleted_at);
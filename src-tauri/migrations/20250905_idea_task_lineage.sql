@@ -0,0 +1,29 @@
+-- 2025-09-05_idea_task_lineage.sql
+-- Fix idea -> task lineage. idea_task_map pointed at a `task` table that does
+-- not exist (it is `tasks`), which made every write touching the map, and
+-- every idea delete, fail with foreign keys on. SQLite cannot alter a foreign
+-- key, so rebuild the table. Also add the tasks.origin_idea_id column that
+-- create_task_from_idea has always written.
+
+ALTER TABLE idea_task_map RENAME TO idea_task_map_old;
+
+CREATE TABLE idea_task_map (
+  idea_id TEXT NOT NULL REFERENCES idea(id)  ON DELETE CASCADE,
+  task_id TEXT NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+  PRIMARY KEY (idea_id, task_id)
+);
+
+INSERT INTO idea_task_map (idea_id, task_id)
+SELECT m.idea_id, m.task_id
+FROM idea_task_map_old m
+WHERE m.idea_id IN (SELECT id FROM idea) AND m.task_id IN (SELECT id FROM tasks);
+
+DROP TABLE idea_task_map_old;
+
+CREATE INDEX IF NOT EXISTS idx_idea_task_map_task ON idea_task_map(task_id);
+
+ALTER TABLE tasks ADD COLUMN origin_idea_id TEXT REFERENCES idea(id) ON DELETE SET NULL;
+
+UPDATE tasks
+SET origin_idea_id = (SELECT m.idea_id FROM idea_task_map m WHERE m.task_id = tasks.id LIMIT 1)
+WHERE origin_idea_id IS NULL;
@@ -0,0 +1,6 @@
+-- 2025-09-06_status_transitions.sql
+-- Status changes now go through a state machine and are logged in
+-- status_reasons together with the status they left. Rows written by the
+-- standalone add_reason keep from_status NULL.
+
+ALTER TABLE status_reasons ADD COLUMN from_status TEXT;
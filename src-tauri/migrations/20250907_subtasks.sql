@@ -0,0 +1,9 @@
+-- 2025-09-07_subtasks.sql
+-- Nested subtasks: a task may hang under another task, to any depth, ordered
+-- among its siblings by ord. Purging a task takes its subtasks with it.
+-- Subtasks always share their parent's project and workspace.
+
+ALTER TABLE tasks ADD COLUMN parent_id TEXT REFERENCES tasks(id) ON DELETE CASCADE;
+ALTER TABLE tasks ADD COLUMN ord INTEGER NOT NULL DEFAULT 0;
+
+CREATE INDEX IF NOT EXISTS idx_tasks_parent ON tasks(parent_id, ord);
@@ -0,0 +1,17 @@
+-- 2025-09-08_estimates.sql
+-- Estimated vs actual time. Tasks get an optional estimate in minutes; actual
+-- time is the sum of the work logged against a task in time_entry.
+
+ALTER TABLE tasks ADD COLUMN estimate_minutes INTEGER;
+
+CREATE TABLE IF NOT EXISTS time_entry (
+  id         TEXT PRIMARY KEY NOT NULL,
+  task_id    TEXT NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+  started_at TIMESTAMP NOT NULL,
+  ended_at   TIMESTAMP,                     -- NULL while a session is running
+  source     TEXT NOT NULL DEFAULT 'manual', -- manual | timer
+  note       TEXT,
+  created_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_time_entry_task ON time_entry(task_id, started_at);
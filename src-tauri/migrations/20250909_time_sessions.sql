@@ -0,0 +1,8 @@
+-- 2025-09-09_time_sessions.sql
+-- Time entries can also point at the idea or doc the work was for. Losing
+-- either keeps the entry; it still counts for its task.
+
+ALTER TABLE time_entry ADD COLUMN idea_id TEXT REFERENCES idea(id) ON DELETE SET NULL;
+ALTER TABLE time_entry ADD COLUMN doc_id TEXT REFERENCES doc(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_time_entry_started ON time_entry(started_at);
@@ -0,0 +1,14 @@
+-- 2025-09-10_reports.sql
+-- Saved productivity reports. The report itself is stored as generated (JSON),
+-- so later edits to tasks or time don't change a saved week.
+
+CREATE TABLE IF NOT EXISTS report (
+  id         TEXT PRIMARY KEY NOT NULL,
+  from_date  TEXT NOT NULL,       -- YYYY-MM-DD in the report's time zone
+  to_date    TEXT NOT NULL,       -- inclusive
+  timezone   TEXT NOT NULL,       -- IANA name, e.g. Europe/Berlin
+  body       TEXT NOT NULL,       -- the Report as JSON
+  created_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_report_from ON report(from_date);
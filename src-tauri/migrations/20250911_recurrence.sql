@@ -0,0 +1,25 @@
+-- 2025-09-11_recurrence.sql
+-- Recurring tasks. A series holds the RRULE and the fields each new
+-- occurrence starts from; its tasks are the occurrences, numbered from 1.
+-- Only the next one exists up front: finishing it schedules the one after.
+
+CREATE TABLE IF NOT EXISTS task_series (
+  id              TEXT PRIMARY KEY NOT NULL,
+  rrule           TEXT NOT NULL,              -- RFC 5545 RRULE value, e.g. FREQ=WEEKLY;BYDAY=MO
+  timezone        TEXT NOT NULL,              -- IANA name the rule expands in
+  dtstart         TIMESTAMP NOT NULL,         -- first occurrence
+  anchor          TEXT NOT NULL DEFAULT 'start', -- start | due: the task field dtstart is
+  last_occurrence INTEGER,                    -- set once the series was stopped or split
+  name            TEXT NOT NULL,
+  category        TEXT NOT NULL,
+  short_summary   TEXT,
+  description     TEXT,
+  estimate_minutes INTEGER,
+  created_at      TIMESTAMP NOT NULL,
+  updated_at      TIMESTAMP NOT NULL
+);
+
+ALTER TABLE tasks ADD COLUMN series_id TEXT REFERENCES task_series(id) ON DELETE SET NULL;
+ALTER TABLE tasks ADD COLUMN occurrence INTEGER;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_tasks_series ON tasks(series_id, occurrence);
@@ -0,0 +1,20 @@
+-- 2025-09-12_reminders.sql
+-- Due-date reminders. One row per reminder that has fired for a task's
+-- current due date, so each fires once and survives restarts along with its
+-- snooze/dismiss state. Rows for an old due date are simply left behind.
+
+CREATE TABLE IF NOT EXISTS reminder (
+  id             TEXT PRIMARY KEY NOT NULL,
+  task_id        TEXT NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+  kind           TEXT NOT NULL,               -- due_soon | overdue
+  offset_minutes INTEGER NOT NULL DEFAULT 0,  -- before due_at; 0 for overdue
+  due_at         TIMESTAMP NOT NULL,          -- the task's end_est_at it was for
+  fire_at        TIMESTAMP NOT NULL,          -- due_at - offset
+  notified_at    TIMESTAMP NOT NULL,          -- last time it was shown
+  snoozed_until  TIMESTAMP,
+  dismissed_at   TIMESTAMP,
+  created_at     TIMESTAMP NOT NULL,
+  UNIQUE (task_id, kind, offset_minutes, due_at)
+);
+
+CREATE INDEX IF NOT EXISTS idx_reminder_open ON reminder(dismissed_at, snoozed_until);
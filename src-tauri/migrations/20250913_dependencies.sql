@@ -0,0 +1,15 @@
+-- 2025-09-13_dependencies.sql
+-- Task dependencies: task_id is blocked by depends_on until that one is
+-- completed. Cycles are refused when a dependency is added.
+
+CREATE TABLE IF NOT EXISTS task_dependency (
+  task_id    TEXT NOT NULL,
+  depends_on TEXT NOT NULL,
+  created_at TIMESTAMP NOT NULL,
+  PRIMARY KEY (task_id, depends_on),
+  CHECK (task_id <> depends_on),
+  FOREIGN KEY (task_id)    REFERENCES tasks(id) ON DELETE CASCADE,
+  FOREIGN KEY (depends_on) REFERENCES tasks(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_task_dependency_on ON task_dependency(depends_on);
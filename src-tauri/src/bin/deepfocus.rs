@@ -0,0 +1,461 @@
+// src/bin/deepfocus.rs
+//! `deepfocus` — terminal client for the Deep Focus database.
+//!
+//! Opens the same `deepfocus.db` as the desktop app (WAL, so both can run at
+//! once) and goes through the same services as the Tauri commands.
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveTime, SecondsFormat, TimeZone, Utc, Weekday};
+use clap::{Args, Parser, Subcommand};
+use serde::Serialize;
+use serde_json::json;
+use sqlx::SqlitePool;
+
+use deep_focus_lib::services::recurrence::RecurrenceInput;
+use deep_focus_lib::services::{docs, ideas, projects, stages, subtasks, tasks};
+use deep_focus_lib::{db, AppError, Result};
+
+#[derive(Parser)]
+#[command(name = "deepfocus", version, about = "Deep Focus from the terminal")]
+struct Cli {
+    /// Print machine-readable JSON instead of text
+    #[arg(long, global = true)]
+    json: bool,
+
+    /// Database file (defaults to the desktop app's deepfocus.db)
+    #[arg(long, global = true, env = "DEEPFOCUS_DB")]
+    db: Option<PathBuf>,
+
+    #[command(subcommand)]
+    cmd: Cmd,
+}
+
+#[derive(Subcommand)]
+enum Cmd {
+    /// Tasks
+    #[command(subcommand)]
+    Task(TaskCmd),
+    /// Ideas
+    #[command(subcommand)]
+    Idea(IdeaCmd),
+    /// Long-form docs
+    #[command(subcommand)]
+    Doc(DocCmd),
+    /// Projects
+    #[command(subcommand)]
+    Project(ProjectCmd),
+    /// Workspaces
+    #[command(subcommand)]
+    Workspace(WorkspaceCmd),
+}
+
+#[derive(Subcommand)]
+enum TaskCmd {
+    /// Create a task
+    Add(TaskAdd),
+    /// List tasks, most recently updated first
+    Ls {
+        /// Project id or `Workspace/Project`
+        #[arg(long, short)]
+        project: Option<String>,
+        #[arg(long, short)]
+        status: Option<tasks::TaskStatus>,
+    },
+    /// Show one task
+    Show { id: String },
+    /// Change a task's status (todo, started, in-progress, stage-complete, completed, dropped)
+    Status {
+        id: String,
+        status: tasks::TaskStatus,
+        /// Reason, required e.g. when dropping or reopening
+        #[arg(long, short)]
+        note: Option<String>,
+    },
+    /// Mark the task's next stage reached
+    Advance { id: String },
+}
+
+#[derive(Args)]
+struct TaskAdd {
+    name: String,
+    #[arg(long, short, default_value = "General")]
+    category: String,
+    /// Project id or `Workspace/Project`
+    #[arg(long, short)]
+    project: Option<String>,
+    /// today, tomorrow, a weekday, +3d, YYYY-MM-DD or RFC 3339
+    #[arg(long)]
+    due: Option<String>,
+    /// Same formats as --due
+    #[arg(long)]
+    start: Option<String>,
+    #[arg(long)]
+    summary: Option<String>,
+    #[arg(long, default_value = "todo")]
+    status: tasks::TaskStatus,
+    /// Add as a subtask of this task id
+    #[arg(long)]
+    parent: Option<String>,
+    /// Estimated effort in minutes
+    #[arg(long)]
+    estimate: Option<i64>,
+    /// Repeat on an RRULE, e.g. "FREQ=WEEKLY;BYDAY=MO" (needs --start or --due)
+    #[arg(long)]
+    repeat: Option<String>,
+    /// IANA time zone the --repeat rule follows
+    #[arg(long, default_value = "UTC")]
+    tz: String,
+}
+
+#[derive(Subcommand)]
+enum IdeaCmd {
+    /// Capture an idea
+    Add {
+        title: String,
+        /// Project id or `Workspace/Project`
+        #[arg(long, short)]
+        project: String,
+        #[arg(long)]
+        summary: Option<String>,
+        /// 0 (highest) ..= 4
+        #[arg(long)]
+        priority: Option<i64>,
+    },
+    /// List ideas, optionally for one project
+    Ls {
+        #[arg(long, short)]
+        project: Option<String>,
+    },
+    /// Show one idea
+    Show { id: String },
+    /// Append a markdown note to an idea
+    Note { id: String, body: String },
+}
+
+#[derive(Subcommand)]
+enum DocCmd {
+    /// List docs, optionally for one project
+    Ls {
+        #[arg(long, short)]
+        project: Option<String>,
+    },
+    /// Print a doc's markdown body
+    Cat { slug: String },
+}
+
+#[derive(Subcommand)]
+enum ProjectCmd {
+    /// List projects
+    Ls {
+        #[arg(long, short)]
+        workspace: Option<String>,
+        /// Include archived projects
+        #[arg(long, short)]
+        all: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum WorkspaceCmd {
+    /// List workspaces
+    Ls,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let json = cli.json;
+
+    match run(cli).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            if json {
+                eprintln!("{}", serde_json::to_string(&e).unwrap_or_else(|_| e.to_string()));
+            } else {
+                eprintln!("error: {e}");
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(cli: Cli) -> Result<()> {
+    let path = cli.db.unwrap_or_else(db::sqlite_path);
+    let pool = db::open_pool(&path).await?;
+    let out = Out { json: cli.json };
+
+    match cli.cmd {
+        Cmd::Task(cmd) => task(&pool, &out, cmd).await,
+        Cmd::Idea(cmd) => idea(&pool, &out, cmd).await,
+        Cmd::Doc(cmd) => doc(&pool, &out, cmd).await,
+        Cmd::Project(ProjectCmd::Ls { workspace, all }) => {
+            let rows = projects::list_projects(&pool, workspace.as_deref(), all).await?;
+            out.print(&rows, || {
+                for p in &rows {
+                    println!("{}  {}/{}  [{}]", p.id, p.workspace_name, p.name, p.status);
+                }
+            })
+        }
+        Cmd::Workspace(WorkspaceCmd::Ls) => {
+            let rows = projects::list_workspaces(&pool).await?;
+            out.print(&rows, || {
+                for w in &rows {
+                    println!("{}  {}  ({})", w.id, w.name, w.kind);
+                }
+            })
+        }
+    }
+}
+
+async fn task(pool: &SqlitePool, out: &Out, cmd: TaskCmd) -> Result<()> {
+    match cmd {
+        TaskCmd::Add(a) => {
+            let project_id = match &a.project {
+                Some(spec) => Some(projects::find_project(pool, spec).await?.id),
+                None => None,
+            };
+            let start_at = a.start.as_deref().map(|s| parse_when(s, false)).transpose()?;
+            let end_est_at = a.due.as_deref().map(|s| parse_when(s, true)).transpose()?;
+
+            let id = tasks::add_task(
+                pool,
+                tasks::TaskInput {
+                    name: a.name,
+                    category: a.category,
+                    short_summary: a.summary,
+                    description: None,
+                    status: a.status,
+                    current_stage: None,
+                    start_at: start_at.map(iso),
+                    end_est_at: end_est_at.map(iso),
+                    project_id,
+                    workspace_id: None,
+                    parent_id: a.parent,
+                    estimate_minutes: a.estimate,
+                    recurrence: a.repeat.map(|rrule| RecurrenceInput { rrule, timezone: a.tz }),
+                },
+            )
+            .await?;
+            out.print(&json!({ "id": id }), || println!("{id}"))
+        }
+        TaskCmd::Ls { project, status } => {
+            let project_id = match &project {
+                Some(spec) => Some(projects::find_project(pool, spec).await?.id),
+                None => None,
+            };
+            let filter = tasks::TaskFilter { project_id, status, ..Default::default() };
+            let rows = tasks::list_tasks(pool, &filter).await?;
+            out.print(&rows, || {
+                for t in &rows {
+                    let due = t.end_est_at.map(local_date).unwrap_or_default();
+                    println!("{}  {:<14}  {:<10}  {}  {}", t.id, t.status, due, t.category, t.name);
+                }
+            })
+        }
+        TaskCmd::Show { id } => {
+            let t = tasks::get_task(pool, &id).await?;
+            out.print(&t, || {
+                println!("{}  [{}]", t.name, t.status);
+                println!("id:       {}", t.id);
+                println!("category: {}", t.category);
+                if let Some(s) = &t.current_stage {
+                    println!("stage:    {s}");
+                }
+                if let Some(d) = t.start_at {
+                    println!("start:    {}", local_date(d));
+                }
+                if let Some(d) = t.end_est_at {
+                    println!("due:      {}", local_date(d));
+                }
+                if let Some(r) = &t.recurrence {
+                    let next = r.next_at.map(local_date).unwrap_or_else(|| "series ends".into());
+                    println!("repeats:  {} ({}), #{}, next {next}", r.rrule, r.timezone, r.occurrence);
+                }
+                if !t.blocked_by.is_empty() {
+                    let names: Vec<String> = t.blocked_by.iter().map(|d| format!("{} ({})", d.name, d.status)).collect();
+                    println!("after:    {}", names.join(", "));
+                }
+                if let Some(e) = t.time.estimate_minutes {
+                    println!("time:     {} of {e} min", t.time.actual_minutes);
+                } else if t.time.actual_minutes > 0 {
+                    println!("time:     {} min", t.time.actual_minutes);
+                }
+                if let Some(s) = &t.short_summary {
+                    println!("\n{s}");
+                }
+                if let Some(d) = &t.description {
+                    println!("\n{d}");
+                }
+                if !t.subtasks.is_empty() {
+                    println!("\nsubtasks ({}% done):", t.progress);
+                    print_subtasks(&t.subtasks, 1);
+                }
+            })
+        }
+        TaskCmd::Status { id, status, note } => {
+            let change = tasks::set_task_status(pool, &id, status, note).await?;
+            out.print(&json!({ "id": id, "status": status, "warning": change.warning }), || {
+                println!("{id} → {status}");
+                if let Some(w) = &change.warning {
+                    println!("warning: {w}");
+                }
+            })
+        }
+        TaskCmd::Advance { id } => {
+            let p = stages::advance_stage(pool, &id).await?;
+            out.print(&p, || {
+                let stage = p.current_stage.as_deref().unwrap_or_default();
+                println!("{id} → {stage} ({}/{}, {}%)", p.reached, p.total, p.percent)
+            })
+        }
+    }
+}
+
+fn print_subtasks(items: &[subtasks::Subtask], depth: usize) {
+    for s in items {
+        println!("{}{:<14}  {:>3}%  {}  {}", "  ".repeat(depth), s.status, s.progress, s.id, s.name);
+        print_subtasks(&s.subtasks, depth + 1);
+    }
+}
+
+async fn idea(pool: &SqlitePool, out: &Out, cmd: IdeaCmd) -> Result<()> {
+    match cmd {
+        IdeaCmd::Add { title, project, summary, priority } => {
+            let project_id = projects::find_project(pool, &project).await?.id;
+            let id = ideas::add_idea(
+                pool,
+                ideas::IdeaInput {
+                    project_id,
+                    title,
+                    summary,
+                    status: None,
+                    priority,
+                    effort_pts: None,
+                    impact_pts: None,
+                },
+            )
+            .await?;
+            out.print(&json!({ "id": id }), || println!("{id}"))
+        }
+        IdeaCmd::Ls { project } => {
+            let mut rows = Vec::new();
+            for p in scoped_projects(pool, project.as_deref()).await? {
+                rows.extend(ideas::list_ideas(pool, &p).await?);
+            }
+            rows.sort_by_key(|r| std::cmp::Reverse(r.updated_at));
+            out.print(&rows, || {
+                for i in &rows {
+                    println!("{}  P{}  {:<9}  {}", i.id, i.priority, i.status, i.title);
+                }
+            })
+        }
+        IdeaCmd::Show { id } => {
+            let i = ideas::get_idea(pool, &id).await?;
+            out.print(&i, || {
+                println!("{}  [{}]  P{}", i.title, i.status, i.priority);
+                println!("id:      {}", i.id);
+                println!("project: {}", i.project_id);
+                if let Some(s) = i.summary.as_deref().filter(|s| !s.is_empty()) {
+                    println!("\n{s}");
+                }
+            })
+        }
+        IdeaCmd::Note { id, body } => {
+            let note_id = ideas::add_idea_note(pool, ideas::IdeaNoteInput { idea_id: id, body_md: body }).await?;
+            out.print(&json!({ "id": note_id }), || println!("{note_id}"))
+        }
+    }
+}
+
+async fn doc(pool: &SqlitePool, out: &Out, cmd: DocCmd) -> Result<()> {
+    match cmd {
+        DocCmd::Ls { project } => {
+            let mut rows = Vec::new();
+            for p in scoped_projects(pool, project.as_deref()).await? {
+                rows.extend(docs::list_docs(pool, &p).await?);
+            }
+            rows.sort_by_key(|r| std::cmp::Reverse(r.updated_at));
+            out.print(&rows, || {
+                for d in &rows {
+                    let slug = d.slug.as_deref().unwrap_or("-");
+                    println!("{}  {:<9}  {:<24}  {}", d.id, d.status, slug, d.title);
+                }
+            })
+        }
+        DocCmd::Cat { slug } => {
+            let d = docs::get_doc_by_slug(pool, &slug).await?;
+            out.print(&d, || print!("{}", d.body_md))
+        }
+    }
+}
+
+/// Ids of one project (by spec) or of every project.
+async fn scoped_projects(pool: &SqlitePool, spec: Option<&str>) -> Result<Vec<String>> {
+    Ok(match spec {
+        Some(spec) => vec![projects::find_project(pool, spec).await?.id],
+        None => projects::list_projects(pool, None, false).await?.into_iter().map(|p| p.id).collect(),
+    })
+}
+
+struct Out {
+    json: bool,
+}
+impl Out {
+    fn print<T: Serialize>(&self, value: &T, text: impl FnOnce()) -> Result<()> {
+        if self.json {
+            let s = serde_json::to_string_pretty(value).map_err(|e| AppError::Other(e.to_string()))?;
+            println!("{s}");
+        } else {
+            text();
+        }
+        Ok(())
+    }
+}
+
+/// Same shape the webview sends (`Date.toISOString()`).
+fn iso(d: DateTime<Utc>) -> String {
+    d.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+fn local_date(d: DateTime<Utc>) -> String {
+    d.with_timezone(&Local).format("%Y-%m-%d").to_string()
+}
+
+/// Parse a human date. Bare days resolve to local start of day, or to the end
+/// of it when `end_of_day` is set (a task "due friday" is due by Friday night).
+fn parse_when(s: &str, end_of_day: bool) -> Result<DateTime<Utc>> {
+    let bad = || AppError::validation("date", format!("Can't read '{s}' as a date"));
+
+    if let Ok(d) = DateTime::parse_from_rfc3339(s) {
+        return Ok(d.with_timezone(&Utc));
+    }
+
+    let today = Local::now().date_naive();
+    let lower = s.trim().to_lowercase();
+    let day = match lower.as_str() {
+        "today" => today,
+        "tomorrow" => today + Duration::days(1),
+        _ => {
+            if let Some(n) = lower.strip_prefix('+').and_then(|n| n.strip_suffix('d')) {
+                today + Duration::days(n.parse().map_err(|_| bad())?)
+            } else if let Ok(wd) = lower.parse::<Weekday>() {
+                let ahead = (7 + wd.num_days_from_monday() - today.weekday().num_days_from_monday()) % 7;
+                today + Duration::days(ahead as i64)
+            } else {
+                NaiveDate::parse_from_str(&lower, "%Y-%m-%d").map_err(|_| bad())?
+            }
+        }
+    };
+
+    let time = if end_of_day {
+        NaiveTime::from_hms_opt(23, 59, 59).expect("valid time")
+    } else {
+        NaiveTime::MIN
+    };
+    Local
+        .from_local_datetime(&day.and_time(time))
+        .earliest()
+        .map(|d| d.with_timezone(&Utc))
+        .ok_or_else(bad)
+}
@@ -0,0 +1,63 @@
+use serde::Serialize;
+use tauri::State;
+
+use crate::http::{self, ApiServer, ApiSettings};
+use crate::services::settings;
+use crate::{AppError, AppState};
+
+pub type CmdResult<T> = std::result::Result<T, AppError>;
+
+#[derive(Debug, Serialize)]
+pub struct ApiStatus {
+    pub enabled: bool,
+    pub port: u16,
+    pub running: bool,
+    pub base_url: Option<String>,
+    pub token: String,
+}
+
+#[tauri::command]
+pub async fn get_api_settings(
+    state: State<'_, AppState>,
+    server: State<'_, ApiServer>,
+) -> CmdResult<ApiStatus> {
+    let cfg: ApiSettings = settings::get_or_default(&state.pool(), http::SETTINGS_KEY).await?;
+    let addr = server.addr();
+    Ok(ApiStatus {
+        enabled: cfg.enabled,
+        port: cfg.port,
+        running: addr.is_some(),
+        base_url: addr.map(|a| format!("http://{a}/api/v1")),
+        token: http::load_or_create_token(&state.app_dir)?,
+    })
+}
+
+/// Persist the settings and start, restart or stop the server to match.
+#[tauri::command]
+pub async fn set_api_settings(
+    state: State<'_, AppState>,
+    server: State<'_, ApiServer>,
+    input: ApiSettings,
+) -> CmdResult<ApiStatus> {
+    if input.enabled {
+        server.start(state.shared_pool(), &state.app_dir, input.port).await?;
+    } else {
+        server.stop().await;
+    }
+    settings::put(&state.pool(), http::SETTINGS_KEY, &input).await?;
+    get_api_settings(state, server).await
+}
+
+/// Issue a new token and restart the server so the old one stops working.
+#[tauri::command]
+pub async fn rotate_api_token(
+    state: State<'_, AppState>,
+    server: State<'_, ApiServer>,
+) -> CmdResult<ApiStatus> {
+    http::rotate_token(&state.app_dir)?;
+    let cfg: ApiSettings = settings::get_or_default(&state.pool(), http::SETTINGS_KEY).await?;
+    if server.addr().is_some() {
+        server.start(state.shared_pool(), &state.app_dir, cfg.port).await?;
+    }
+    get_api_settings(state, server).await
+}
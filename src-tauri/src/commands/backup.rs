@@ -0,0 +1,47 @@
+use tauri::State;
+
+use crate::services::backup::{self, BackupCheck, BackupInfo, BackupSettings};
+use crate::services::timer::Timer;
+use crate::{AppError, AppState};
+
+pub type CmdResult<T> = std::result::Result<T, AppError>;
+
+#[tauri::command]
+pub async fn list_backups(state: State<'_, AppState>) -> CmdResult<Vec<BackupInfo>> {
+    backup::list_backups(&state.app_dir)
+}
+
+#[tauri::command]
+pub async fn create_backup(state: State<'_, AppState>) -> CmdResult<BackupInfo> {
+    backup::create_backup(&state.pool(), &state.app_dir).await
+}
+
+#[tauri::command]
+pub async fn verify_backup(state: State<'_, AppState>, name: String) -> CmdResult<BackupCheck> {
+    backup::verify_backup(&state.app_dir, &name).await
+}
+
+/// Swap the live database for `name`. The frontend should reload its views afterwards.
+#[tauri::command]
+pub async fn restore_backup(
+    state: State<'_, AppState>,
+    timer: State<'_, Timer>,
+    name: String,
+) -> CmdResult<()> {
+    let pool = backup::restore_backup(&state.shared_pool(), &state.db_path, &state.app_dir, &name).await?;
+    timer.load(&pool).await?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_backup_settings(state: State<'_, AppState>) -> CmdResult<BackupSettings> {
+    backup::get_settings(&state.pool()).await
+}
+
+#[tauri::command]
+pub async fn set_backup_settings(
+    state: State<'_, AppState>,
+    input: BackupSettings,
+) -> CmdResult<()> {
+    backup::set_settings(&state.pool(), input).await
+}
@@ -0,0 +1,13 @@
+use tauri::State;
+
+use crate::events::EntityKind;
+use crate::services::history::{self, FieldChange};
+use crate::{AppError, AppState};
+
+pub type CmdResult<T> = std::result::Result<T, AppError>;
+
+/// Field changes for a task, idea or project, oldest first.
+#[tauri::command]
+pub async fn get_history(state: State<'_, AppState>, kind: EntityKind, id: String) -> CmdResult<Vec<FieldChange>> {
+    history::get_history(&state.pool(), kind, &id).await
+}
@@ -0,0 +1,23 @@
+use tauri::State;
+
+use crate::services::journal::{self, OpSummary, UndoState};
+use crate::{AppError, AppState};
+
+pub type CmdResult<T> = std::result::Result<T, AppError>;
+
+/// Revert the last change. Returns what was undone, or null if history is empty.
+#[tauri::command]
+pub async fn undo(state: State<'_, AppState>) -> CmdResult<Option<OpSummary>> {
+    journal::undo(&state.pool()).await
+}
+
+#[tauri::command]
+pub async fn redo(state: State<'_, AppState>) -> CmdResult<Option<OpSummary>> {
+    journal::redo(&state.pool()).await
+}
+
+/// Labels for the Edit menu ("Undo Edit task", "Redo Add doc").
+#[tauri::command]
+pub async fn get_undo_state(state: State<'_, AppState>) -> CmdResult<UndoState> {
+    journal::undo_state(&state.pool()).await
+}
@@ -1,38 +1,34 @@
 // src/commands/metrics.rs
+use chrono::{DateTime, Utc};
 use tauri::State;
-use crate::AppState;
 
-pub type CmdResult<T> = Result<T, String>;
+use crate::services::metrics::{self, AccuracyGroup, EstimateAccuracy};
+use crate::{AppError, AppState};
+
+pub type CmdResult<T> = std::result::Result<T, AppError>;
 
 #[tauri::command]
 pub async fn count_tasks_by_project(state: State<'_, AppState>, project_id: String) -> CmdResult<i64> {
-    let pool = &state.pool;
-    let n: i64 = sqlx::query_scalar("SELECT COUNT(1) FROM tasks WHERE project_id = ?1")
-        .bind(project_id)
-        .fetch_one(pool)
-        .await
-        .map_err(|e| e.to_string())?;
-    Ok(n)
+    metrics::count_tasks_by_project(&state.pool(), &project_id).await
 }
 
 #[tauri::command]
 pub async fn count_ideas_by_project(state: State<'_, AppState>, project_id: String) -> CmdResult<i64> {
-    let pool = &state.pool;
-    let n: i64 = sqlx::query_scalar("SELECT COUNT(1) FROM idea WHERE project_id = ?1")
-        .bind(project_id)
-        .fetch_one(pool)
-        .await
-        .map_err(|e| e.to_string())?;
-    Ok(n)
+    metrics::count_ideas_by_project(&state.pool(), &project_id).await
 }
 
 #[tauri::command]
 pub async fn count_docs_by_project(state: State<'_, AppState>, project_id: String) -> CmdResult<i64> {
-    let pool = &state.pool;
-    let n: i64 = sqlx::query_scalar("SELECT COUNT(1) FROM doc WHERE project_id = ?1")
-        .bind(project_id)
-        .fetch_one(pool)
-        .await
-        .map_err(|e| e.to_string())?;
-    Ok(n)
+    metrics::count_docs_by_project(&state.pool(), &project_id).await
+}
+
+/// Estimated vs actual minutes of completed tasks, per category or project.
+#[tauri::command]
+pub async fn estimate_accuracy(
+    state: State<'_, AppState>,
+    group: AccuracyGroup,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) -> CmdResult<Vec<EstimateAccuracy>> {
+    metrics::estimate_accuracy(&state.pool(), group, from, to).await
 }
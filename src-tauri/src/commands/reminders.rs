@@ -0,0 +1,37 @@
+use chrono::{DateTime, Utc};
+use tauri::State;
+
+use crate::services::reminders::{self, Notification, ReminderSettings};
+use crate::{AppError, AppState};
+
+pub type CmdResult<T> = std::result::Result<T, AppError>;
+
+#[tauri::command]
+pub async fn list_notifications(state: State<'_, AppState>) -> CmdResult<Vec<Notification>> {
+    reminders::list_notifications(&state.pool(), Utc::now()).await
+}
+
+#[tauri::command]
+pub async fn snooze_notification(state: State<'_, AppState>, id: String, until: DateTime<Utc>) -> CmdResult<()> {
+    reminders::snooze_notification(&state.pool(), &id, until).await
+}
+
+#[tauri::command]
+pub async fn dismiss_notification(state: State<'_, AppState>, id: String) -> CmdResult<()> {
+    reminders::dismiss_notification(&state.pool(), &id).await
+}
+
+#[tauri::command]
+pub async fn dismiss_all_notifications(state: State<'_, AppState>) -> CmdResult<u64> {
+    reminders::dismiss_all_notifications(&state.pool()).await
+}
+
+#[tauri::command]
+pub async fn get_reminder_settings(state: State<'_, AppState>) -> CmdResult<ReminderSettings> {
+    reminders::get_settings(&state.pool()).await
+}
+
+#[tauri::command]
+pub async fn set_reminder_settings(state: State<'_, AppState>, settings: ReminderSettings) -> CmdResult<()> {
+    reminders::set_settings(&state.pool(), settings).await
+}
@@ -0,0 +1,57 @@
+use std::path::PathBuf;
+
+use tauri::State;
+
+use crate::services::report_export::{self, ReportFormat};
+use crate::services::reports::{self, Report, ReportInput, ReportSummary};
+use crate::{AppError, AppState};
+
+pub type CmdResult<T> = std::result::Result<T, AppError>;
+
+#[tauri::command]
+pub async fn generate_report(state: State<'_, AppState>, input: ReportInput) -> CmdResult<Report> {
+    reports::generate_report(&state.pool(), &input).await
+}
+
+#[tauri::command]
+pub async fn save_report(state: State<'_, AppState>, input: ReportInput) -> CmdResult<Report> {
+    reports::save_report(&state.pool(), &input).await
+}
+
+#[tauri::command]
+pub async fn list_reports(state: State<'_, AppState>) -> CmdResult<Vec<ReportSummary>> {
+    reports::list_reports(&state.pool()).await
+}
+
+#[tauri::command]
+pub async fn get_report(state: State<'_, AppState>, id: String) -> CmdResult<Report> {
+    reports::get_report(&state.pool(), &id).await
+}
+
+#[tauri::command]
+pub async fn delete_report(state: State<'_, AppState>, id: String) -> CmdResult<()> {
+    reports::delete_report(&state.pool(), &id).await
+}
+
+/// The report as text, e.g. for a preview or the clipboard.
+#[tauri::command]
+pub async fn render_report(report: Report, format: ReportFormat) -> CmdResult<String> {
+    Ok(report_export::render(&report, format))
+}
+
+/// Write the report to `path` (picked by a save dialog).
+#[tauri::command]
+pub async fn export_report(report: Report, format: ReportFormat, path: String) -> CmdResult<()> {
+    report_export::export_report(&report, format, &PathBuf::from(path))
+}
+
+/// Keep the report as a new doc in `project_id`; returns the doc id.
+#[tauri::command]
+pub async fn report_to_doc(
+    state: State<'_, AppState>,
+    report: Report,
+    project_id: String,
+    title: Option<String>,
+) -> CmdResult<String> {
+    report_export::report_to_doc(&state.pool(), &report, &project_id, title).await
+}
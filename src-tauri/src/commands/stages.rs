@@ -0,0 +1,37 @@
+use tauri::State;
+
+use crate::services::stages::{self, Stage, StageInput, StageProgress, UpdateStageInput};
+use crate::{AppError, AppState};
+
+pub type CmdResult<T> = std::result::Result<T, AppError>;
+
+#[tauri::command]
+pub async fn list_stages(state: State<'_, AppState>, task_id: String) -> CmdResult<Vec<Stage>> {
+    stages::list_stages(&state.pool(), &task_id).await
+}
+
+#[tauri::command]
+pub async fn add_stage(state: State<'_, AppState>, input: StageInput) -> CmdResult<String> {
+    stages::add_stage(&state.pool(), input).await
+}
+
+#[tauri::command]
+pub async fn update_stage(state: State<'_, AppState>, input: UpdateStageInput) -> CmdResult<()> {
+    stages::update_stage(&state.pool(), input).await
+}
+
+#[tauri::command]
+pub async fn reorder_stages(state: State<'_, AppState>, task_id: String, ids: Vec<String>) -> CmdResult<()> {
+    stages::reorder_stages(&state.pool(), &task_id, &ids).await
+}
+
+#[tauri::command]
+pub async fn remove_stage(state: State<'_, AppState>, id: String) -> CmdResult<()> {
+    stages::remove_stage(&state.pool(), &id).await
+}
+
+/// Reach the next stage; the task goes to `stage-complete`.
+#[tauri::command]
+pub async fn advance_stage(state: State<'_, AppState>, task_id: String) -> CmdResult<StageProgress> {
+    stages::advance_stage(&state.pool(), &task_id).await
+}
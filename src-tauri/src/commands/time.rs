@@ -0,0 +1,52 @@
+use chrono::{DateTime, Utc};
+use tauri::State;
+
+use crate::services::time::{self, LogTimeInput, StartSessionInput, TimeEntry, UpdateTimeEntryInput};
+use crate::{AppError, AppState};
+
+pub type CmdResult<T> = std::result::Result<T, AppError>;
+
+#[tauri::command]
+pub async fn list_time_entries(state: State<'_, AppState>, task_id: String) -> CmdResult<Vec<TimeEntry>> {
+    time::list_time_entries(&state.pool(), &task_id).await
+}
+
+#[tauri::command]
+pub async fn running_session(state: State<'_, AppState>) -> CmdResult<Option<TimeEntry>> {
+    time::running_session(&state.pool()).await
+}
+
+#[tauri::command]
+pub async fn log_time(state: State<'_, AppState>, input: LogTimeInput) -> CmdResult<String> {
+    time::log_time(&state.pool(), input).await
+}
+
+#[tauri::command]
+pub async fn start_session(state: State<'_, AppState>, input: StartSessionInput) -> CmdResult<TimeEntry> {
+    time::start_session(&state.pool(), input).await
+}
+
+#[tauri::command]
+pub async fn stop_session(state: State<'_, AppState>, id: String) -> CmdResult<TimeEntry> {
+    time::stop_session(&state.pool(), &id).await
+}
+
+#[tauri::command]
+pub async fn update_time_entry(state: State<'_, AppState>, input: UpdateTimeEntryInput) -> CmdResult<TimeEntry> {
+    time::update_time_entry(&state.pool(), input).await
+}
+
+#[tauri::command]
+pub async fn split_time_entry(state: State<'_, AppState>, id: String, at: DateTime<Utc>) -> CmdResult<String> {
+    time::split_time_entry(&state.pool(), &id, at).await
+}
+
+#[tauri::command]
+pub async fn merge_time_entries(state: State<'_, AppState>, ids: Vec<String>) -> CmdResult<String> {
+    time::merge_time_entries(&state.pool(), &ids).await
+}
+
+#[tauri::command]
+pub async fn delete_time_entry(state: State<'_, AppState>, id: String) -> CmdResult<()> {
+    time::delete_time_entry(&state.pool(), &id).await
+}
@@ -0,0 +1,46 @@
+use tauri::State;
+
+use crate::services::timer::{self, Timer, TimerSettings, TimerState};
+use crate::{AppError, AppState};
+
+pub type CmdResult<T> = std::result::Result<T, AppError>;
+
+#[tauri::command]
+pub async fn get_timer(timer: State<'_, Timer>) -> CmdResult<TimerState> {
+    Ok(timer.state().await)
+}
+
+#[tauri::command]
+pub async fn start_timer(state: State<'_, AppState>, timer: State<'_, Timer>, task_id: String) -> CmdResult<TimerState> {
+    timer.start(&state.pool(), &task_id).await
+}
+
+#[tauri::command]
+pub async fn pause_timer(state: State<'_, AppState>, timer: State<'_, Timer>) -> CmdResult<TimerState> {
+    timer.pause(&state.pool()).await
+}
+
+#[tauri::command]
+pub async fn resume_timer(state: State<'_, AppState>, timer: State<'_, Timer>) -> CmdResult<TimerState> {
+    timer.resume(&state.pool()).await
+}
+
+#[tauri::command]
+pub async fn skip_timer(state: State<'_, AppState>, timer: State<'_, Timer>) -> CmdResult<TimerState> {
+    timer.skip(&state.pool()).await
+}
+
+#[tauri::command]
+pub async fn stop_timer(state: State<'_, AppState>, timer: State<'_, Timer>) -> CmdResult<TimerState> {
+    timer.stop(&state.pool()).await
+}
+
+#[tauri::command]
+pub async fn get_timer_settings(state: State<'_, AppState>) -> CmdResult<TimerSettings> {
+    timer::get_settings(&state.pool()).await
+}
+
+#[tauri::command]
+pub async fn set_timer_settings(state: State<'_, AppState>, input: TimerSettings) -> CmdResult<()> {
+    timer::set_settings(&state.pool(), input).await
+}
@@ -0,0 +1,45 @@
+use tauri::State;
+
+use crate::events::EntityKind;
+use crate::services::trash::{self, DeletePreview, PurgeReport, TrashItem};
+use crate::{AppError, AppState};
+
+pub type CmdResult<T> = std::result::Result<T, AppError>;
+
+#[tauri::command]
+pub async fn list_trash(state: State<'_, AppState>) -> CmdResult<Vec<TrashItem>> {
+    trash::list_trash(&state.pool()).await
+}
+
+#[tauri::command]
+pub async fn restore_from_trash(state: State<'_, AppState>, kind: EntityKind, id: String) -> CmdResult<()> {
+    trash::restore(&state.pool(), kind, &id).await
+}
+
+/// Delete permanently, including attachment files. Cannot be undone.
+#[tauri::command]
+pub async fn purge(state: State<'_, AppState>, kind: EntityKind, id: String) -> CmdResult<PurgeReport> {
+    trash::purge(&state.pool(), &state.app_dir, kind, &id).await
+}
+
+#[tauri::command]
+pub async fn empty_trash(state: State<'_, AppState>) -> CmdResult<PurgeReport> {
+    trash::empty_trash(&state.pool(), &state.app_dir).await
+}
+
+/// Row counts and attachment bytes a permanent delete would remove.
+#[tauri::command]
+pub async fn preview_delete(state: State<'_, AppState>, kind: EntityKind, id: String) -> CmdResult<DeletePreview> {
+    trash::preview_delete(&state.pool(), &state.app_dir, kind, &id).await
+}
+
+/// Delete permanently, skipping the Trash; `token` comes from `preview_delete`.
+#[tauri::command]
+pub async fn delete_permanently(
+    state: State<'_, AppState>,
+    kind: EntityKind,
+    id: String,
+    token: String,
+) -> CmdResult<PurgeReport> {
+    trash::delete_permanently(&state.pool(), &state.app_dir, kind, &id, &token).await
+}
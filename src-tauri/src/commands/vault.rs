@@ -0,0 +1,35 @@
+use std::path::PathBuf;
+
+use tauri::State;
+
+use crate::services::backup;
+use crate::services::vault::{self, ImportMode, ImportReport, Manifest};
+use crate::{AppError, AppState};
+
+pub type CmdResult<T> = std::result::Result<T, AppError>;
+
+/// Write a zip archive of the whole vault to `path` (picked by a save dialog).
+#[tauri::command]
+pub async fn export_vault(state: State<'_, AppState>, path: String) -> CmdResult<Manifest> {
+    vault::export_vault(&state.pool(), &state.app_dir, &PathBuf::from(path)).await
+}
+
+/// Check an archive before importing it, so the UI can show what it holds.
+#[tauri::command]
+pub async fn inspect_vault_archive(path: String) -> CmdResult<Manifest> {
+    vault::read_manifest(&PathBuf::from(path))
+}
+
+#[tauri::command]
+pub async fn import_vault(
+    state: State<'_, AppState>,
+    path: String,
+    mode: ImportMode,
+) -> CmdResult<ImportReport> {
+    let pool = state.pool();
+    // replace wipes everything, so keep a way back
+    if mode == ImportMode::Replace {
+        backup::create_backup(&pool, &state.app_dir).await?;
+    }
+    vault::import_vault(&pool, &state.app_dir, &PathBuf::from(path), mode).await
+}
@@ -1,44 +1,100 @@
-use std::{fs, path::PathBuf, time::Duration};
+use std::{fs, path::{Component, Path, PathBuf}, sync::{Arc, RwLock}, time::Duration};
 use directories::ProjectDirs;
-use sqlx::{sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions, SqliteSynchronous}, SqlitePool };
+use sqlx::{migrate::Migrator, sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions, SqliteSynchronous}, SqlitePool };
 
-fn sqlite_path() -> PathBuf {
+/// Embedded schema migrations, shared by the app and the test harness.
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+/// Per-user data dir holding `deepfocus.db` and `attachments/`.
+pub fn app_dir() -> PathBuf {
     // Change org/app names to whatever you like
     let proj = ProjectDirs::from("com", "DeepFocus", "DeepFocus")
         .expect("could not determine project dirs");
     let dir = proj.data_dir();
-    print!("{:?}",dir);
     fs::create_dir_all(dir).expect("create app data dir");
-    dir.join("deepfocus.db")
+    dir.to_path_buf()
 }
 
-pub async fn init_pool() -> SqlitePool {
-    let path = sqlite_path();
-  //let url = format!("sqlite:{}", path.to_string_lossy());
+pub fn sqlite_path() -> PathBuf {
+    app_dir().join("deepfocus.db")
+}
+
+/// Where the files we store for doc and idea attachments live.
+pub fn attachments_dir(app_dir: &Path) -> PathBuf {
+    app_dir.join("attachments")
+}
+
+/// `true` if `path` names a file inside [`attachments_dir`], i.e. one of ours
+/// that purge may delete. `..` is refused outright rather than resolved, so a
+/// path can't start inside the directory and climb out of it.
+pub fn is_stored_attachment(app_dir: &Path, path: &Path) -> bool {
+    let root = attachments_dir(app_dir);
+    path.is_absolute()
+        && !path.components().any(|c| matches!(c, Component::ParentDir | Component::CurDir))
+        && path.starts_with(&root)
+        && path != root
+}
 
+/// Open (or create) the database at `path` and bring it up to date.
+///
+/// WAL plus a busy timeout lets the desktop app and the `deepfocus` CLI
+/// share one file: readers never block, writers wait their turn.
+pub async fn open_pool(path: &Path) -> crate::Result<SqlitePool> {
     let opts = SqliteConnectOptions::new()
-        .filename(&path)
+        .filename(path)
         .create_if_missing(true)
         .journal_mode(SqliteJournalMode::Wal)
         .synchronous(SqliteSynchronous::Normal)
         .busy_timeout(Duration::from_secs(5)) // nice-to-have
-        .foreign_keys(true);     
+        .foreign_keys(true);
 
     let pool = SqlitePoolOptions::new()
         .max_connections(5)
         .connect_with(opts)
+        .await?;
+
+    MIGRATOR.run(&pool).await.map_err(sqlx::Error::from)?;
+    crate::services::docs::refresh_html(&pool).await?;
+
+    sqlx::query("PRAGMA optimize;").execute(&pool).await.ok();
+
+    Ok(pool)
+}
+
+pub async fn init_pool() -> SqlitePool {
+    let path = sqlite_path();
+    open_pool(&path)
         .await
         .unwrap_or_else(|e| {
-            panic!("connect sqlite at {} failed: {e}", path.display());
-        });
+            panic!("open sqlite at {} failed: {e}", path.display());
+        })
+}
 
-    sqlx::migrate!("./migrations")
-        .run(&pool)
-        .await
-        .expect("run migrations");
+/// The live pool behind a lock, shared by Tauri state and the local API so
+/// a restore can swap the database under both. Take a fresh handle with
+/// [`SharedPool::get`] per operation instead of keeping one around.
+#[derive(Clone)]
+pub struct SharedPool(Arc<RwLock<SqlitePool>>);
 
-        sqlx::query("PRAGMA optimize;").execute(&pool).await.ok();
+impl SharedPool {
+    pub fn new(pool: SqlitePool) -> Self {
+        SharedPool(Arc::new(RwLock::new(pool)))
+    }
 
+    pub fn get(&self) -> SqlitePool {
+        self.0.read().expect("pool lock").clone()
+    }
 
-    pool
-}
\ No newline at end of file
+    pub fn set(&self, pool: SqlitePool) {
+        *self.0.write().expect("pool lock") = pool;
+    }
+}
+
+/// `true` if `table` has a row with this primary key. `table` must be a trusted literal.
+pub async fn row_exists(pool: &SqlitePool, table: &str, id: &str) -> sqlx::Result<bool> {
+    let n: i64 = sqlx::query_scalar(&format!("SELECT COUNT(1) FROM {table} WHERE id = ?1"))
+        .bind(id)
+        .fetch_one(pool)
+        .await?;
+    Ok(n > 0)
+}
@@ -0,0 +1,157 @@
+// src/error.rs
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use thiserror::Error;
+
+/// Stable error codes the frontend can branch on without matching strings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+    NotFound,
+    Validation,
+    Conflict,
+    ForeignKey,
+    Io,
+    Db,
+    Other,
+}
+impl ErrorCode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCode::NotFound => "NotFound",
+            ErrorCode::Validation => "Validation",
+            ErrorCode::Conflict => "Conflict",
+            ErrorCode::ForeignKey => "ForeignKey",
+            ErrorCode::Io => "Io",
+            ErrorCode::Db => "Db",
+            ErrorCode::Other => "Other",
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum AppError {
+    #[error("{entity} not found")]
+    NotFound { entity: &'static str, id: Option<String> },
+
+    #[error("{message}")]
+    Validation { field: Option<&'static str>, message: String },
+
+    #[error("{message}")]
+    Conflict { entity: Option<&'static str>, field: Option<&'static str>, message: String },
+
+    #[error("{entity} not found for {field}")]
+    ForeignKey { entity: &'static str, field: &'static str, id: Option<String> },
+
+    #[error("io: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("db: {0}")]
+    Db(sqlx::Error),
+
+    #[error("{0}")]
+    Other(String),
+}
+
+impl AppError {
+    pub fn not_found(entity: &'static str, id: impl Into<String>) -> Self {
+        AppError::NotFound { entity, id: Some(id.into()) }
+    }
+
+    pub fn validation(field: &'static str, message: impl Into<String>) -> Self {
+        AppError::Validation { field: Some(field), message: message.into() }
+    }
+
+    pub fn conflict(entity: &'static str, field: &'static str, message: impl Into<String>) -> Self {
+        AppError::Conflict { entity: Some(entity), field: Some(field), message: message.into() }
+    }
+
+    /// A referenced row (e.g. the project behind `project_id`) does not exist.
+    pub fn foreign_key(entity: &'static str, field: &'static str, id: impl Into<String>) -> Self {
+        AppError::ForeignKey { entity, field, id: Some(id.into()) }
+    }
+
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            AppError::NotFound { .. } => ErrorCode::NotFound,
+            AppError::Validation { .. } => ErrorCode::Validation,
+            AppError::Conflict { .. } => ErrorCode::Conflict,
+            AppError::ForeignKey { .. } => ErrorCode::ForeignKey,
+            AppError::Io(_) => ErrorCode::Io,
+            AppError::Db(_) => ErrorCode::Db,
+            AppError::Other(_) => ErrorCode::Other,
+        }
+    }
+
+    fn entity(&self) -> Option<&'static str> {
+        match self {
+            AppError::NotFound { entity, .. } | AppError::ForeignKey { entity, .. } => Some(entity),
+            AppError::Conflict { entity, .. } => *entity,
+            _ => None,
+        }
+    }
+
+    fn field(&self) -> Option<&'static str> {
+        match self {
+            AppError::Validation { field, .. } | AppError::Conflict { field, .. } => *field,
+            AppError::ForeignKey { field, .. } => Some(field),
+            _ => None,
+        }
+    }
+
+    fn id(&self) -> Option<&str> {
+        match self {
+            AppError::NotFound { id, .. } | AppError::ForeignKey { id, .. } => id.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+// Map SQLite constraint failures onto stable codes; everything else stays `Db`.
+impl From<sqlx::Error> for AppError {
+    fn from(e: sqlx::Error) -> Self {
+        match &e {
+            sqlx::Error::RowNotFound => AppError::NotFound { entity: "record", id: None },
+            sqlx::Error::Database(db) if db.is_unique_violation() => AppError::Conflict {
+                entity: None,
+                field: None,
+                message: db.message().to_string(),
+            },
+            sqlx::Error::Database(db) if db.is_foreign_key_violation() => AppError::ForeignKey {
+                entity: "record",
+                field: "reference",
+                id: None,
+            },
+            sqlx::Error::Database(db) if db.is_check_violation() => AppError::Validation {
+                field: None,
+                message: db.message().to_string(),
+            },
+            _ => AppError::Db(e),
+        }
+    }
+}
+
+/// Serialized to the UI as `{ code, message, entity?, field?, id? }`.
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("AppError", 5)?;
+        s.serialize_field("code", self.code().as_str())?;
+        s.serialize_field("message", &self.to_string())?;
+        if let Some(entity) = self.entity() {
+            s.serialize_field("entity", entity)?;
+        } else {
+            s.skip_field("entity")?;
+        }
+        if let Some(field) = self.field() {
+            s.serialize_field("field", field)?;
+        } else {
+            s.skip_field("field")?;
+        }
+        if let Some(id) = self.id() {
+            s.serialize_field("id", id)?;
+        } else {
+            s.skip_field("id")?;
+        }
+        s.end()
+    }
+}
+
+pub type Result<T> = std::result::Result<T, AppError>;
@@ -0,0 +1,65 @@
+// src/events.rs
+//! In-process change feed.
+//!
+//! Services call [`publish`] after a write commits; `run()` forwards every
+//! change to all windows as the `entity-changed` Tauri event. Writes made via
+//! the local HTTP API go through the same services, so they show up too.
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+/// Tauri event name the frontend listens on.
+pub const ENTITY_CHANGED: &str = "entity-changed";
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EntityKind {
+    Task,
+    Idea,
+    Doc,
+    Project,
+    Workspace,
+    Vault, // everything may have changed (restore, import); refetch all
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeOp {
+    Created,
+    Updated,
+    Deleted,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct EntityChanged {
+    pub kind: EntityKind,
+    pub id: String,
+    pub op: ChangeOp,
+}
+
+impl EntityChanged {
+    /// Catch-all telling views to refetch everything; `id` is `"*"`.
+    pub fn vault() -> Self {
+        EntityChanged { kind: EntityKind::Vault, id: "*".into(), op: ChangeOp::Updated }
+    }
+}
+
+fn bus() -> &'static broadcast::Sender<EntityChanged> {
+    static BUS: OnceLock<broadcast::Sender<EntityChanged>> = OnceLock::new();
+    BUS.get_or_init(|| broadcast::channel(256).0)
+}
+
+/// Announce a committed change. A no-op when nobody is listening.
+pub fn publish(kind: EntityKind, id: impl Into<String>, op: ChangeOp) {
+    bus().send(EntityChanged { kind, id: id.into(), op }).ok();
+}
+
+pub fn subscribe() -> broadcast::Receiver<EntityChanged> {
+    bus().subscribe()
+}
+
+/// After restore or import, when individual changes aren't worth listing.
+pub fn publish_vault_changed() {
+    bus().send(EntityChanged::vault()).ok();
+}
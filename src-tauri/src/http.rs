@@ -0,0 +1,680 @@
+// src/http.rs
+//! Optional loopback REST API for editor plugins and shell scripts.
+//!
+//! Binds to 127.0.0.1 only and requires `Authorization: Bearer <token>`,
+//! where the token lives in `app_dir/api-token`. Every handler calls the
+//! same service function as the matching Tauri command.
+use std::net::{Ipv4Addr, SocketAddr};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use axum::extract::{FromRequest, FromRequestParts, Path as UrlPath, Query, Request, State};
+use axum::http::request::Parts;
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{delete, get, patch, post, put};
+use axum::{Json, Router};
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sqlx::SqlitePool;
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+use crate::services::{
+    dependencies, docs, ideas, metrics, projects, recurrence, report_export, reports, settings, stages, subtasks, tasks, time,
+};
+use crate::db::SharedPool;
+use crate::{AppError, Result};
+
+pub const SETTINGS_KEY: &str = "api";
+const TOKEN_FILE: &str = "api-token";
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub struct ApiSettings {
+    pub enabled: bool,
+    pub port: u16,
+}
+impl Default for ApiSettings {
+    fn default() -> Self {
+        ApiSettings { enabled: false, port: 4317 }
+    }
+}
+
+// ---------- Token ----------
+
+/// Read the per-install token, creating it on first use.
+pub fn load_or_create_token(app_dir: &Path) -> Result<String> {
+    let path = app_dir.join(TOKEN_FILE);
+    match std::fs::read_to_string(&path) {
+        Ok(s) if !s.trim().is_empty() => Ok(s.trim().to_string()),
+        Ok(_) => write_token(&path),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => write_token(&path),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Replace the token; clients holding the old one get 401 from now on.
+pub fn rotate_token(app_dir: &Path) -> Result<String> {
+    write_token(&app_dir.join(TOKEN_FILE))
+}
+
+fn write_token(path: &Path) -> Result<String> {
+    use std::io::Write;
+
+    let token = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+    let mut opts = std::fs::OpenOptions::new();
+    opts.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut opts, 0o600);
+    let mut file = opts.open(path)?;
+    // the mode only applies to a new file; an existing one is tightened before the token goes in
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+    }
+    file.write_all(token.as_bytes())?;
+    Ok(token)
+}
+
+// ---------- Server lifecycle ----------
+
+/// Handle to the running server, managed as Tauri state.
+#[derive(Default)]
+pub struct ApiServer {
+    running: Mutex<Option<Running>>,
+}
+
+struct Running {
+    addr: SocketAddr,
+    shutdown: oneshot::Sender<()>,
+    task: JoinHandle<()>,
+}
+
+impl ApiServer {
+    /// (Re)start on `port`. Binding happens before this returns, so a busy
+    /// port surfaces as an `Io` error instead of a silent background failure.
+    pub async fn start(&self, pool: SharedPool, app_dir: &Path, port: u16) -> Result<SocketAddr> {
+        self.stop().await;
+
+        let token = load_or_create_token(app_dir)?;
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port)).await?;
+        let addr = listener.local_addr()?;
+        let (shutdown, rx) = oneshot::channel::<()>();
+
+        let app = router(pool, token);
+        let task = tokio::spawn(async move {
+            let signal = async {
+                rx.await.ok();
+            };
+            if let Err(e) = axum::serve(listener, app).with_graceful_shutdown(signal).await {
+                log::error!("local api stopped: {e}");
+            }
+        });
+
+        *self.running.lock().expect("api lock") = Some(Running { addr, shutdown, task });
+        Ok(addr)
+    }
+
+    /// Stop the server and wait until the port is released.
+    pub async fn stop(&self) {
+        let running = self.running.lock().expect("api lock").take();
+        if let Some(r) = running {
+            r.shutdown.send(()).ok();
+            r.task.await.ok();
+        }
+    }
+
+    pub fn addr(&self) -> Option<SocketAddr> {
+        self.running.lock().expect("api lock").as_ref().map(|r| r.addr)
+    }
+}
+
+/// Start the server if the user enabled it. Called once from `run()`.
+pub async fn start_if_enabled(server: &ApiServer, pool: SharedPool, app_dir: &Path) -> Result<()> {
+    let cfg: ApiSettings = settings::get_or_default(&pool.get(), SETTINGS_KEY).await?;
+    if cfg.enabled {
+        server.start(pool, app_dir, cfg.port).await?;
+    }
+    Ok(())
+}
+
+// ---------- Router ----------
+
+#[derive(Clone)]
+struct Api {
+    pool: SharedPool, // looked up per request so a restore carries over
+    token: Arc<str>,
+}
+
+impl Api {
+    fn pool(&self) -> SqlitePool {
+        self.pool.get()
+    }
+}
+
+pub fn router(pool: SharedPool, token: String) -> Router {
+    let api = Api { pool, token: token.into() };
+
+    let v1 = Router::new()
+        // tasks
+        .route("/tasks", get(list_tasks).post(add_task))
+        .route("/tasks/{id}", get(get_task).patch(update_task).delete(delete_task))
+        .route("/tasks/{id}/status", put(set_task_status))
+        .route("/tasks/{id}/reasons", get(list_status_reasons).post(add_reason))
+        .route("/tasks/{id}/project", put(move_task))
+        .route("/tasks/{id}/parent", put(set_task_parent))
+        .route("/tasks/{id}/subtasks", put(reorder_subtasks))
+        .route("/tasks/{id}/recurrence", put(set_task_recurrence).delete(stop_task_recurrence))
+        .route("/tasks/{id}/dependencies", post(add_dependency))
+        .route("/tasks/{id}/dependencies/{depends_on}", delete(remove_dependency))
+        .route("/tasks/{id}/time", get(list_time_entries).post(log_time))
+        .route("/tasks/{id}/time/start", post(start_session))
+        .route("/tasks/{id}/stages", get(list_stages).post(add_stage).put(reorder_stages))
+        .route("/tasks/{id}/stages/advance", post(advance_stage))
+        .route("/stages/{id}", patch(update_stage).delete(remove_stage))
+        .route("/time/running", get(running_session))
+        .route("/time/merge", post(merge_time_entries))
+        .route("/time/{id}", get(get_time_entry).patch(update_time_entry).delete(delete_time_entry))
+        .route("/time/{id}/stop", post(stop_session))
+        .route("/time/{id}/split", post(split_time_entry))
+        // ideas
+        .route("/ideas", post(add_idea))
+        .route("/ideas/search", get(search_ideas))
+        .route("/ideas/{id}", get(get_idea).patch(update_idea).delete(delete_idea))
+        .route("/ideas/{id}/notes", post(add_idea_note))
+        .route("/ideas/{id}/links", post(add_idea_link))
+        .route("/ideas/{id}/tasks", post(create_task_from_idea))
+        // docs
+        .route("/docs", post(add_doc))
+        .route("/docs/search", get(search_docs))
+        .route("/docs/{id}", get(get_doc).patch(update_doc_meta).delete(delete_doc))
+        .route("/docs/{id}/body", put(update_doc_body))
+        // workspaces and projects
+        .route("/workspaces", get(list_workspaces).post(create_workspace))
+        .route("/workspaces/{id}", patch(update_workspace))
+        .route("/projects", get(list_projects).post(create_project))
+        .route("/projects/{id}", patch(update_project).delete(delete_project))
+        .route("/projects/{id}/workspace", put(move_project))
+        .route("/projects/{id}/ideas", get(list_ideas))
+        .route("/projects/{id}/docs", get(list_docs))
+        .route("/projects/{id}/metrics", get(project_metrics))
+        .route("/projects/{id}/dependencies", get(dependency_graph))
+        .route("/metrics/estimates", get(estimate_accuracy))
+        .route("/reports", get(list_reports).post(save_report))
+        .route("/reports/preview", get(generate_report))
+        .route("/reports/{id}", get(get_report).delete(delete_report))
+        .route("/reports/{id}/export", get(export_report))
+        .route_layer(middleware::from_fn_with_state(api.clone(), require_token));
+
+    Router::new()
+        .route("/health", get(|| async { Json(json!({ "ok": true })) }))
+        .nest("/api/v1", v1)
+        .with_state(api)
+}
+
+async fn require_token(State(api): State<Api>, req: Request, next: Next) -> Response {
+    let presented = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+
+    match presented {
+        Some(t) if constant_time_eq(t.as_bytes(), api.token.as_bytes()) => next.run(req).await,
+        _ => (
+            StatusCode::UNAUTHORIZED,
+            Json(json!({ "code": "Unauthorized", "message": "missing or invalid bearer token" })),
+        )
+            .into_response(),
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        use crate::ErrorCode::*;
+        let status = match self.code() {
+            NotFound => StatusCode::NOT_FOUND,
+            Validation => StatusCode::BAD_REQUEST,
+            Conflict => StatusCode::CONFLICT,
+            ForeignKey => StatusCode::UNPROCESSABLE_ENTITY,
+            Io | Db | Other => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (status, Json(self)).into_response()
+    }
+}
+
+/// JSON body whose rejections come back as `Validation` errors.
+struct Body<T>(T);
+
+impl<S: Send + Sync, T: DeserializeOwned> FromRequest<S> for Body<T> {
+    type Rejection = AppError;
+
+    async fn from_request(req: Request, state: &S) -> std::result::Result<Self, Self::Rejection> {
+        let Json(v) = Json::<Value>::from_request(req, state)
+            .await
+            .map_err(|e| AppError::Validation { field: None, message: e.body_text() })?;
+        from_json(v).map(Body)
+    }
+}
+
+/// Query string whose rejections come back as `Validation` errors.
+struct Params<T>(T);
+
+impl<S: Send + Sync, T: DeserializeOwned> FromRequestParts<S> for Params<T> {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> std::result::Result<Self, Self::Rejection> {
+        let Query(v) = Query::<T>::from_request_parts(parts, state)
+            .await
+            .map_err(|e| AppError::Validation { field: None, message: e.body_text() })?;
+        Ok(Params(v))
+    }
+}
+
+fn from_json<T: DeserializeOwned>(v: Value) -> Result<T> {
+    serde_json::from_value(v).map_err(|e| AppError::Validation { field: None, message: e.to_string() })
+}
+
+/// Fold a path id into the body so it can feed the shared `*Input` types.
+fn with_key<T: DeserializeOwned>(mut body: Value, key: &str, id: String) -> Result<T> {
+    match &mut body {
+        Value::Object(m) => {
+            m.insert(key.to_string(), Value::String(id));
+        }
+        _ => return Err(AppError::validation("body", "Expected a JSON object")),
+    }
+    from_json(body)
+}
+
+type ApiResult<T> = std::result::Result<Json<T>, AppError>;
+type Created = std::result::Result<(StatusCode, Json<Value>), AppError>;
+
+fn created(id: String) -> Created {
+    Ok((StatusCode::CREATED, Json(json!({ "id": id }))))
+}
+
+#[derive(Deserialize)]
+struct SearchQuery {
+    q: String,
+    project_id: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ProjectsQuery {
+    workspace_id: Option<String>,
+    #[serde(default)]
+    include_archived: bool,
+}
+
+#[derive(Deserialize)]
+struct AccuracyQuery {
+    group: metrics::AccuracyGroup,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize)]
+struct StatusBody {
+    status: tasks::TaskStatus,
+    note: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct OrderBody {
+    ids: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct ExportQuery {
+    format: report_export::ReportFormat,
+}
+
+#[derive(Deserialize)]
+struct ScopeQuery {
+    #[serde(default)]
+    scope: recurrence::SeriesScope,
+}
+
+#[derive(Deserialize)]
+struct SplitBody {
+    at: DateTime<Utc>,
+}
+
+/// A task after a status change, with what the change warned about.
+#[derive(Serialize)]
+struct ChangedTask {
+    #[serde(flatten)]
+    task: tasks::TaskDetail,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    warning: Option<String>,
+}
+
+// ---------- Tasks ----------
+
+async fn list_tasks(State(api): State<Api>, Params(filter): Params<tasks::TaskFilter>) -> ApiResult<Vec<tasks::TaskRow>> {
+    Ok(Json(tasks::list_tasks(&api.pool(), &filter).await?))
+}
+
+async fn add_task(State(api): State<Api>, Body(input): Body<tasks::TaskInput>) -> Created {
+    created(tasks::add_task(&api.pool(), input).await?)
+}
+
+async fn get_task(State(api): State<Api>, UrlPath(id): UrlPath<String>) -> ApiResult<tasks::TaskDetail> {
+    Ok(Json(tasks::get_task(&api.pool(), &id).await?))
+}
+
+/// `?scope=following` also edits the later occurrences of a recurring task.
+async fn update_task(
+    State(api): State<Api>,
+    UrlPath(id): UrlPath<String>,
+    Params(q): Params<ScopeQuery>,
+    Body(body): Body<Value>,
+) -> ApiResult<ChangedTask> {
+    let change = recurrence::update_series_task(&api.pool(), with_key(body, "id", id.clone())?, q.scope).await?;
+    Ok(Json(ChangedTask { task: tasks::get_task(&api.pool(), &id).await?, warning: change.warning }))
+}
+
+async fn set_task_status(State(api): State<Api>, UrlPath(id): UrlPath<String>, Body(b): Body<StatusBody>) -> ApiResult<ChangedTask> {
+    let change = tasks::set_task_status(&api.pool(), &id, b.status, b.note).await?;
+    Ok(Json(ChangedTask { task: tasks::get_task(&api.pool(), &id).await?, warning: change.warning }))
+}
+
+async fn add_reason(State(api): State<Api>, UrlPath(id): UrlPath<String>, Body(body): Body<Value>) -> std::result::Result<StatusCode, AppError> {
+    tasks::add_reason(&api.pool(), with_key(body, "task_id", id)?).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn list_status_reasons(State(api): State<Api>, UrlPath(id): UrlPath<String>) -> ApiResult<Vec<tasks::StatusReason>> {
+    Ok(Json(tasks::list_status_reasons(&api.pool(), &id).await?))
+}
+
+async fn move_task(State(api): State<Api>, UrlPath(id): UrlPath<String>, Body(body): Body<Value>) -> ApiResult<tasks::TaskDetail> {
+    tasks::move_task(&api.pool(), with_key(body, "id", id.clone())?).await?;
+    Ok(Json(tasks::get_task(&api.pool(), &id).await?))
+}
+
+async fn set_task_parent(State(api): State<Api>, UrlPath(id): UrlPath<String>, Body(body): Body<Value>) -> ApiResult<tasks::TaskDetail> {
+    subtasks::set_task_parent(&api.pool(), with_key(body, "id", id.clone())?).await?;
+    Ok(Json(tasks::get_task(&api.pool(), &id).await?))
+}
+
+async fn reorder_subtasks(State(api): State<Api>, UrlPath(id): UrlPath<String>, Body(b): Body<OrderBody>) -> ApiResult<tasks::TaskDetail> {
+    subtasks::reorder_subtasks(&api.pool(), &id, &b.ids).await?;
+    Ok(Json(tasks::get_task(&api.pool(), &id).await?))
+}
+
+async fn set_task_recurrence(State(api): State<Api>, UrlPath(id): UrlPath<String>, Body(input): Body<recurrence::RecurrenceInput>) -> ApiResult<tasks::TaskDetail> {
+    recurrence::set_task_recurrence(&api.pool(), &id, Some(input)).await?;
+    Ok(Json(tasks::get_task(&api.pool(), &id).await?))
+}
+
+async fn stop_task_recurrence(State(api): State<Api>, UrlPath(id): UrlPath<String>) -> ApiResult<tasks::TaskDetail> {
+    recurrence::set_task_recurrence(&api.pool(), &id, None).await?;
+    Ok(Json(tasks::get_task(&api.pool(), &id).await?))
+}
+
+async fn add_dependency(State(api): State<Api>, UrlPath(id): UrlPath<String>, Body(body): Body<Value>) -> ApiResult<tasks::TaskDetail> {
+    dependencies::add_dependency(&api.pool(), with_key(body, "task_id", id.clone())?).await?;
+    Ok(Json(tasks::get_task(&api.pool(), &id).await?))
+}
+
+async fn remove_dependency(
+    State(api): State<Api>,
+    UrlPath((id, depends_on)): UrlPath<(String, String)>,
+) -> ApiResult<tasks::TaskDetail> {
+    dependencies::remove_dependency(&api.pool(), dependencies::DependencyInput { task_id: id.clone(), depends_on }).await?;
+    Ok(Json(tasks::get_task(&api.pool(), &id).await?))
+}
+
+async fn delete_task(State(api): State<Api>, UrlPath(id): UrlPath<String>) -> std::result::Result<StatusCode, AppError> {
+    tasks::delete_task(&api.pool(), &id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ---------- Stages ----------
+
+async fn list_stages(State(api): State<Api>, UrlPath(id): UrlPath<String>) -> ApiResult<Vec<stages::Stage>> {
+    Ok(Json(stages::list_stages(&api.pool(), &id).await?))
+}
+
+async fn add_stage(State(api): State<Api>, UrlPath(id): UrlPath<String>, Body(body): Body<Value>) -> Created {
+    created(stages::add_stage(&api.pool(), with_key(body, "task_id", id)?).await?)
+}
+
+async fn reorder_stages(State(api): State<Api>, UrlPath(id): UrlPath<String>, Body(b): Body<OrderBody>) -> ApiResult<Vec<stages::Stage>> {
+    stages::reorder_stages(&api.pool(), &id, &b.ids).await?;
+    Ok(Json(stages::list_stages(&api.pool(), &id).await?))
+}
+
+async fn advance_stage(State(api): State<Api>, UrlPath(id): UrlPath<String>) -> ApiResult<stages::StageProgress> {
+    Ok(Json(stages::advance_stage(&api.pool(), &id).await?))
+}
+
+async fn update_stage(State(api): State<Api>, UrlPath(id): UrlPath<String>, Body(body): Body<Value>) -> std::result::Result<StatusCode, AppError> {
+    stages::update_stage(&api.pool(), with_key(body, "id", id)?).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn remove_stage(State(api): State<Api>, UrlPath(id): UrlPath<String>) -> std::result::Result<StatusCode, AppError> {
+    stages::remove_stage(&api.pool(), &id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ---------- Time ----------
+
+async fn list_time_entries(State(api): State<Api>, UrlPath(id): UrlPath<String>) -> ApiResult<Vec<time::TimeEntry>> {
+    Ok(Json(time::list_time_entries(&api.pool(), &id).await?))
+}
+
+async fn log_time(State(api): State<Api>, UrlPath(id): UrlPath<String>, Body(body): Body<Value>) -> Created {
+    created(time::log_time(&api.pool(), with_key(body, "task_id", id)?).await?)
+}
+
+async fn start_session(State(api): State<Api>, UrlPath(id): UrlPath<String>, Body(body): Body<Value>) -> ApiResult<time::TimeEntry> {
+    Ok(Json(time::start_session(&api.pool(), with_key(body, "task_id", id)?).await?))
+}
+
+async fn running_session(State(api): State<Api>) -> ApiResult<Option<time::TimeEntry>> {
+    Ok(Json(time::running_session(&api.pool()).await?))
+}
+
+async fn get_time_entry(State(api): State<Api>, UrlPath(id): UrlPath<String>) -> ApiResult<time::TimeEntry> {
+    Ok(Json(time::get_time_entry(&api.pool(), &id).await?))
+}
+
+async fn update_time_entry(State(api): State<Api>, UrlPath(id): UrlPath<String>, Body(body): Body<Value>) -> ApiResult<time::TimeEntry> {
+    Ok(Json(time::update_time_entry(&api.pool(), with_key(body, "id", id)?).await?))
+}
+
+async fn stop_session(State(api): State<Api>, UrlPath(id): UrlPath<String>) -> ApiResult<time::TimeEntry> {
+    Ok(Json(time::stop_session(&api.pool(), &id).await?))
+}
+
+async fn split_time_entry(State(api): State<Api>, UrlPath(id): UrlPath<String>, Body(b): Body<SplitBody>) -> Created {
+    created(time::split_time_entry(&api.pool(), &id, b.at).await?)
+}
+
+async fn merge_time_entries(State(api): State<Api>, Body(b): Body<OrderBody>) -> ApiResult<time::TimeEntry> {
+    let id = time::merge_time_entries(&api.pool(), &b.ids).await?;
+    Ok(Json(time::get_time_entry(&api.pool(), &id).await?))
+}
+
+async fn delete_time_entry(State(api): State<Api>, UrlPath(id): UrlPath<String>) -> std::result::Result<StatusCode, AppError> {
+    time::delete_time_entry(&api.pool(), &id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ---------- Ideas ----------
+
+async fn list_ideas(State(api): State<Api>, UrlPath(project_id): UrlPath<String>) -> ApiResult<Vec<ideas::IdeaRow>> {
+    Ok(Json(ideas::list_ideas(&api.pool(), &project_id).await?))
+}
+
+async fn add_idea(State(api): State<Api>, Body(input): Body<ideas::IdeaInput>) -> Created {
+    created(ideas::add_idea(&api.pool(), input).await?)
+}
+
+async fn get_idea(State(api): State<Api>, UrlPath(id): UrlPath<String>) -> ApiResult<ideas::IdeaDetail> {
+    Ok(Json(ideas::get_idea(&api.pool(), &id).await?))
+}
+
+async fn update_idea(State(api): State<Api>, UrlPath(id): UrlPath<String>, Body(body): Body<Value>) -> ApiResult<ideas::IdeaDetail> {
+    ideas::update_idea(&api.pool(), with_key(body, "id", id.clone())?).await?;
+    Ok(Json(ideas::get_idea(&api.pool(), &id).await?))
+}
+
+async fn add_idea_note(State(api): State<Api>, UrlPath(id): UrlPath<String>, Body(body): Body<Value>) -> Created {
+    created(ideas::add_idea_note(&api.pool(), with_key(body, "idea_id", id)?).await?)
+}
+
+async fn add_idea_link(State(api): State<Api>, UrlPath(id): UrlPath<String>, Body(body): Body<Value>) -> Created {
+    created(ideas::add_idea_link(&api.pool(), with_key(body, "idea_id", id)?).await?)
+}
+
+async fn create_task_from_idea(State(api): State<Api>, UrlPath(id): UrlPath<String>, Body(body): Body<Value>) -> Created {
+    created(ideas::create_task_from_idea(&api.pool(), with_key(body, "idea_id", id)?).await?)
+}
+
+async fn search_ideas(State(api): State<Api>, Params(q): Params<SearchQuery>) -> ApiResult<Vec<(String, String)>> {
+    Ok(Json(ideas::search_ideas(&api.pool(), &q.q, q.project_id.as_deref()).await?))
+}
+
+async fn delete_idea(State(api): State<Api>, UrlPath(id): UrlPath<String>) -> std::result::Result<StatusCode, AppError> {
+    ideas::delete_idea(&api.pool(), &id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ---------- Docs ----------
+
+async fn list_docs(State(api): State<Api>, UrlPath(project_id): UrlPath<String>) -> ApiResult<Vec<docs::DocRow>> {
+    Ok(Json(docs::list_docs(&api.pool(), &project_id).await?))
+}
+
+async fn add_doc(State(api): State<Api>, Body(input): Body<docs::DocInput>) -> Created {
+    created(docs::add_doc(&api.pool(), input).await?)
+}
+
+async fn get_doc(State(api): State<Api>, UrlPath(id): UrlPath<String>) -> ApiResult<docs::DocDetail> {
+    Ok(Json(docs::get_doc(&api.pool(), &id).await?))
+}
+
+async fn update_doc_body(State(api): State<Api>, UrlPath(id): UrlPath<String>, Body(body): Body<Value>) -> ApiResult<docs::DocDetail> {
+    docs::update_doc_body(&api.pool(), with_key(body, "id", id.clone())?).await?;
+    Ok(Json(docs::get_doc(&api.pool(), &id).await?))
+}
+
+async fn update_doc_meta(State(api): State<Api>, UrlPath(id): UrlPath<String>, Body(body): Body<Value>) -> ApiResult<docs::DocDetail> {
+    docs::update_doc_meta(&api.pool(), with_key(body, "id", id.clone())?).await?;
+    Ok(Json(docs::get_doc(&api.pool(), &id).await?))
+}
+
+async fn search_docs(State(api): State<Api>, Params(q): Params<SearchQuery>) -> ApiResult<Vec<(String, String)>> {
+    Ok(Json(docs::search_docs(&api.pool(), &q.q, q.project_id.as_deref()).await?))
+}
+
+async fn delete_doc(State(api): State<Api>, UrlPath(id): UrlPath<String>) -> std::result::Result<StatusCode, AppError> {
+    docs::delete_doc(&api.pool(), &id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ---------- Workspaces, projects, metrics ----------
+
+async fn list_workspaces(State(api): State<Api>) -> ApiResult<Vec<projects::WorkspaceRow>> {
+    Ok(Json(projects::list_workspaces(&api.pool()).await?))
+}
+
+async fn create_workspace(State(api): State<Api>, Body(input): Body<projects::CreateWorkspaceInput>) -> Created {
+    created(projects::create_workspace(&api.pool(), input).await?)
+}
+
+async fn list_projects(State(api): State<Api>, Params(q): Params<ProjectsQuery>) -> ApiResult<Vec<projects::ProjectRow>> {
+    Ok(Json(projects::list_projects(&api.pool(), q.workspace_id.as_deref(), q.include_archived).await?))
+}
+
+async fn create_project(State(api): State<Api>, Body(input): Body<projects::CreateProjectInput>) -> Created {
+    created(projects::create_project(&api.pool(), input).await?)
+}
+
+async fn update_workspace(State(api): State<Api>, UrlPath(id): UrlPath<String>, Body(body): Body<Value>) -> std::result::Result<StatusCode, AppError> {
+    projects::update_workspace(&api.pool(), with_key(body, "id", id)?).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn update_project(State(api): State<Api>, UrlPath(id): UrlPath<String>, Body(body): Body<Value>) -> std::result::Result<StatusCode, AppError> {
+    projects::update_project(&api.pool(), with_key(body, "id", id)?).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn move_project(State(api): State<Api>, UrlPath(id): UrlPath<String>, Body(body): Body<Value>) -> std::result::Result<StatusCode, AppError> {
+    projects::move_project(&api.pool(), with_key(body, "id", id)?).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn delete_project(State(api): State<Api>, UrlPath(id): UrlPath<String>) -> std::result::Result<StatusCode, AppError> {
+    projects::delete_project(&api.pool(), &id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn project_metrics(State(api): State<Api>, UrlPath(id): UrlPath<String>) -> ApiResult<Value> {
+    let pool = &api.pool();
+    Ok(Json(json!({
+        "tasks": metrics::count_tasks_by_project(pool, &id).await?,
+        "ideas": metrics::count_ideas_by_project(pool, &id).await?,
+        "docs": metrics::count_docs_by_project(pool, &id).await?,
+    })))
+}
+
+async fn dependency_graph(State(api): State<Api>, UrlPath(id): UrlPath<String>) -> ApiResult<dependencies::DependencyGraph> {
+    Ok(Json(dependencies::dependency_graph(&api.pool(), &id).await?))
+}
+
+async fn estimate_accuracy(State(api): State<Api>, Params(q): Params<AccuracyQuery>) -> ApiResult<Vec<metrics::EstimateAccuracy>> {
+    Ok(Json(metrics::estimate_accuracy(&api.pool(), q.group, q.from, q.to).await?))
+}
+
+// ---------- Reports ----------
+
+async fn generate_report(State(api): State<Api>, Params(q): Params<reports::ReportInput>) -> ApiResult<reports::Report> {
+    Ok(Json(reports::generate_report(&api.pool(), &q).await?))
+}
+
+async fn save_report(State(api): State<Api>, Body(input): Body<reports::ReportInput>) -> std::result::Result<(StatusCode, Json<reports::Report>), AppError> {
+    Ok((StatusCode::CREATED, Json(reports::save_report(&api.pool(), &input).await?)))
+}
+
+async fn list_reports(State(api): State<Api>) -> ApiResult<Vec<reports::ReportSummary>> {
+    Ok(Json(reports::list_reports(&api.pool()).await?))
+}
+
+async fn get_report(State(api): State<Api>, UrlPath(id): UrlPath<String>) -> ApiResult<reports::Report> {
+    Ok(Json(reports::get_report(&api.pool(), &id).await?))
+}
+
+async fn delete_report(State(api): State<Api>, UrlPath(id): UrlPath<String>) -> std::result::Result<StatusCode, AppError> {
+    reports::delete_report(&api.pool(), &id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// The saved report as a download in the requested format.
+async fn export_report(State(api): State<Api>, UrlPath(id): UrlPath<String>, Params(q): Params<ExportQuery>) -> std::result::Result<Response, AppError> {
+    let report = reports::get_report(&api.pool(), &id).await?;
+    let name = format!("report-{}-{}.{}", report.from, report.to, q.format.extension());
+    Ok((
+        [
+            (header::CONTENT_TYPE, q.format.content_type().to_string()),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{name}\"")),
+        ],
+        report_export::render(&report, q.format),
+    )
+        .into_response())
+}
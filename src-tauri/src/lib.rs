@@ -1,33 +1,59 @@
-use thiserror::Error;
-
 use sqlx::SqlitePool;
-use tauri::Manager;
+use tauri::{Emitter, Manager};
 use std::path::PathBuf;
-use directories::ProjectDirs;
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
 
- // <-- needed for .manage()
+use services::reminders;
+use services::timer::{self, Timer, TimerEvent};
 
 pub mod commands{
+    pub mod api;
+    pub mod backup;
     pub mod tasks;
+    pub mod history;
     pub mod ideas;
+    pub mod journal;
     pub mod docs;
     pub mod projects;
     pub mod metrics;
+    pub mod reminders;
+    pub mod reports;
+    pub mod trash;
+    pub mod stages;
+    pub mod time;
+    pub mod timer;
+    pub mod vault;
 }
 pub mod db;
+pub mod error;
+pub mod events;
+pub mod http;
+pub mod services;
+
+pub use error::{AppError, ErrorCode, Result};
 
-#[derive(Clone)]
 pub struct AppState {
-    pub pool: SqlitePool,
+    pool: db::SharedPool, // swapped by restore_backup
     pub app_dir: PathBuf,
+    pub db_path: PathBuf,
 }
 
-#[derive(Debug, Error)]
-pub enum AppError {
-    #[error("db: {0}")] Db(#[from] sqlx::Error),
-    #[error("{0}")] Other(String),
+impl AppState {
+    pub fn new(pool: SqlitePool, app_dir: PathBuf, db_path: PathBuf) -> Self {
+        AppState { pool: db::SharedPool::new(pool), app_dir, db_path }
+    }
+
+    /// Handle to the live database. Cheap to clone; don't hold it across a restore.
+    pub fn pool(&self) -> SqlitePool {
+        self.pool.get()
+    }
+
+    /// The swappable handle itself, for holders that outlive a restore.
+    pub fn shared_pool(&self) -> db::SharedPool {
+        self.pool.clone()
+    }
 }
-pub type Result<T> = std::result::Result<T, AppError>;
 
 #[tauri::command]
 fn invoke_task(task: &str) -> String {
@@ -38,16 +64,103 @@ fn invoke_task(task: &str) -> String {
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_log::Builder::new().level(log::LevelFilter::Info).build())
         .setup(|app| {
-             let proj = ProjectDirs::from("com", "DeepFocus", "DeepFocus")
-                .expect("could not determine project dirs");
-            let app_dir = proj.data_dir().to_path_buf();
-            std::fs::create_dir_all(&app_dir).ok();
+            let app_dir = db::app_dir();
+            let db_path = db::sqlite_path();
 
             // Initialize sqlx pool at startup and store in global state
             tauri::async_runtime::block_on(async {
                 let pool = db::init_pool().await; // <- db::init_pool() returns SqlitePool
-                app.manage(AppState {pool, app_dir});
+                app.manage(AppState::new(pool, app_dir, db_path));
+            });
+
+            // Fan service-level changes out to every window
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let mut rx = events::subscribe();
+                loop {
+                    let change = match rx.recv().await {
+                        Ok(c) => c,
+                        // fell behind: tell views to refetch everything
+                        Err(RecvError::Lagged(_)) => events::EntityChanged::vault(),
+                        Err(RecvError::Closed) => break,
+                    };
+                    handle.emit(events::ENTITY_CHANGED, change).ok();
+                }
+            });
+
+            // Local REST API is opt-in; a busy port must not stop the app
+            app.manage(http::ApiServer::default());
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let state = handle.state::<AppState>();
+                let server = handle.state::<http::ApiServer>();
+                if let Err(e) = http::start_if_enabled(&server, state.shared_pool(), &state.app_dir).await {
+                    log::warn!("local api not started: {e}");
+                }
+            });
+
+            // Focus timer runs here, not in the webview; pick up where a
+            // previous run left off, then tick every second
+            app.manage(Timer::default());
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let state = handle.state::<AppState>();
+                let timer = handle.state::<Timer>();
+                if let Err(e) = timer.load(&state.pool()).await {
+                    log::warn!("timer state not recovered: {e}");
+                }
+                let mut every = tokio::time::interval(Duration::from_secs(1));
+                loop {
+                    every.tick().await;
+                    if let Err(e) = timer.tick(&state.pool(), chrono::Utc::now()).await {
+                        log::error!("timer tick failed: {e}");
+                    }
+                }
+            });
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let mut rx = handle.state::<Timer>().subscribe();
+                loop {
+                    match rx.recv().await {
+                        Ok(TimerEvent::State(s)) => handle.emit(timer::TIMER_EVENT, s).ok(),
+                        Ok(TimerEvent::PhaseEnded(p)) => handle.emit(timer::PHASE_EVENT, p).ok(),
+                        Err(RecvError::Lagged(_)) => continue,
+                        Err(RecvError::Closed) => break,
+                    };
+                }
+            });
+
+            // Due-date reminders; the first pass catches up on any missed
+            // while the app was closed
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let mut every = tokio::time::interval(reminders::CHECK_EVERY);
+                loop {
+                    every.tick().await;
+                    let state = handle.state::<AppState>();
+                    match reminders::run_due(&state.pool(), chrono::Utc::now()).await {
+                        Ok(fired) => {
+                            for n in fired {
+                                handle.emit(reminders::NOTIFICATION_EVENT, n).ok();
+                            }
+                        }
+                        Err(e) => log::error!("reminder check failed: {e}"),
+                    }
+                }
+            });
+
+            // Scheduled snapshots; checks often, backs up per the configured interval
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    let state = handle.state::<AppState>();
+                    if let Err(e) = services::backup::backup_if_due(&state.pool(), &state.app_dir).await {
+                        log::error!("scheduled backup failed: {e}");
+                    }
+                    tokio::time::sleep(Duration::from_secs(15 * 60)).await;
+                }
             });
             Ok(())
         })
@@ -59,7 +172,53 @@ pub fn run() {
             commands::tasks::get_task,
             commands::tasks::set_task_status,
             commands::tasks::add_reason,
+            commands::tasks::list_status_reasons,
+            commands::tasks::get_status_rules,
+            commands::tasks::set_status_rules,
             commands::tasks::update_task,
+            commands::tasks::move_task,
+            commands::tasks::delete_task,
+            commands::tasks::set_task_parent,
+            commands::tasks::reorder_subtasks,
+            commands::tasks::set_task_recurrence,
+            commands::tasks::update_series_task,
+            commands::tasks::preview_recurrence,
+            commands::tasks::add_dependency,
+            commands::tasks::remove_dependency,
+            commands::tasks::dependency_graph,
+            // time
+            commands::time::list_time_entries,
+            commands::time::running_session,
+            commands::time::log_time,
+            commands::time::start_session,
+            commands::time::stop_session,
+            commands::time::update_time_entry,
+            commands::time::split_time_entry,
+            commands::time::merge_time_entries,
+            commands::time::delete_time_entry,
+            // focus timer
+            commands::timer::get_timer,
+            commands::timer::start_timer,
+            commands::timer::pause_timer,
+            commands::timer::resume_timer,
+            commands::timer::skip_timer,
+            commands::timer::stop_timer,
+            commands::timer::get_timer_settings,
+            commands::timer::set_timer_settings,
+            // reminders and notifications
+            commands::reminders::list_notifications,
+            commands::reminders::snooze_notification,
+            commands::reminders::dismiss_notification,
+            commands::reminders::dismiss_all_notifications,
+            commands::reminders::get_reminder_settings,
+            commands::reminders::set_reminder_settings,
+            // stages
+            commands::stages::list_stages,
+            commands::stages::add_stage,
+            commands::stages::update_stage,
+            commands::stages::reorder_stages,
+            commands::stages::remove_stage,
+            commands::stages::advance_stage,
              // IDEAS
             commands::ideas::add_idea,
             commands::ideas::list_ideas,
@@ -71,6 +230,7 @@ pub fn run() {
             commands::ideas::link_idea_doc,
             commands::ideas::search_ideas,
             commands::ideas::create_task_from_idea,
+            commands::ideas::delete_idea,
             // DOCS
             commands::docs::add_doc,
             commands::docs::list_docs,
@@ -79,15 +239,59 @@ pub fn run() {
             commands::docs::update_doc_meta,
             commands::docs::search_docs,
             commands::docs::save_doc_attachment,
+            commands::docs::delete_doc,
             //project
             commands::projects::list_workspaces,
             commands::projects::create_workspace,
             commands::projects::list_projects,
             commands::projects::create_project,
+            commands::projects::update_workspace,
+            commands::projects::update_project,
+            commands::projects::archive_project,
+            commands::projects::unarchive_project,
+            commands::projects::move_project,
+            commands::projects::delete_project,
             //metrics 
             commands::metrics::count_tasks_by_project,
             commands::metrics::count_ideas_by_project,
             commands::metrics::count_docs_by_project,
+            commands::metrics::estimate_accuracy,
+            // reports
+            commands::reports::generate_report,
+            commands::reports::save_report,
+            commands::reports::list_reports,
+            commands::reports::get_report,
+            commands::reports::delete_report,
+            commands::reports::render_report,
+            commands::reports::export_report,
+            commands::reports::report_to_doc,
+            // local api
+            commands::api::get_api_settings,
+            commands::api::set_api_settings,
+            commands::api::rotate_api_token,
+            // backups
+            commands::backup::list_backups,
+            commands::backup::create_backup,
+            commands::backup::verify_backup,
+            commands::backup::restore_backup,
+            commands::backup::get_backup_settings,
+            commands::backup::set_backup_settings,
+            // export / import
+            commands::vault::export_vault,
+            commands::vault::inspect_vault_archive,
+            commands::vault::import_vault,
+            // undo / redo
+            commands::journal::undo,
+            commands::journal::redo,
+            commands::journal::get_undo_state,
+            commands::history::get_history,
+            // trash
+            commands::trash::list_trash,
+            commands::trash::restore_from_trash,
+            commands::trash::purge,
+            commands::trash::empty_trash,
+            commands::trash::preview_delete,
+            commands::trash::delete_permanently,
               // <-- save file to disk + db row
  // <-- idea → task
         ])
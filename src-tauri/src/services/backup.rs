@@ -0,0 +1,322 @@
+// src/services/backup.rs
+//! Point-in-time snapshots of the database under `app_dir/backups`.
+//!
+//! Snapshots are taken with `VACUUM INTO`, which produces a consistent,
+//! self-contained file even while the app keeps writing in WAL mode.
+//! Scheduled snapshots are untagged and pruned to the configured retention;
+//! manual (`-manual`) and `-pre-restore` ones are left for the user to delete.
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::{Connection, SqliteConnection, SqlitePool};
+
+use crate::services::settings;
+use crate::db::{self, SharedPool};
+use crate::{events, AppError, Result};
+
+pub const SETTINGS_KEY: &str = "backup";
+const PREFIX: &str = "deepfocus-";
+const EXT: &str = ".db";
+
+// ---------- Types ----------
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct BackupSettings {
+    pub enabled: bool,
+    pub interval_hours: u32,
+    pub keep: usize, // newest N scheduled snapshots survive pruning
+}
+impl Default for BackupSettings {
+    fn default() -> Self {
+        BackupSettings { enabled: true, interval_hours: 24, keep: 7 }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct BackupInfo {
+    pub name: String,
+    pub path: String,
+    pub size_bytes: u64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BackupCheck {
+    pub name: String,
+    pub ok: bool,
+    pub problems: Vec<String>, // integrity_check output when not ok
+}
+
+// ---------- Files ----------
+
+pub fn backups_dir(app_dir: &Path) -> PathBuf {
+    app_dir.join("backups")
+}
+
+/// Newest first.
+pub fn list_backups(app_dir: &Path) -> Result<Vec<BackupInfo>> {
+    let dir = backups_dir(app_dir);
+    let entries = match fs::read_dir(&dir) {
+        Ok(e) => e,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut out = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if !(name.starts_with(PREFIX) && name.ends_with(EXT)) {
+            continue;
+        }
+        let meta = entry.metadata()?;
+        out.push(BackupInfo {
+            path: entry.path().to_string_lossy().into_owned(),
+            size_bytes: meta.len(),
+            created_at: meta.modified().map(DateTime::<Utc>::from).unwrap_or_else(|_| Utc::now()),
+            name,
+        });
+    }
+    // names embed a UTC timestamp, so they sort chronologically
+    out.sort_by(|a, b| b.name.cmp(&a.name));
+    Ok(out)
+}
+
+/// Map a backup name from the UI to a path, refusing anything outside `backups/`.
+fn backup_path(app_dir: &Path, name: &str) -> Result<PathBuf> {
+    if !name.starts_with(PREFIX) || !name.ends_with(EXT) || name.contains(['/', '\\']) {
+        return Err(AppError::validation("name", "Invalid backup name"));
+    }
+    let path = backups_dir(app_dir).join(name);
+    if !path.is_file() {
+        return Err(AppError::not_found("backup", name));
+    }
+    Ok(path)
+}
+
+// ---------- Snapshots ----------
+
+async fn snapshot(pool: &SqlitePool, app_dir: &Path, tag: &str) -> Result<BackupInfo> {
+    let dir = backups_dir(app_dir);
+    fs::create_dir_all(&dir)?;
+
+    let name = format!("{PREFIX}{}{tag}{EXT}", Utc::now().format("%Y%m%d-%H%M%S-%3f"));
+    let path = dir.join(&name);
+
+    sqlx::query("VACUUM INTO ?1")
+        .bind(path.to_string_lossy().as_ref())
+        .execute(pool)
+        .await?;
+
+    let meta = fs::metadata(&path)?;
+    Ok(BackupInfo {
+        name,
+        path: path.to_string_lossy().into_owned(),
+        size_bytes: meta.len(),
+        created_at: Utc::now(),
+    })
+}
+
+/// Take a manual snapshot now; retention doesn't touch it.
+pub async fn create_backup(pool: &SqlitePool, app_dir: &Path) -> Result<BackupInfo> {
+    snapshot(pool, app_dir, "-manual").await
+}
+
+/// Take a scheduled snapshot, then prune scheduled ones down to the
+/// configured retention.
+pub async fn scheduled_backup(pool: &SqlitePool, app_dir: &Path) -> Result<BackupInfo> {
+    let cfg: BackupSettings = settings::get_or_default(pool, SETTINGS_KEY).await?;
+    let info = snapshot(pool, app_dir, "").await?;
+    prune(app_dir, cfg.keep)?;
+    Ok(info)
+}
+
+/// Scheduler tick: snapshot if backups are enabled and the newest scheduled
+/// one is older than the configured interval. Manual and pre-restore
+/// snapshots aren't part of the rotation, so they don't count.
+pub async fn backup_if_due(pool: &SqlitePool, app_dir: &Path) -> Result<Option<BackupInfo>> {
+    let cfg: BackupSettings = settings::get_or_default(pool, SETTINGS_KEY).await?;
+    if !cfg.enabled {
+        return Ok(None);
+    }
+    let due = match list_backups(app_dir)?.iter().find(|b| is_scheduled(&b.name)) {
+        Some(newest) => Utc::now() - newest.created_at >= Duration::hours(cfg.interval_hours.into()),
+        None => true,
+    };
+    if !due {
+        return Ok(None);
+    }
+    scheduled_backup(pool, app_dir).await.map(Some)
+}
+
+/// Untagged names are the scheduler's: `deepfocus-<timestamp>.db`.
+fn is_scheduled(name: &str) -> bool {
+    name.strip_prefix(PREFIX)
+        .and_then(|n| n.strip_suffix(EXT))
+        .is_some_and(|stamp| !stamp.contains(|c: char| c.is_ascii_alphabetic()))
+}
+
+/// Delete the oldest scheduled backups beyond `keep`. Returns how many were removed.
+pub fn prune(app_dir: &Path, keep: usize) -> Result<usize> {
+    let keep = keep.max(1); // never prune the only copy
+    let old = list_backups(app_dir)?.into_iter().filter(|b| is_scheduled(&b.name)).skip(keep);
+    let mut removed = 0;
+    for b in old {
+        fs::remove_file(&b.path)?;
+        removed += 1;
+    }
+    Ok(removed)
+}
+
+pub async fn get_settings(pool: &SqlitePool) -> Result<BackupSettings> {
+    settings::get_or_default(pool, SETTINGS_KEY).await
+}
+
+pub async fn set_settings(pool: &SqlitePool, input: BackupSettings) -> Result<()> {
+    if input.interval_hours == 0 {
+        return Err(AppError::validation("interval_hours", "Backup interval must be at least one hour"));
+    }
+    if input.keep == 0 {
+        return Err(AppError::validation("keep", "Keep at least one backup"));
+    }
+    settings::put(pool, SETTINGS_KEY, &input).await
+}
+
+// ---------- Verify / restore ----------
+
+/// `PRAGMA integrity_check` output for the database file at `path`, opened read-only.
+async fn integrity_check(path: &Path) -> Result<Vec<String>> {
+    let opts = SqliteConnectOptions::from_str("sqlite:")?
+        .filename(path)
+        .read_only(true);
+
+    let mut conn = SqliteConnection::connect_with(&opts).await?;
+    let rows: Vec<String> = sqlx::query_scalar("PRAGMA integrity_check")
+        .fetch_all(&mut conn)
+        .await?;
+    conn.close().await?;
+    Ok(rows)
+}
+
+/// Run `PRAGMA integrity_check` against a backup opened read-only.
+pub async fn verify_backup(app_dir: &Path, name: &str) -> Result<BackupCheck> {
+    let path = backup_path(app_dir, name)?;
+    let rows = integrity_check(&path).await?;
+
+    let ok = rows.len() == 1 && rows[0] == "ok";
+    Ok(BackupCheck {
+        name: name.to_string(),
+        ok,
+        problems: if ok { Vec::new() } else { rows },
+    })
+}
+
+/// `path` with `suffix` appended to the file name, e.g. `deepfocus.db-wal`.
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut s = path.as_os_str().to_owned();
+    s.push(suffix);
+    PathBuf::from(s)
+}
+
+/// Remove a database file and its WAL/shared-memory companions, if present.
+fn remove_db_files(path: &Path) -> Result<()> {
+    for p in [path.to_path_buf(), sibling(path, "-wal"), sibling(path, "-shm")] {
+        match fs::remove_file(&p) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Replace the live database with a verified backup, swap the new pool into
+/// `shared` and return it.
+///
+/// The current database is snapshotted first (tagged `-pre-restore`) so a
+/// restore can itself be undone. The backup is copied and
+/// checked before the old pool is closed; if swapping the files or opening
+/// the result fails after that, the original database is put back and
+/// reopened (or reopened where it was moved aside, if it can't be put back),
+/// and the swap's error is returned with any of those failures attached.
+pub async fn restore_backup(shared: &SharedPool, db_path: &Path, app_dir: &Path, name: &str) -> Result<SqlitePool> {
+    let check = verify_backup(app_dir, name).await?;
+    if !check.ok {
+        return Err(AppError::validation(
+            "name",
+            format!("Backup failed integrity check: {}", check.problems.join("; ")),
+        ));
+    }
+    let src = backup_path(app_dir, name)?;
+    let pool = shared.get();
+    snapshot(&pool, app_dir, "-pre-restore").await?;
+
+    // stage next to the target so the final renames stay on one filesystem
+    let staged = sibling(db_path, ".restoring");
+    fs::copy(&src, &staged)?;
+    match integrity_check(&staged).await {
+        Ok(rows) if rows.len() == 1 && rows[0] == "ok" => {}
+        Ok(rows) => {
+            fs::remove_file(&staged).ok();
+            return Err(AppError::Other(format!("Copied backup failed integrity check: {}", rows.join("; "))));
+        }
+        Err(e) => {
+            fs::remove_file(&staged).ok();
+            return Err(e);
+        }
+    }
+
+    // closing waits for in-flight queries and checkpoints the WAL into the file
+    pool.close().await;
+    let aside = sibling(db_path, ".replaced");
+    let mut moved_aside = false;
+    let swapped = async {
+        for suffix in ["-wal", "-shm"] {
+            match fs::remove_file(sibling(db_path, suffix)) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+                _ => {}
+            }
+        }
+        fs::rename(db_path, &aside)?;
+        moved_aside = true;
+        fs::rename(&staged, db_path)?;
+        // migrations run on open, so an older backup comes back on today's schema
+        db::open_pool(db_path).await
+    }
+    .await;
+
+    match swapped {
+        Ok(pool) => {
+            fs::remove_file(&aside).ok();
+            shared.set(pool.clone());
+            events::publish_vault_changed();
+            Ok(pool)
+        }
+        Err(e) => {
+            // best effort from here: `e` is what went wrong, anything failing
+            // while putting things back is only added to it
+            let mut also = Vec::new();
+            if moved_aside {
+                let back = remove_db_files(db_path).and_then(|_| Ok(fs::rename(&aside, db_path)?));
+                if let Err(r) = back {
+                    also.push(format!("putting the original back failed: {r}"));
+                }
+            }
+            fs::remove_file(&staged).ok();
+            // if the original is still aside, run on it there rather than on whatever is left at `db_path`
+            let live = if moved_aside && aside.exists() { aside.as_path() } else { db_path };
+            match db::open_pool(live).await {
+                Ok(pool) => shared.set(pool),
+                Err(r) => also.push(format!("reopening {} failed: {r}", live.display())),
+            }
+            if also.is_empty() {
+                Err(e)
+            } else {
+                Err(AppError::Other(format!("{e} ({})", also.join("; "))))
+            }
+        }
+    }
+}
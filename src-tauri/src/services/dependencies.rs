@@ -0,0 +1,331 @@
+// src/services/dependencies.rs
+//! "Blocked by" / "blocks" relations between tasks.
+//!
+//! A task is blocked while any of its live predecessors isn't completed.
+//! Being blocked doesn't lock the task: `blocked` on its rows and a warning
+//! when it is started are all it gets, and only with the
+//! `note_to_start_blocked` status rule on does moving it to in-progress take
+//! a note, the way the other note rules do.
+//! Dependencies may cross projects; one that would close a cycle is refused
+//! with the chain it would have made.
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqliteConnection, SqlitePool};
+
+use crate::events::{self, ChangeOp, EntityKind};
+use crate::services::journal::{self, Recorder};
+use crate::services::projects;
+use crate::services::tasks::{self, TaskStatus};
+use crate::{db, AppError, Result};
+
+/// `blocked` column for a query over `tasks t`.
+pub(crate) const BLOCKED: &str = r#"
+    EXISTS (
+      SELECT 1 FROM task_dependency d JOIN tasks p ON p.id = d.depends_on
+      WHERE d.task_id = t.id AND p.deleted_at IS NULL AND p.status <> 'completed'
+    )
+"#;
+
+// ---------- Types ----------
+
+#[derive(Deserialize)]
+pub struct DependencyInput {
+    pub task_id: String,    // the blocked task
+    pub depends_on: String, // the task it waits for
+}
+
+/// The other end of a dependency, on `TaskDetail`.
+#[derive(Debug, Serialize, Clone)]
+pub struct LinkedTask {
+    pub id: String,
+    pub name: String,
+    pub status: String,
+    pub project_id: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GraphNode {
+    pub id: String,
+    pub name: String,
+    pub status: String,
+    pub project_id: Option<String>, // may be another project's task
+    pub blocked: bool,
+    pub depth: u32, // longest chain of predecessors in the graph; 0 = nothing before it
+}
+
+#[derive(Debug, Serialize)]
+pub struct GraphEdge {
+    pub from: String, // predecessor
+    pub to: String,   // the task it blocks
+}
+
+#[derive(Debug, Serialize)]
+pub struct DependencyGraph {
+    pub nodes: Vec<GraphNode>, // by depth, then name
+    pub edges: Vec<GraphEdge>,
+}
+
+// ---------- Helpers ----------
+
+fn linked(row: &sqlx::sqlite::SqliteRow) -> LinkedTask {
+    LinkedTask {
+        id: row.get("id"),
+        name: row.get("name"),
+        status: row.get("status"),
+        project_id: row.get("project_id"),
+    }
+}
+
+async fn task_name(conn: &mut SqliteConnection, id: &str) -> Result<String> {
+    Ok(sqlx::query_scalar("SELECT name FROM tasks WHERE id = ?1")
+        .bind(id)
+        .fetch_optional(conn)
+        .await?
+        .unwrap_or_default())
+}
+
+/// The chain of predecessors from `from` back to `to`, both included, if
+/// there is one.
+async fn find_chain(conn: &mut SqliteConnection, from: &str, to: &str) -> Result<Option<Vec<String>>> {
+    let edges: Vec<(String, String)> = sqlx::query_as("SELECT task_id, depends_on FROM task_dependency")
+        .fetch_all(conn)
+        .await?;
+    let mut before: HashMap<&str, Vec<&str>> = HashMap::new();
+    for (task, dep) in &edges {
+        before.entry(task).or_default().push(dep);
+    }
+
+    let mut came_from: HashMap<&str, &str> = HashMap::new();
+    let mut queue = VecDeque::from([from]);
+    while let Some(id) = queue.pop_front() {
+        if id == to {
+            let mut chain = vec![to.to_string()];
+            let mut at = to;
+            while let Some(prev) = came_from.get(at) {
+                chain.push(prev.to_string());
+                at = prev;
+            }
+            chain.reverse();
+            return Ok(Some(chain));
+        }
+        for &next in before.get(id).into_iter().flatten() {
+            if next != from && !came_from.contains_key(next) {
+                came_from.insert(next, id);
+                queue.push_back(next);
+            }
+        }
+    }
+    Ok(None)
+}
+
+// ---------- Queries ----------
+
+/// Tasks `task_id` waits for, finished or not.
+pub async fn blocked_by(pool: &SqlitePool, task_id: &str) -> Result<Vec<LinkedTask>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT t.id, t.name, t.status, t.project_id
+        FROM task_dependency d JOIN tasks t ON t.id = d.depends_on
+        WHERE d.task_id = ?1 AND t.deleted_at IS NULL
+        ORDER BY t.name
+        "#,
+    )
+    .bind(task_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows.iter().map(linked).collect())
+}
+
+/// Tasks waiting for `task_id`.
+pub async fn blocks(pool: &SqlitePool, task_id: &str) -> Result<Vec<LinkedTask>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT t.id, t.name, t.status, t.project_id
+        FROM task_dependency d JOIN tasks t ON t.id = d.task_id
+        WHERE d.depends_on = ?1 AND t.deleted_at IS NULL
+        ORDER BY t.name
+        "#,
+    )
+    .bind(task_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows.iter().map(linked).collect())
+}
+
+/// Moving a task to in-progress while it has open predecessors warns with
+/// their names; with the `note_to_start_blocked` rule on it also needs a
+/// note saying why.
+pub(crate) async fn check_start(pool: &SqlitePool, task_id: &str, to: TaskStatus, note: Option<&str>) -> Result<Option<String>> {
+    if to != TaskStatus::InProgress {
+        return Ok(None);
+    }
+    let open: Vec<String> = blocked_by(pool, task_id)
+        .await?
+        .into_iter()
+        .filter(|t| t.status != TaskStatus::Completed.to_string())
+        .map(|t| t.name)
+        .collect();
+    if open.is_empty() {
+        return Ok(None);
+    }
+    let warning = format!("Blocked by {}", open.join(", "));
+    if note.is_none_or(|n| n.trim().is_empty()) && tasks::get_status_rules(pool).await?.note_to_start_blocked {
+        return Err(AppError::validation("note", format!("{warning}; add a note to start it anyway")));
+    }
+    Ok(Some(warning))
+}
+
+/// Dependencies touching a project's live tasks, with the other ends even
+/// when they live elsewhere.
+pub async fn dependency_graph(pool: &SqlitePool, project_id: &str) -> Result<DependencyGraph> {
+    if !db::row_exists(pool, "project", project_id).await? {
+        return Err(AppError::not_found("project", project_id));
+    }
+    let edges: Vec<(String, String)> = sqlx::query_as(
+        r#"
+        SELECT d.depends_on, d.task_id
+        FROM task_dependency d
+        JOIN tasks a ON a.id = d.task_id
+        JOIN tasks b ON b.id = d.depends_on
+        WHERE a.deleted_at IS NULL AND b.deleted_at IS NULL
+          AND (a.project_id = ?1 OR b.project_id = ?1)
+        "#,
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await?;
+
+    let ids: HashSet<&str> = edges.iter().flat_map(|(a, b)| [a.as_str(), b.as_str()]).collect();
+    let ids_json = serde_json::to_string(&ids).map_err(|e| AppError::Other(e.to_string()))?;
+    let rows = sqlx::query(&format!(
+        "SELECT t.id, t.name, t.status, t.project_id, {BLOCKED} AS blocked FROM tasks t WHERE t.id IN (SELECT value FROM json_each(?1))"
+    ))
+    .bind(ids_json)
+    .fetch_all(pool)
+    .await?;
+
+    // longest path from the roots, visiting tasks once all their predecessors are placed
+    let mut waiting: HashMap<&str, usize> = ids.iter().map(|id| (*id, 0)).collect();
+    for (_, to) in &edges {
+        *waiting.entry(to).or_default() += 1;
+    }
+    let mut depth: HashMap<&str, u32> = HashMap::new();
+    let mut ready: VecDeque<&str> = waiting.iter().filter(|(_, n)| **n == 0).map(|(id, _)| *id).collect();
+    while let Some(id) = ready.pop_front() {
+        let d = *depth.entry(id).or_default();
+        for (from, to) in &edges {
+            if from == id {
+                let next = depth.entry(to).or_default();
+                *next = (*next).max(d + 1);
+                let n = waiting.get_mut(to.as_str()).expect("node of an edge");
+                *n -= 1;
+                if *n == 0 {
+                    ready.push_back(to);
+                }
+            }
+        }
+    }
+
+    let mut nodes: Vec<GraphNode> = rows
+        .iter()
+        .map(|r| {
+            let id: String = r.get("id");
+            GraphNode {
+                depth: depth.get(id.as_str()).copied().unwrap_or_default(),
+                name: r.get("name"),
+                status: r.get("status"),
+                project_id: r.get("project_id"),
+                blocked: r.get("blocked"),
+                id,
+            }
+        })
+        .collect();
+    nodes.sort_by(|a, b| a.depth.cmp(&b.depth).then_with(|| a.name.cmp(&b.name)));
+
+    Ok(DependencyGraph {
+        nodes,
+        edges: edges.into_iter().map(|(from, to)| GraphEdge { from, to }).collect(),
+    })
+}
+
+// ---------- Editing ----------
+
+/// Make `task_id` wait for `depends_on`.
+pub async fn add_dependency(pool: &SqlitePool, input: DependencyInput) -> Result<()> {
+    if input.task_id == input.depends_on {
+        return Err(AppError::validation("depends_on", "A task can't depend on itself"));
+    }
+    if !tasks::is_live(pool, &input.task_id).await? {
+        return Err(AppError::not_found("task", &input.task_id));
+    }
+    if !tasks::is_live(pool, &input.depends_on).await? {
+        return Err(AppError::foreign_key("task", "depends_on", &input.depends_on));
+    }
+    projects::ensure_writable(pool, "tasks", &input.task_id).await?;
+
+    // checked inside the write lock, so two adds can't close a loop between them
+    let mut tx = journal::begin(pool).await?;
+    let exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM task_dependency WHERE task_id = ?1 AND depends_on = ?2)")
+        .bind(&input.task_id)
+        .bind(&input.depends_on)
+        .fetch_one(&mut *tx)
+        .await?;
+    if exists {
+        return Err(AppError::conflict("task_dependency", "depends_on", "Already blocked by that task"));
+    }
+    // depends_on already waiting (through any chain) for task_id closes a loop
+    if let Some(chain) = find_chain(&mut tx, &input.depends_on, &input.task_id).await? {
+        let mut names = vec![task_name(&mut tx, &input.task_id).await?];
+        for id in &chain {
+            names.push(task_name(&mut tx, id).await?);
+        }
+        return Err(AppError::conflict(
+            "task_dependency",
+            "depends_on",
+            format!("That would make a cycle: {}", names.join(" → ")),
+        ));
+    }
+
+    let mut op = Recorder::new("Add dependency");
+    op.track_key(&mut tx, "task_dependency", &[("task_id", &input.task_id), ("depends_on", &input.depends_on)]).await?;
+
+    sqlx::query("INSERT INTO task_dependency (task_id, depends_on, created_at) VALUES (?1, ?2, ?3)")
+        .bind(&input.task_id)
+        .bind(&input.depends_on)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+    op.commit(&mut tx).await?;
+    tx.commit().await?;
+    events::publish(EntityKind::Task, &input.task_id, ChangeOp::Updated);
+    events::publish(EntityKind::Task, &input.depends_on, ChangeOp::Updated);
+
+    Ok(())
+}
+
+pub async fn remove_dependency(pool: &SqlitePool, input: DependencyInput) -> Result<()> {
+    projects::ensure_writable(pool, "tasks", &input.task_id).await?;
+
+    let mut tx = journal::begin(pool).await?;
+    let mut op = Recorder::new("Remove dependency");
+    op.track_key(&mut tx, "task_dependency", &[("task_id", &input.task_id), ("depends_on", &input.depends_on)]).await?;
+
+    let done = sqlx::query("DELETE FROM task_dependency WHERE task_id = ?1 AND depends_on = ?2")
+        .bind(&input.task_id)
+        .bind(&input.depends_on)
+        .execute(&mut *tx)
+        .await?;
+    if done.rows_affected() == 0 {
+        return Err(AppError::not_found("task_dependency", format!("{} -> {}", input.task_id, input.depends_on)));
+    }
+
+    op.commit(&mut tx).await?;
+    tx.commit().await?;
+    events::publish(EntityKind::Task, &input.task_id, ChangeOp::Updated);
+    events::publish(EntityKind::Task, &input.depends_on, ChangeOp::Updated);
+
+    Ok(())
+}
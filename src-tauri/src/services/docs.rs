@@ -0,0 +1,472 @@
+use std::{fmt, path::Path};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqlitePool};
+use uuid::Uuid;
+use base64::Engine;
+
+use crate::events::{self, ChangeOp, EntityKind};
+use crate::services::journal::{self, Recorder};
+use crate::services::markdown;
+use crate::services::projects;
+use crate::services::settings;
+use crate::services::trash;
+use crate::{db, AppError, Result};
+
+/// Setting holding the renderer version the stored `body_html` came from.
+const HTML_VERSION_KEY: &str = "doc_html_version";
+/// Bump when `markdown::to_html` output changes, to render every doc again.
+const HTML_VERSION: i64 = 1;
+
+// ---------- Types ----------
+#[derive(Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "kebab-case")]
+pub enum DocStatus {
+    Draft,
+    InReview,
+    Published,
+}
+impl fmt::Display for DocStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            DocStatus::Draft => "draft",
+            DocStatus::InReview => "in_review",
+            DocStatus::Published => "published",
+        })
+    }
+}
+
+#[derive(Deserialize)]
+pub struct DocInput {
+    pub project_id: String,
+    pub title: String,
+    pub slug: Option<String>,
+    pub body_md: String,
+    pub cover_path: Option<String>,
+    pub status: Option<DocStatus>, // default draft
+}
+
+#[derive(Debug, Serialize)]
+pub struct DocRow {
+    pub id: String,
+    pub project_id: String,
+    pub title: String,
+    pub slug: Option<String>,
+    pub status: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DocDetail {
+    pub id: String,
+    pub project_id: String,
+    pub title: String,
+    pub slug: Option<String>,
+    pub body_md: String,
+    pub body_html: String,
+    pub cover_path: Option<String>,
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize)]
+pub struct UpdateDocBodyInput {
+    pub id: String,
+    pub body_md: String,
+}
+
+#[derive(Deserialize)]
+pub struct UpdateDocMetaInput {
+    pub id: String,
+    pub title: Option<String>,
+    pub slug: Option<String>,
+    pub cover_path: Option<String>,
+    pub status: Option<DocStatus>,
+}
+
+#[derive(Deserialize)]
+pub struct SaveDocAttachmentInput {
+    pub doc_id: String,
+    pub filename: String,
+    pub bytes_base64: String,
+    pub mime: Option<String>,
+}
+
+// ---------- Commands ----------
+
+// `doc.slug` is the only UNIQUE column a doc write can trip over.
+fn slug_conflict(e: sqlx::Error) -> AppError {
+    match AppError::from(e) {
+        AppError::Conflict { .. } => AppError::conflict("doc", "slug", "Slug is already in use"),
+        other => other,
+    }
+}
+
+pub async fn add_doc(pool: &SqlitePool, input: DocInput) -> Result<String> {
+    let id = Uuid::new_v4().to_string();
+    let now = Utc::now();
+
+    let body_html = markdown::to_html(&input.body_md);
+    let status = input.status.unwrap_or(DocStatus::Draft).to_string();
+
+    if input.title.trim().is_empty() {
+        return Err(AppError::validation("title", "Doc title is required"));
+    }
+    if !db::row_exists(pool, "project", &input.project_id).await? {
+        return Err(AppError::foreign_key("project", "project_id", &input.project_id));
+    }
+
+    projects::ensure_project_writable(pool, &input.project_id).await?;
+
+    let version_id = Uuid::new_v4().to_string();
+    let mut tx = journal::begin(pool).await?;
+    let mut op = Recorder::new("Add doc");
+    op.track(&mut tx, "doc", &id).await?;
+    op.track(&mut tx, "doc_version", &version_id).await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO doc (
+          id, project_id, title, slug, body_md, body_html, cover_path, status, created_at, updated_at
+        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(&id)
+    .bind(&input.project_id)
+    .bind(&input.title)
+    .bind(&input.slug)
+    .bind(&input.body_md)
+    .bind(&body_html)
+    .bind(input.cover_path.unwrap_or_default())
+    .bind(status)
+    .bind(now)
+    .bind(now)
+    .execute(&mut *tx)
+    .await
+    .map_err(slug_conflict)?;
+
+    // initial version snapshot
+    sqlx::query(
+        r#"INSERT INTO doc_version (id, doc_id, body_md, created_at) VALUES (?, ?, ?, ?)"#,
+    )
+    .bind(&version_id)
+    .bind(&id)
+    .bind(&input.body_md)
+    .bind(now)
+    .execute(&mut *tx)
+    .await?;
+
+    op.commit(&mut tx).await?;
+    tx.commit().await?;
+    events::publish(EntityKind::Doc, &id, ChangeOp::Created);
+
+    Ok(id)
+}
+
+pub async fn list_docs(pool: &SqlitePool, project_id: &str) -> Result<Vec<DocRow>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT id, project_id, title, slug, status, updated_at
+        FROM doc
+        WHERE project_id = ? AND deleted_at IS NULL
+        ORDER BY updated_at DESC
+        "#,
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await?;
+
+    let docs: Vec<DocRow> = rows
+        .into_iter()
+        .map(|row| DocRow {
+            id: row.get("id"),
+            project_id: row.get("project_id"),
+            title: row.get("title"),
+            slug: row.get::<Option<String>, _>("slug"),
+            status: row.get("status"),
+            updated_at: row.get("updated_at"),
+        })
+        .collect();
+
+    Ok(docs)
+}
+
+pub async fn get_doc(pool: &SqlitePool, id: &str) -> Result<DocDetail> {
+    let row = sqlx::query(
+        r#"
+        SELECT id, project_id, title, slug, body_md, body_html, cover_path, status, created_at, updated_at
+        FROM doc WHERE id = ?
+        "#,
+    )
+    .bind(id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::not_found("doc", id))?;
+
+    Ok(DocDetail {
+        id: row.get("id"),
+        project_id: row.get("project_id"),
+        title: row.get("title"),
+        slug: row.get::<Option<String>, _>("slug"),
+        body_md: row.get("body_md"),
+        body_html: row.get("body_html"),
+        cover_path: {
+            let s: String = row.get("cover_path");
+            if s.is_empty() { None } else { Some(s) }
+        },
+        status: row.get("status"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    })
+}
+
+/// Look a doc up by slug, falling back to its id.
+pub async fn get_doc_by_slug(pool: &SqlitePool, slug: &str) -> Result<DocDetail> {
+    let id: String = sqlx::query_scalar(
+        r#"SELECT id FROM doc WHERE slug = ?1 OR id = ?1 ORDER BY slug = ?1 DESC LIMIT 1"#,
+    )
+    .bind(slug)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::not_found("doc", slug))?;
+
+    get_doc(pool, &id).await
+}
+
+pub async fn update_doc_body(
+    pool: &SqlitePool,
+    input: UpdateDocBodyInput,
+) -> Result<()> {
+    let now = Utc::now();
+
+    let body_html = markdown::to_html(&input.body_md);
+
+    projects::ensure_writable(pool, "doc", &input.id).await?;
+
+    let version_id = Uuid::new_v4().to_string();
+    let mut tx = journal::begin(pool).await?;
+    let mut op = Recorder::new("Edit doc");
+    op.track(&mut tx, "doc", &input.id).await?;
+    op.track(&mut tx, "doc_version", &version_id).await?;
+
+    let res = sqlx::query(
+        r#"
+        UPDATE doc SET body_md = ?, body_html = ?, updated_at = ?
+        WHERE id = ?
+        "#,
+    )
+    .bind(&input.body_md)
+    .bind(&body_html)
+    .bind(now)
+    .bind(&input.id)
+    .execute(&mut *tx)
+    .await?;
+    if res.rows_affected() == 0 {
+        return Err(AppError::not_found("doc", &input.id));
+    }
+
+    // version snapshot
+    sqlx::query(
+        r#"INSERT INTO doc_version (id, doc_id, body_md, created_at) VALUES (?, ?, ?, ?)"#,
+    )
+    .bind(&version_id)
+    .bind(&input.id)
+    .bind(&input.body_md)
+    .bind(now)
+    .execute(&mut *tx)
+    .await?;
+
+    op.commit(&mut tx).await?;
+    tx.commit().await?;
+    events::publish(EntityKind::Doc, &input.id, ChangeOp::Updated);
+
+    Ok(())
+}
+
+pub async fn update_doc_meta(
+    pool: &SqlitePool,
+    input: UpdateDocMetaInput,
+) -> Result<()> {
+    let now = Utc::now();
+
+    let cur = sqlx::query(
+        r#"
+        SELECT title, slug, cover_path, status
+        FROM doc WHERE id = ?
+        "#,
+    )
+    .bind(&input.id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::not_found("doc", &input.id))?;
+
+    let title: String = input.title.unwrap_or_else(|| cur.get("title"));
+    let slug: Option<String> = input.slug.or(cur.get::<Option<String>, _>("slug"));
+    let cover_path: String = input
+        .cover_path
+        .or_else(|| {
+            let s: String = cur.get("cover_path");
+            if s.is_empty() { None } else { Some(s) }
+        })
+        .unwrap_or_default();
+    let status: String = input
+        .status
+        .map(|s| s.to_string())
+        .unwrap_or_else(|| cur.get::<String, _>("status"));
+
+    projects::ensure_writable(pool, "doc", &input.id).await?;
+
+    let mut tx = journal::begin(pool).await?;
+    let mut op = Recorder::new("Edit doc details");
+    op.track(&mut tx, "doc", &input.id).await?;
+
+    sqlx::query(
+        r#"
+        UPDATE doc SET title = ?, slug = ?, cover_path = ?, status = ?, updated_at = ?
+        WHERE id = ?
+        "#,
+    )
+    .bind(&title)
+    .bind(&slug)
+    .bind(&cover_path)
+    .bind(&status)
+    .bind(now)
+    .bind(&input.id)
+    .execute(&mut *tx)
+    .await
+    .map_err(slug_conflict)?;
+
+    op.commit(&mut tx).await?;
+    tx.commit().await?;
+    events::publish(EntityKind::Doc, &input.id, ChangeOp::Updated);
+
+    Ok(())
+}
+
+pub async fn search_docs(
+    pool: &SqlitePool,
+    q: &str,
+    project_id: Option<&str>,
+) -> Result<Vec<(String, String)>> {
+    let rows = if let Some(pid) = project_id {
+        sqlx::query(
+            r#"
+            SELECT d.id AS id, d.title AS title
+            FROM doc_fts f
+            JOIN doc d ON d.rowid = f.rowid
+            WHERE doc_fts MATCH ? AND d.project_id = ? AND d.deleted_at IS NULL
+            ORDER BY d.updated_at DESC
+            "#,
+        )
+        .bind(q)
+        .bind(pid)
+        .fetch_all(pool)
+        .await
+    } else {
+        sqlx::query(
+            r#"
+            SELECT d.id AS id, d.title AS title
+            FROM doc_fts f
+            JOIN doc d ON d.rowid = f.rowid
+            WHERE doc_fts MATCH ? AND d.deleted_at IS NULL
+            ORDER BY d.updated_at DESC
+            "#,
+        )
+        .bind(q)
+        .fetch_all(pool)
+        .await
+    }?;
+
+    let out = rows
+        .into_iter()
+        .map(|r| (r.get::<String, _>("id"), r.get::<String, _>("title")))
+        .collect();
+
+    Ok(out)
+}
+
+/// Save a binary attachment to disk and create a doc_attachment row.
+/// Frontend should then render it in Markdown using `convertFileSrc(path)`.
+pub async fn save_doc_attachment(
+    pool: &SqlitePool,
+    app_dir: &Path,
+    input: SaveDocAttachmentInput,
+) -> Result<String> {
+    if input.filename.is_empty()
+        || input.filename.contains(['/', '\\'])
+        || input.filename == ".."
+    {
+        return Err(AppError::validation("filename", "Invalid attachment filename"));
+    }
+    if !db::row_exists(pool, "doc", &input.doc_id).await? {
+        return Err(AppError::foreign_key("doc", "doc_id", &input.doc_id));
+    }
+    projects::ensure_writable(pool, "doc", &input.doc_id).await?;
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(&input.bytes_base64)
+        .map_err(|e| AppError::validation("bytes_base64", e.to_string()))?;
+
+    let dir = db::attachments_dir(app_dir).join(&input.doc_id);
+    std::fs::create_dir_all(&dir)?;
+
+    let path = dir.join(&input.filename);
+    std::fs::write(&path, &bytes)?;
+
+    let attachment_id = Uuid::new_v4().to_string();
+    let mut tx = journal::begin(pool).await?;
+    let mut op = Recorder::new("Add doc attachment");
+    op.track(&mut tx, "doc_attachment", &attachment_id).await?;
+
+    sqlx::query(
+        r#"INSERT INTO doc_attachment (id, doc_id, filename, path, mime, created_at) VALUES (?, ?, ?, ?, ?, ?)"#,
+    )
+    .bind(&attachment_id)
+    .bind(&input.doc_id)
+    .bind(&input.filename)
+    .bind(path.to_string_lossy().to_string())
+    .bind(input.mime.unwrap_or_default())
+    .bind(Utc::now())
+    .execute(&mut *tx)
+    .await?;
+
+    op.commit(&mut tx).await?;
+    tx.commit().await?;
+    events::publish(EntityKind::Doc, &input.doc_id, ChangeOp::Updated);
+
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// Move a doc to the Trash; see `services::trash` for restore and purge.
+pub async fn delete_doc(pool: &SqlitePool, id: &str) -> Result<()> {
+    trash::delete(pool, EntityKind::Doc, id).await
+}
+
+/// Render every doc's `body_html` again if the stored one predates the
+/// current renderer: early docs kept their Markdown there, and later ones
+/// let raw HTML through. Runs when the database is opened.
+pub async fn refresh_html(pool: &SqlitePool) -> Result<()> {
+    let version: Option<i64> = settings::get(pool, HTML_VERSION_KEY).await?;
+    if version.is_some_and(|v| v >= HTML_VERSION) {
+        return Ok(());
+    }
+
+    let mut tx = pool.begin().await?;
+    let docs: Vec<(String, String, String)> = sqlx::query_as("SELECT id, body_md, body_html FROM doc")
+        .fetch_all(&mut *tx)
+        .await?;
+    for (id, body_md, body_html) in docs {
+        let fresh = markdown::to_html(&body_md);
+        if fresh != body_html {
+            sqlx::query("UPDATE doc SET body_html = ?2 WHERE id = ?1")
+                .bind(&id)
+                .bind(&fresh)
+                .execute(&mut *tx)
+                .await?;
+        }
+    }
+    settings::put(&mut *tx, HTML_VERSION_KEY, &HTML_VERSION).await?;
+    tx.commit().await?;
+    Ok(())
+}
@@ -0,0 +1,122 @@
+// src/services/history.rs
+//! Field-level change log for tasks, ideas and projects.
+//!
+//! Rows are appended from the journal whenever a tracked row is updated
+//! (including by undo/redo), so every mutation path is covered without each
+//! service listing its own fields. Nothing here is ever rewritten.
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::{Row, SqliteConnection, SqlitePool};
+
+use crate::events::EntityKind;
+use crate::services::rows::Image;
+use crate::{AppError, Result};
+
+/// Bookkeeping columns that change on every write and say nothing new.
+const SKIP_FIELDS: &[&str] = &["id", "created_at", "updated_at"];
+
+#[derive(Debug, Serialize)]
+pub struct FieldChange {
+    pub id: i64,
+    pub field: String,
+    pub old_value: Value,
+    pub new_value: Value,
+    pub changed_at: DateTime<Utc>,
+}
+
+fn kind_for_table(table: &str) -> Option<EntityKind> {
+    match table {
+        "tasks" => Some(EntityKind::Task),
+        "idea" => Some(EntityKind::Idea),
+        "project" => Some(EntityKind::Project),
+        _ => None,
+    }
+}
+
+fn kind_name(kind: EntityKind) -> Option<&'static str> {
+    match kind {
+        EntityKind::Task => Some("task"),
+        EntityKind::Idea => Some("idea"),
+        EntityKind::Project => Some("project"),
+        _ => None,
+    }
+}
+
+/// Append one entry per changed field. Only updates are logged: a row that
+/// was created or deleted has no "old → new" worth showing field by field.
+pub(crate) async fn record(
+    conn: &mut SqliteConnection,
+    table: &str,
+    before: Option<&Image>,
+    after: Option<&Image>,
+) -> Result<()> {
+    let (Some(kind), Some(before), Some(after)) = (kind_for_table(table).and_then(kind_name), before, after) else {
+        return Ok(());
+    };
+    let Some(id) = after.get("id").and_then(Value::as_str) else {
+        return Ok(());
+    };
+    let now = Utc::now();
+
+    for (field, new) in after {
+        let old = before.get(field).unwrap_or(&Value::Null);
+        if old == new || SKIP_FIELDS.contains(&field.as_str()) {
+            continue;
+        }
+        sqlx::query(
+            r#"
+            INSERT INTO entity_change (entity_kind, entity_id, field, old_value, new_value, changed_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            "#,
+        )
+        .bind(kind)
+        .bind(id)
+        .bind(field)
+        .bind(json_text(old))
+        .bind(json_text(new))
+        .bind(now)
+        .execute(&mut *conn)
+        .await?;
+    }
+    Ok(())
+}
+
+fn json_text(v: &Value) -> Option<String> {
+    (!v.is_null()).then(|| v.to_string())
+}
+
+fn parse_value(raw: Option<String>) -> Value {
+    raw.and_then(|s| serde_json::from_str(&s).ok()).unwrap_or(Value::Null)
+}
+
+/// Every recorded field change for one entity, oldest first.
+pub async fn get_history(pool: &SqlitePool, kind: EntityKind, id: &str) -> Result<Vec<FieldChange>> {
+    let Some(kind) = kind_name(kind) else {
+        return Err(AppError::validation("kind", "History is kept for tasks, ideas and projects"));
+    };
+
+    let rows = sqlx::query(
+        r#"
+        SELECT id, field, old_value, new_value, changed_at
+        FROM entity_change
+        WHERE entity_kind = ?1 AND entity_id = ?2
+        ORDER BY id ASC
+        "#,
+    )
+    .bind(kind)
+    .bind(id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|r| FieldChange {
+            id: r.get("id"),
+            field: r.get("field"),
+            old_value: parse_value(r.get("old_value")),
+            new_value: parse_value(r.get("new_value")),
+            changed_at: r.get("changed_at"),
+        })
+        .collect())
+}
@@ -0,0 +1,598 @@
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqlitePool};
+use uuid::Uuid;
+
+use crate::events::{self, ChangeOp, EntityKind};
+use crate::services::journal::{self, Recorder};
+use crate::services::projects;
+use crate::services::trash;
+use crate::{db, AppError, Result};
+
+// ---------- Types ----------
+#[derive(Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "kebab-case")]
+pub enum IdeaStatus {
+    Inbox,
+    Exploring,
+    Building,
+    Paused,
+    Shipped,
+    Dropped,
+}
+impl fmt::Display for IdeaStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            IdeaStatus::Inbox => "inbox",
+            IdeaStatus::Exploring => "exploring",
+            IdeaStatus::Building => "building",
+            IdeaStatus::Paused => "paused",
+            IdeaStatus::Shipped => "shipped",
+            IdeaStatus::Dropped => "dropped",
+        })
+    }
+}
+
+#[derive(Deserialize)]
+pub struct IdeaInput {
+    pub project_id: String,
+    pub title: String,
+    pub summary: Option<String>,
+    pub status: Option<IdeaStatus>, // default inbox if None
+    pub priority: Option<i64>,      // 0..4
+    pub effort_pts: Option<i64>,
+    pub impact_pts: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IdeaRow {
+    pub id: String,
+    pub project_id: String,
+    pub title: String,
+    pub status: String,
+    pub priority: i64,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IdeaDetail {
+    pub id: String,
+    pub project_id: String,
+    pub title: String,
+    pub summary: Option<String>,
+    pub status: String,
+    pub priority: i64,
+    pub effort_pts: i64,
+    pub impact_pts: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub tasks: Vec<LinkedTask>, // created from this idea, trashed ones left out
+    pub ready_to_ship: bool,    // every linked task is completed; offer to mark it shipped
+}
+
+#[derive(Debug, Serialize)]
+pub struct LinkedTask {
+    pub id: String,
+    pub name: String,
+    pub status: String,
+}
+
+#[derive(Deserialize)]
+pub struct UpdateIdeaInput {
+    pub id: String,
+    pub title: Option<String>,
+    pub summary: Option<String>,
+    pub status: Option<IdeaStatus>,
+    pub priority: Option<i64>,
+    pub effort_pts: Option<i64>,
+    pub impact_pts: Option<i64>,
+}
+
+#[derive(Deserialize)]
+pub struct IdeaNoteInput {
+    pub idea_id: String,
+    pub body_md: String,
+}
+
+#[derive(Deserialize)]
+pub struct IdeaLinkInput {
+    pub idea_id: String,
+    pub kind: String, // ref | tweet | video | doc | other
+    pub url: String,
+    pub title: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct IdeaAttachmentInput {
+    pub idea_id: String,
+    pub filename: String,
+    pub path: String,
+    pub mime: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct IdeaDocLinkInput {
+    pub idea_id: String,
+    pub doc_id: String,
+}
+
+// create a task from an idea
+#[derive(Deserialize)]
+pub struct IdeaToTaskInput {
+    pub idea_id: String,
+    pub category: String,
+    pub description: Option<String>,
+    pub start_at: Option<DateTime<Utc>>,
+    pub end_est_at: Option<DateTime<Utc>>,
+}
+
+// ---------- Commands ----------
+
+pub async fn add_idea(pool: &SqlitePool, input: IdeaInput) -> Result<String> {
+    let id = Uuid::new_v4().to_string();
+    let now = Utc::now();
+
+    let status = input.status.unwrap_or(IdeaStatus::Inbox).to_string();
+    let priority = input.priority.unwrap_or(2);
+    let effort = input.effort_pts.unwrap_or(1);
+    let impact = input.impact_pts.unwrap_or(1);
+
+    if input.title.trim().is_empty() {
+        return Err(AppError::validation("title", "Idea title is required"));
+    }
+    if !(0..=4).contains(&priority) {
+        return Err(AppError::validation("priority", "Priority must be between 0 and 4"));
+    }
+    if !db::row_exists(pool, "project", &input.project_id).await? {
+        return Err(AppError::foreign_key("project", "project_id", &input.project_id));
+    }
+
+    projects::ensure_project_writable(pool, &input.project_id).await?;
+
+    let mut tx = journal::begin(pool).await?;
+    let mut op = Recorder::new("Add idea");
+    op.track(&mut tx, "idea", &id).await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO idea (
+          id, project_id, title, summary, status, priority, effort_pts, impact_pts, created_at, updated_at
+        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(&id)
+    .bind(&input.project_id)
+    .bind(&input.title)
+    .bind(&input.summary)
+    .bind(&status)
+    .bind(priority)
+    .bind(effort)
+    .bind(impact)
+    .bind(now)
+    .bind(now)
+    .execute(&mut *tx)
+    .await?;
+
+    op.commit(&mut tx).await?;
+    tx.commit().await?;
+    events::publish(EntityKind::Idea, &id, ChangeOp::Created);
+
+    Ok(id)
+}
+
+pub async fn list_ideas(pool: &SqlitePool, project_id: &str) -> Result<Vec<IdeaRow>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT id, project_id, title, status, priority, updated_at
+        FROM idea
+        WHERE project_id = ? AND deleted_at IS NULL
+        ORDER BY updated_at DESC
+        "#,
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await?;
+
+    let ideas: Vec<IdeaRow> = rows
+        .into_iter()
+        .map(|row| IdeaRow {
+            id: row.get("id"),
+            project_id: row.get("project_id"),
+            title: row.get("title"),
+            status: row.get("status"),
+            priority: row.get::<i64, _>("priority"),
+            updated_at: row.get("updated_at"),
+        })
+        .collect();
+
+    Ok(ideas)
+}
+
+pub async fn get_idea(pool: &SqlitePool, id: &str) -> Result<IdeaDetail> {
+    let row = sqlx::query(
+        r#"
+        SELECT id, project_id, title, summary, status, priority, effort_pts, impact_pts,
+               created_at, updated_at
+        FROM idea
+        WHERE id = ?
+        "#,
+    )
+    .bind(id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::not_found("idea", id))?;
+
+    let tasks = linked_tasks(pool, id).await?;
+    let status: String = row.get("status");
+    let ready_to_ship = !tasks.is_empty()
+        && tasks.iter().all(|t| t.status == "completed")
+        && !matches!(status.as_str(), "shipped" | "dropped");
+
+    Ok(IdeaDetail {
+        id: row.get("id"),
+        project_id: row.get("project_id"),
+        title: row.get("title"),
+        summary: row.get::<Option<String>, _>("summary"),
+        status,
+        priority: row.get::<i64, _>("priority"),
+        effort_pts: row.get::<i64, _>("effort_pts"),
+        impact_pts: row.get::<i64, _>("impact_pts"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+        tasks,
+        ready_to_ship,
+    })
+}
+
+/// Tasks created from an idea, oldest first.
+pub async fn linked_tasks(pool: &SqlitePool, idea_id: &str) -> Result<Vec<LinkedTask>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT t.id, t.name, t.status
+        FROM idea_task_map m
+        JOIN tasks t ON t.id = m.task_id
+        WHERE m.idea_id = ? AND t.deleted_at IS NULL
+        ORDER BY t.created_at
+        "#,
+    )
+    .bind(idea_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|r| LinkedTask { id: r.get("id"), name: r.get("name"), status: r.get("status") })
+        .collect())
+}
+
+pub async fn update_idea(pool: &SqlitePool, input: UpdateIdeaInput) -> Result<()> {
+    let now = Utc::now();
+
+    let cur = sqlx::query(
+        r#"
+        SELECT title, summary, status, priority, effort_pts, impact_pts
+        FROM idea WHERE id = ?
+        "#,
+    )
+    .bind(&input.id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::not_found("idea", &input.id))?;
+
+    let title: String = input.title.unwrap_or_else(|| cur.get("title"));
+    let summary: Option<String> = input.summary.or(cur.get::<Option<String>, _>("summary"));
+    let status: String = input
+        .status
+        .map(|s| s.to_string())
+        .unwrap_or_else(|| cur.get::<String, _>("status"));
+    let priority: i64 = input.priority.unwrap_or_else(|| cur.get::<i64, _>("priority"));
+    let effort: i64 = input.effort_pts.unwrap_or_else(|| cur.get::<i64, _>("effort_pts"));
+    let impact: i64 = input.impact_pts.unwrap_or_else(|| cur.get::<i64, _>("impact_pts"));
+
+    if title.trim().is_empty() {
+        return Err(AppError::validation("title", "Idea title is required"));
+    }
+    if !(0..=4).contains(&priority) {
+        return Err(AppError::validation("priority", "Priority must be between 0 and 4"));
+    }
+
+    projects::ensure_writable(pool, "idea", &input.id).await?;
+
+    let mut tx = journal::begin(pool).await?;
+    let mut op = Recorder::new("Edit idea");
+    op.track(&mut tx, "idea", &input.id).await?;
+
+    sqlx::query(
+        r#"
+        UPDATE idea SET
+          title = ?, summary = ?, status = ?, priority = ?, effort_pts = ?, impact_pts = ?,
+          updated_at = ?
+        WHERE id = ?
+        "#,
+    )
+    .bind(&title)
+    .bind(&summary)
+    .bind(&status)
+    .bind(priority)
+    .bind(effort)
+    .bind(impact)
+    .bind(now)
+    .bind(&input.id)
+    .execute(&mut *tx)
+    .await?;
+
+    op.commit(&mut tx).await?;
+    tx.commit().await?;
+    events::publish(EntityKind::Idea, &input.id, ChangeOp::Updated);
+
+    Ok(())
+}
+
+pub async fn add_idea_note(pool: &SqlitePool, input: IdeaNoteInput) -> Result<String> {
+    let id = Uuid::new_v4().to_string();
+    let now = Utc::now();
+
+    if !db::row_exists(pool, "idea", &input.idea_id).await? {
+        return Err(AppError::foreign_key("idea", "idea_id", &input.idea_id));
+    }
+
+    projects::ensure_writable(pool, "idea", &input.idea_id).await?;
+
+    let mut tx = journal::begin(pool).await?;
+    let mut op = Recorder::new("Add idea note");
+    op.track(&mut tx, "idea_note", &id).await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO idea_note (id, idea_id, body_md, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(&id)
+    .bind(&input.idea_id)
+    .bind(&input.body_md)
+    .bind(now)
+    .bind(now)
+    .execute(&mut *tx)
+    .await?;
+
+    op.commit(&mut tx).await?;
+    tx.commit().await?;
+    events::publish(EntityKind::Idea, &input.idea_id, ChangeOp::Updated);
+
+    Ok(id)
+}
+
+pub async fn add_idea_link(pool: &SqlitePool, input: IdeaLinkInput) -> Result<String> {
+    let id = Uuid::new_v4().to_string();
+    let now = Utc::now();
+
+    if !db::row_exists(pool, "idea", &input.idea_id).await? {
+        return Err(AppError::foreign_key("idea", "idea_id", &input.idea_id));
+    }
+
+    projects::ensure_writable(pool, "idea", &input.idea_id).await?;
+
+    let mut tx = journal::begin(pool).await?;
+    let mut op = Recorder::new("Add idea link");
+    op.track(&mut tx, "idea_link", &id).await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO idea_link (id, idea_id, kind, url, title, created_at)
+        VALUES (?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(&id)
+    .bind(&input.idea_id)
+    .bind(&input.kind)
+    .bind(&input.url)
+    .bind(&input.title)
+    .bind(now)
+    .execute(&mut *tx)
+    .await?;
+
+    op.commit(&mut tx).await?;
+    tx.commit().await?;
+    events::publish(EntityKind::Idea, &input.idea_id, ChangeOp::Updated);
+
+    Ok(id)
+}
+
+pub async fn add_idea_attachment(
+    pool: &SqlitePool,
+    input: IdeaAttachmentInput,
+) -> Result<String> {
+    let id = Uuid::new_v4().to_string();
+    let now = Utc::now();
+
+    if !db::row_exists(pool, "idea", &input.idea_id).await? {
+        return Err(AppError::foreign_key("idea", "idea_id", &input.idea_id));
+    }
+
+    projects::ensure_writable(pool, "idea", &input.idea_id).await?;
+
+    let mut tx = journal::begin(pool).await?;
+    let mut op = Recorder::new("Add idea attachment");
+    op.track(&mut tx, "idea_attachment", &id).await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO idea_attachment (id, idea_id, filename, path, mime, created_at)
+        VALUES (?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(&id)
+    .bind(&input.idea_id)
+    .bind(&input.filename)
+    .bind(&input.path)
+    .bind(input.mime.unwrap_or_default())
+    .bind(now)
+    .execute(&mut *tx)
+    .await?;
+
+    op.commit(&mut tx).await?;
+    tx.commit().await?;
+    events::publish(EntityKind::Idea, &input.idea_id, ChangeOp::Updated);
+
+    Ok(id)
+}
+
+pub async fn link_idea_doc(pool: &SqlitePool, input: IdeaDocLinkInput) -> Result<()> {
+    if !db::row_exists(pool, "idea", &input.idea_id).await? {
+        return Err(AppError::foreign_key("idea", "idea_id", &input.idea_id));
+    }
+    if !db::row_exists(pool, "doc", &input.doc_id).await? {
+        return Err(AppError::foreign_key("doc", "doc_id", &input.doc_id));
+    }
+
+    projects::ensure_writable(pool, "idea", &input.idea_id).await?;
+    projects::ensure_writable(pool, "doc", &input.doc_id).await?;
+
+    let mut tx = journal::begin(pool).await?;
+    let mut op = Recorder::new("Link idea to doc");
+    op.track_key(&mut tx, "idea_doc_map", &[("idea_id", &input.idea_id), ("doc_id", &input.doc_id)]).await?;
+
+    sqlx::query(
+        r#"INSERT OR IGNORE INTO idea_doc_map (idea_id, doc_id) VALUES (?, ?)"#,
+    )
+    .bind(&input.idea_id)
+    .bind(&input.doc_id)
+    .execute(&mut *tx)
+    .await?;
+
+    op.commit(&mut tx).await?;
+    tx.commit().await?;
+    events::publish(EntityKind::Idea, &input.idea_id, ChangeOp::Updated);
+    events::publish(EntityKind::Doc, &input.doc_id, ChangeOp::Updated);
+
+    Ok(())
+}
+
+pub async fn search_ideas(
+    pool: &SqlitePool,
+    q: &str,
+    project_id: Option<&str>,
+) -> Result<Vec<(String, String)>> {
+    let rows = if let Some(pid) = project_id {
+        sqlx::query(
+            r#"
+            SELECT i.id AS id, i.title AS title
+            FROM idea_fts f
+            JOIN idea i ON i.rowid = f.rowid
+            WHERE idea_fts MATCH ? AND i.project_id = ? AND i.deleted_at IS NULL
+            ORDER BY i.updated_at DESC
+            "#,
+        )
+        .bind(q)
+        .bind(pid)
+        .fetch_all(pool)
+        .await
+    } else {
+        sqlx::query(
+            r#"
+            SELECT i.id AS id, i.title AS title
+            FROM idea_fts f
+            JOIN idea i ON i.rowid = f.rowid
+            WHERE idea_fts MATCH ? AND i.deleted_at IS NULL
+            ORDER BY i.updated_at DESC
+            "#,
+        )
+        .bind(q)
+        .fetch_all(pool)
+        .await
+    }?;
+
+    let out = rows
+        .into_iter()
+        .map(|r| (r.get::<String, _>("id"), r.get::<String, _>("title")))
+        .collect();
+
+    Ok(out)
+}
+
+/// Create a new task from an idea and link them.
+pub async fn create_task_from_idea(
+    pool: &SqlitePool,
+    input: IdeaToTaskInput,
+) -> Result<String> {
+    // 1) fetch idea
+    let idea = sqlx::query(
+        r#"SELECT id, project_id, title, summary FROM idea WHERE id = ?"#,
+    )
+    .bind(&input.idea_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::not_found("idea", &input.idea_id))?;
+
+    let project_id: String = idea.get("project_id");
+    let title: String = idea.get("title");
+    let summary: Option<String> = idea.get::<Option<String>, _>("summary");
+
+    // 2) create task with same project (workspace inferred via join)
+    let task_id = Uuid::new_v4().to_string();
+    let now = Utc::now();
+    let status = "todo"; // starting status
+
+    projects::ensure_project_writable(pool, &project_id).await?;
+
+    let mut tx = journal::begin(pool).await?;
+    let mut op = Recorder::new("Create task from idea");
+    op.track(&mut tx, "tasks", &task_id).await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO tasks (
+          id, name, category, short_summary, description, status, current_stage,
+          start_at, end_est_at, created_at, updated_at,
+          project_id, workspace_id, origin_idea_id
+        )
+        SELECT
+          ?, ?, ?, ?, ?, ?, NULL,
+          ?, ?, ?, ?,
+          p.id, w.id, ?
+        FROM project p
+        JOIN workspace w ON w.id = p.workspace_id
+        WHERE p.id = ?
+        "#,
+    )
+    .bind(&task_id)
+    .bind(&title)
+    .bind(&input.category)
+    .bind(&summary)
+    .bind(&input.description)
+    .bind(status)
+    .bind(input.start_at)
+    .bind(input.end_est_at)
+    .bind(now)
+    .bind(now)
+    .bind(&input.idea_id)
+    .bind(project_id)
+    .execute(&mut *tx)
+    .await?;
+
+    // 3) insert link
+    op.track_key(&mut tx, "idea_task_map", &[("idea_id", &input.idea_id), ("task_id", &task_id)]).await?;
+
+    sqlx::query(
+        r#"INSERT OR IGNORE INTO idea_task_map (idea_id, task_id) VALUES (?, ?)"#,
+    )
+    .bind(&input.idea_id)
+    .bind(&task_id)
+    .execute(&mut *tx)
+    .await?;
+
+    op.commit(&mut tx).await?;
+    tx.commit().await?;
+    events::publish(EntityKind::Task, &task_id, ChangeOp::Created);
+    events::publish(EntityKind::Idea, &input.idea_id, ChangeOp::Updated);
+
+    Ok(task_id)
+}
+
+/// Move a idea to the Trash; see `services::trash` for restore and purge.
+pub async fn delete_idea(pool: &SqlitePool, id: &str) -> Result<()> {
+    trash::delete(pool, EntityKind::Idea, id).await
+}
@@ -0,0 +1,297 @@
+// src/services/journal.rs
+//! Persistent undo/redo history.
+//!
+//! A mutating service opens a transaction with [`begin`] and wraps its writes
+//! in a [`Recorder`]: `track` every row it is about to touch, write, then
+//! `commit`, all on that transaction, and commit it last so the data and its
+//! journal entry land together. The journal stores before/after images, so
+//! undo and redo are plain row writes and survive restarts. A replay that
+//! finds a row changed outside the journal, or would delete one that rows
+//! outside the op still point at, is refused. Field history
+//! (`services::history`) is derived from the same images.
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::{Row, Sqlite, SqliteConnection, SqlitePool, Transaction};
+
+use crate::events::{self, ChangeOp, EntityKind};
+use crate::services::history;
+use crate::services::rows::{self, Image};
+use crate::{AppError, Result};
+
+/// Oldest ops beyond this are dropped when a new one is recorded.
+pub const HISTORY_LIMIT: i64 = 200;
+
+// ---------- Types ----------
+
+#[derive(Debug, Serialize)]
+pub struct OpSummary {
+    pub id: i64,
+    pub label: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// What the Edit menu shows: the next undo and redo, if any.
+#[derive(Debug, Serialize)]
+pub struct UndoState {
+    pub undo: Option<OpSummary>,
+    pub redo: Option<OpSummary>,
+}
+
+// ---------- Recording ----------
+
+/// Start the transaction for a recorded action. `BEGIN IMMEDIATE` takes the
+/// write lock up front, so the CLI or the local API can't write between the
+/// before-images and the journal entry.
+pub async fn begin(pool: &SqlitePool) -> Result<Transaction<'static, Sqlite>> {
+    Ok(pool.begin_with("BEGIN IMMEDIATE").await?)
+}
+
+struct Tracked {
+    table: &'static str,
+    key: Image,
+    before: Option<Image>,
+}
+
+/// Collects before-images for one user action and journals it on `commit`.
+pub struct Recorder {
+    label: &'static str,
+    rows: Vec<Tracked>,
+}
+
+impl Recorder {
+    pub fn new(label: &'static str) -> Self {
+        Recorder { label, rows: Vec::new() }
+    }
+
+    /// Snapshot `table` row `id` before it is written (or created).
+    pub async fn track(&mut self, conn: &mut SqliteConnection, table: &'static str, id: &str) -> Result<()> {
+        self.track_key(conn, table, &[("id", id)]).await
+    }
+
+    /// Like [`Recorder::track`] for tables with a composite primary key.
+    /// A row tracked twice keeps its first before-image.
+    pub async fn track_key(&mut self, conn: &mut SqliteConnection, table: &'static str, key: &[(&str, &str)]) -> Result<()> {
+        let key: Image = key.iter().map(|(k, v)| (k.to_string(), Value::from(*v))).collect();
+        if self.rows.iter().any(|t| t.table == table && t.key == key) {
+            return Ok(());
+        }
+        let before = rows::fetch_image(conn, table, &key).await?;
+        self.rows.push(Tracked { table, key, before });
+        Ok(())
+    }
+
+    /// Journal the tracked rows' after-images, on the transaction that made
+    /// the writes; the caller commits it. Rows that did not change are
+    /// skipped, and an action that changed nothing leaves no entry. Recording
+    /// a new op discards anything that could have been redone.
+    pub async fn commit(self, tx: &mut SqliteConnection) -> Result<()> {
+        let mut changes = Vec::new();
+        for t in self.rows {
+            let after = rows::fetch_image(tx, t.table, &t.key).await?;
+            if after != t.before {
+                changes.push((t.table, t.key, t.before, after));
+            }
+        }
+        if changes.is_empty() {
+            return Ok(());
+        }
+
+        sqlx::query("DELETE FROM op_journal WHERE undone = 1").execute(&mut *tx).await?;
+
+        let op_id = sqlx::query("INSERT INTO op_journal (label, undone, created_at) VALUES (?1, 0, ?2)")
+            .bind(self.label)
+            .bind(Utc::now())
+            .execute(&mut *tx)
+            .await?
+            .last_insert_rowid();
+
+        for (seq, (table, key, before, after)) in changes.iter().enumerate() {
+            sqlx::query(
+                r#"
+                INSERT INTO op_journal_row (op_id, seq, tbl, row_key, before, after)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                "#,
+            )
+            .bind(op_id)
+            .bind(seq as i64)
+            .bind(*table)
+            .bind(Value::Object(key.clone()).to_string())
+            .bind(before.as_ref().map(|b| Value::Object(b.clone()).to_string()))
+            .bind(after.as_ref().map(|a| Value::Object(a.clone()).to_string()))
+            .execute(&mut *tx)
+            .await?;
+            history::record(tx, table, before.as_ref(), after.as_ref()).await?;
+        }
+
+        sqlx::query(
+            r#"
+            DELETE FROM op_journal
+            WHERE id <= (SELECT id FROM op_journal ORDER BY id DESC LIMIT 1 OFFSET ?1)
+            "#,
+        )
+        .bind(HISTORY_LIMIT)
+        .execute(&mut *tx)
+        .await?;
+        Ok(())
+    }
+}
+
+// ---------- Undo / redo ----------
+
+fn summary_from_row(r: &sqlx::sqlite::SqliteRow) -> OpSummary {
+    OpSummary { id: r.get("id"), label: r.get("label"), created_at: r.get("created_at") }
+}
+
+pub async fn undo_state(pool: &SqlitePool) -> Result<UndoState> {
+    let undo = sqlx::query("SELECT id, label, created_at FROM op_journal WHERE undone = 0 ORDER BY id DESC LIMIT 1")
+        .fetch_optional(pool)
+        .await?;
+    let redo = sqlx::query("SELECT id, label, created_at FROM op_journal WHERE undone = 1 ORDER BY id ASC LIMIT 1")
+        .fetch_optional(pool)
+        .await?;
+    Ok(UndoState { undo: undo.as_ref().map(summary_from_row), redo: redo.as_ref().map(summary_from_row) })
+}
+
+/// Revert the newest op. `Ok(None)` when there is nothing to undo.
+pub async fn undo(pool: &SqlitePool) -> Result<Option<OpSummary>> {
+    replay(pool, Direction::Undo).await
+}
+
+/// Re-apply the most recently undone op. `Ok(None)` when there is nothing to redo.
+pub async fn redo(pool: &SqlitePool) -> Result<Option<OpSummary>> {
+    replay(pool, Direction::Redo).await
+}
+
+/// Forget all history, e.g. after the vault was replaced wholesale.
+pub async fn clear(conn: &mut SqliteConnection) -> Result<()> {
+    sqlx::query("DELETE FROM op_journal").execute(conn).await?;
+    Ok(())
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Direction {
+    Undo,
+    Redo,
+}
+
+async fn replay(pool: &SqlitePool, dir: Direction) -> Result<Option<OpSummary>> {
+    let mut tx = begin(pool).await?;
+
+    let pick = match dir {
+        Direction::Undo => "SELECT id, label, created_at FROM op_journal WHERE undone = 0 ORDER BY id DESC LIMIT 1",
+        Direction::Redo => "SELECT id, label, created_at FROM op_journal WHERE undone = 1 ORDER BY id ASC LIMIT 1",
+    };
+    let Some(op) = sqlx::query(pick).fetch_optional(&mut *tx).await? else {
+        return Ok(None);
+    };
+    let op = summary_from_row(&op);
+
+    let order = if dir == Direction::Undo { "DESC" } else { "ASC" };
+    let entries = sqlx::query(&format!(
+        "SELECT tbl, row_key, before, after FROM op_journal_row WHERE op_id = ?1 ORDER BY seq {order}"
+    ))
+    .bind(op.id)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let mut touched = Vec::new();
+    for e in entries {
+        let table: String = e.get("tbl");
+        let key = rows::parse_image(&e.get::<String, _>("row_key"))?;
+        let before = e.get::<Option<String>, _>("before").map(|s| rows::parse_image(&s)).transpose()?;
+        let after = e.get::<Option<String>, _>("after").map(|s| rows::parse_image(&s)).transpose()?;
+        let (expected, target) = match dir {
+            Direction::Undo => (after, before),
+            Direction::Redo => (before, after),
+        };
+
+        // something outside the journal changed the row since; don't clobber it
+        if rows::fetch_image(&mut tx, &table, &key).await? != expected {
+            return Err(AppError::Conflict {
+                entity: None,
+                field: None,
+                message: format!("Cannot {} '{}': the {table} row has changed since", verb(dir), op.label),
+            });
+        }
+        // rows outside this op that still point at one it deletes would be
+        // cascaded away or cut loose, with nothing to bring them back
+        if let (Some(current), None) = (&expected, &target) {
+            let users = rows::referenced_by(&mut tx, &table, current).await?;
+            if !users.is_empty() {
+                return Err(AppError::Conflict {
+                    entity: None,
+                    field: None,
+                    message: format!("Cannot {} '{}': {} rows still point at this {table} row", verb(dir), op.label, users.join(", ")),
+                });
+            }
+        }
+        rows::write_image(&mut tx, &table, &key, target.as_ref()).await?;
+        history::record(&mut tx, &table, expected.as_ref(), target.as_ref()).await?;
+        touched.push((table, key, expected, target));
+    }
+
+    sqlx::query("UPDATE op_journal SET undone = ?1 WHERE id = ?2")
+        .bind(dir == Direction::Undo)
+        .bind(op.id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    for (table, key, from, to) in &touched {
+        announce(table, key, from.as_ref(), to.as_ref());
+    }
+    Ok(Some(op))
+}
+
+fn verb(dir: Direction) -> &'static str {
+    match dir {
+        Direction::Undo => "undo",
+        Direction::Redo => "redo",
+    }
+}
+
+/// Publish the change a replayed row amounts to, on its owning entity.
+fn announce(table: &str, key: &Image, from: Option<&Image>, to: Option<&Image>) {
+    let field = |name: &str| {
+        to.or(from)
+            .and_then(|img| img.get(name))
+            .or_else(|| key.get(name))
+            .and_then(Value::as_str)
+            .map(str::to_string)
+    };
+    let own_op = match (from, to) {
+        (None, Some(_)) => ChangeOp::Created,
+        (Some(_), None) => ChangeOp::Deleted,
+        _ => ChangeOp::Updated,
+    };
+
+    let (kind, id, op) = match table {
+        "tasks" => (EntityKind::Task, field("id"), own_op),
+        "status_reasons" | "task_stages" | "time_entry" | "task_dependency" => {
+            (EntityKind::Task, field("task_id"), ChangeOp::Updated)
+        }
+        "idea" => (EntityKind::Idea, field("id"), own_op),
+        "idea_note" | "idea_link" | "idea_attachment" | "idea_tag" | "idea_task_map" | "idea_doc_map" => {
+            (EntityKind::Idea, field("idea_id"), ChangeOp::Updated)
+        }
+        "doc" => (EntityKind::Doc, field("id"), own_op),
+        "doc_version" | "doc_attachment" | "doc_tag" => (EntityKind::Doc, field("doc_id"), ChangeOp::Updated),
+        "project" => (EntityKind::Project, field("id"), own_op),
+        "workspace" => (EntityKind::Workspace, field("id"), own_op),
+        _ => return,
+    };
+    if let Some(id) = id {
+        events::publish(kind, id, op);
+    }
+    if table == "task_dependency" {
+        if let Some(other) = field("depends_on") {
+            events::publish(EntityKind::Task, other, ChangeOp::Updated);
+        }
+    }
+    if table == "idea_doc_map" {
+        if let Some(doc) = field("doc_id") {
+            events::publish(EntityKind::Doc, doc, ChangeOp::Updated);
+        }
+    }
+}
@@ -0,0 +1,28 @@
+// src/services/markdown.rs
+//! Markdown to HTML, shared by docs (`body_html`) and report exports.
+use pulldown_cmark::{html, Event, Options, Parser};
+
+/// CommonMark plus tables, strikethrough and task lists. Raw HTML in the
+/// source comes out escaped, as text, so a doc can't carry a `<script>`.
+pub fn to_html(md: &str) -> String {
+    let opts = Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+    let events = Parser::new_ext(md, opts).map(|e| match e {
+        Event::Html(raw) | Event::InlineHtml(raw) => Event::Text(raw),
+        e => e,
+    });
+    let mut out = String::with_capacity(md.len() * 3 / 2);
+    html::push_html(&mut out, events);
+    out
+}
+
+/// Escape text so it reads literally in Markdown, table cells included.
+pub fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' | '|' | '!') {
+            out.push('\\');
+        }
+        out.push(if c == '\n' { ' ' } else { c });
+    }
+    out
+}
@@ -0,0 +1,113 @@
+// src/services/metrics.rs
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqlitePool};
+
+use crate::services::time::ENTRY_MINUTES;
+use crate::Result;
+
+pub async fn count_tasks_by_project(pool: &SqlitePool, project_id: &str) -> Result<i64> {
+    let n: i64 = sqlx::query_scalar("SELECT COUNT(1) FROM tasks WHERE project_id = ?1 AND deleted_at IS NULL")
+        .bind(project_id)
+        .fetch_one(pool)
+        .await?;
+    Ok(n)
+}
+
+pub async fn count_ideas_by_project(pool: &SqlitePool, project_id: &str) -> Result<i64> {
+    let n: i64 = sqlx::query_scalar("SELECT COUNT(1) FROM idea WHERE project_id = ?1 AND deleted_at IS NULL")
+        .bind(project_id)
+        .fetch_one(pool)
+        .await?;
+    Ok(n)
+}
+
+pub async fn count_docs_by_project(pool: &SqlitePool, project_id: &str) -> Result<i64> {
+    let n: i64 = sqlx::query_scalar("SELECT COUNT(1) FROM doc WHERE project_id = ?1 AND deleted_at IS NULL")
+        .bind(project_id)
+        .fetch_one(pool)
+        .await?;
+    Ok(n)
+}
+
+// ---------- Estimate accuracy ----------
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AccuracyGroup {
+    Category,
+    Project,
+}
+
+/// How estimates held up for one category or project.
+#[derive(Debug, Serialize)]
+pub struct EstimateAccuracy {
+    pub key: Option<String>, // category, or project id (None for unfiled tasks)
+    pub label: String,
+    pub tasks: i64,
+    pub estimate_minutes: i64,
+    pub actual_minutes: i64,
+    pub ratio: f64, // actual / estimate, 2 decimals; above 1 means underestimated
+}
+
+/// Completed tasks with an estimate, grouped, most underestimated first.
+/// Each task counts its own estimate against the time logged on it directly,
+/// so a parent and its subtasks are never double-counted. A task's
+/// completion time is its last transition to `completed`; `from`/`to`
+/// (inclusive/exclusive) filter on that.
+pub async fn estimate_accuracy(
+    pool: &SqlitePool,
+    group: AccuracyGroup,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) -> Result<Vec<EstimateAccuracy>> {
+    let (key, label) = match group {
+        AccuracyGroup::Category => ("d.category", "d.category"),
+        AccuracyGroup::Project => ("d.project_id", "COALESCE(w.name || '/' || p.name, '(no project)')"),
+    };
+    let rows = sqlx::query(&format!(
+        r#"
+        WITH d AS (
+          SELECT t.category, t.project_id, t.estimate_minutes,
+                 COALESCE(
+                   (SELECT MAX(r.created_at) FROM status_reasons r
+                    WHERE r.task_id = t.id AND r.status = 'completed' AND r.from_status IS NOT NULL),
+                   t.updated_at
+                 ) AS completed_at,
+                 (SELECT COALESCE(SUM({ENTRY_MINUTES}), 0.0) FROM time_entry e
+                  WHERE e.task_id = t.id AND e.ended_at IS NOT NULL) AS actual
+          FROM tasks t
+          WHERE t.status = 'completed' AND t.deleted_at IS NULL AND t.estimate_minutes > 0
+        )
+        SELECT {key} AS key, {label} AS label, COUNT(*) AS tasks,
+               SUM(d.estimate_minutes) AS estimate, SUM(d.actual) AS actual
+        FROM d
+        LEFT JOIN project p ON p.id = d.project_id
+        LEFT JOIN workspace w ON w.id = p.workspace_id
+        WHERE (?1 IS NULL OR datetime(d.completed_at) >= datetime(?1))
+          AND (?2 IS NULL OR datetime(d.completed_at) < datetime(?2))
+        GROUP BY {key}
+        "#
+    ))
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool)
+    .await?;
+
+    let mut out: Vec<EstimateAccuracy> = rows
+        .iter()
+        .map(|r| {
+            let (estimate, actual): (i64, f64) = (r.get("estimate"), r.get("actual"));
+            EstimateAccuracy {
+                key: r.get("key"),
+                label: r.get("label"),
+                tasks: r.get("tasks"),
+                estimate_minutes: estimate,
+                actual_minutes: actual.round() as i64,
+                ratio: (actual / estimate as f64 * 100.0).round() / 100.0,
+            }
+        })
+        .collect();
+    out.sort_by(|a, b| b.ratio.total_cmp(&a.ratio));
+    Ok(out)
+}
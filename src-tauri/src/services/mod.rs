@@ -0,0 +1,26 @@
+//! Business logic over a plain `SqlitePool`, shared by the Tauri commands
+//! and anything else that needs it without a running app.
+
+pub mod tasks;
+pub mod stages;
+pub mod subtasks;
+pub mod recurrence;
+pub mod rrule;
+pub mod dependencies;
+pub mod time;
+pub mod timer;
+pub mod reminders;
+pub mod ideas;
+pub mod docs;
+pub mod markdown;
+pub mod projects;
+pub mod metrics;
+pub mod reports;
+pub mod report_export;
+pub mod settings;
+pub mod backup;
+pub mod vault;
+pub mod rows;
+pub mod journal;
+pub mod history;
+pub mod trash;
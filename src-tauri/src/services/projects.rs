@@ -0,0 +1,453 @@
+// src/services/projects.rs
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{sqlite::SqliteRow, Row, SqlitePool};
+use uuid::Uuid;
+
+use crate::events::{self, ChangeOp, EntityKind};
+use crate::services::journal::{self, Recorder};
+use crate::services::trash;
+use crate::{db, AppError, Result};
+
+// ---------- Types returned to the UI ----------
+
+#[derive(Debug, Serialize)]
+pub struct WorkspaceRow {
+    pub id: String,
+    pub name: String,
+    pub kind: String, // personal | work | custom
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProjectRow {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub status: String, // active | paused | archived
+    pub workspace_id: String,
+    pub workspace_name: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// ---------- Inputs from the UI ----------
+
+#[derive(Deserialize)]
+pub struct CreateWorkspaceInput {
+    pub name: String,
+    #[serde(default = "default_kind")]
+    pub kind: String, // personal | work | custom
+}
+fn default_kind() -> String { "custom".into() }
+
+#[derive(Deserialize)]
+pub struct UpdateWorkspaceInput {
+    pub id: String,
+    pub name: Option<String>,
+    pub kind: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct CreateProjectInput {
+    pub workspace_id: String,
+    pub name: String,
+    pub description: Option<String>,
+    #[serde(default = "default_status")]
+    pub status: String, // active | paused | archived
+}
+fn default_status() -> String { "active".into() }
+
+#[derive(Deserialize)]
+pub struct UpdateProjectInput {
+    pub id: String,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub status: Option<String>, // active | paused | archived
+}
+
+#[derive(Deserialize)]
+pub struct MoveProjectInput {
+    pub id: String,
+    pub workspace_id: String,
+}
+
+fn validate_status(status: &str) -> Result<()> {
+    if !matches!(status, "active" | "paused" | "archived") {
+        return Err(AppError::validation("status", "Project status must be active, paused or archived"));
+    }
+    Ok(())
+}
+
+// ---------- Commands ----------
+
+pub async fn list_workspaces(pool: &SqlitePool) -> Result<Vec<WorkspaceRow>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT id, name, kind, created_at, updated_at
+        FROM workspace
+        ORDER BY
+          CASE kind WHEN 'personal' THEN 0 WHEN 'work' THEN 1 ELSE 2 END,
+          name
+        "#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|r| WorkspaceRow {
+            id: r.get("id"),
+            name: r.get("name"),
+            kind: r.get("kind"),
+            created_at: r.get("created_at"),
+            updated_at: r.get("updated_at"),
+        })
+        .collect())
+}
+
+pub async fn create_workspace(pool: &SqlitePool, input: CreateWorkspaceInput) -> Result<String> {
+    let now = Utc::now();
+
+    let name = input.name.trim();
+    if name.is_empty() {
+        return Err(AppError::validation("name", "Workspace name is required"));
+    }
+
+    // pick a stable id for common cases, otherwise UUID
+    let id = if input.kind == "personal" && name.eq_ignore_ascii_case("personal") {
+        "ws_personal".to_string()
+    } else if input.kind == "work" && name.eq_ignore_ascii_case("work") {
+        "ws_work".to_string()
+    } else {
+        format!("ws_{}", Uuid::new_v4())
+    };
+
+    // INSERT OR IGNORE so re-creating the same one doesn't blow up
+    let mut tx = journal::begin(pool).await?;
+    let mut op = Recorder::new("Add workspace");
+    op.track(&mut tx, "workspace", &id).await?;
+
+    sqlx::query(
+        r#"
+        INSERT OR IGNORE INTO workspace (id, name, kind, created_at, updated_at)
+        VALUES (?1, ?2, ?3, ?4, ?5)
+        "#
+    )
+    .bind(&id)
+    .bind(name)
+    .bind(&input.kind)
+    .bind(now)
+    .bind(now)
+    .execute(&mut *tx)
+    .await?;
+
+    op.commit(&mut tx).await?;
+    tx.commit().await?;
+    events::publish(EntityKind::Workspace, &id, ChangeOp::Created);
+
+    Ok(id)
+}
+
+/// Projects, optionally in one workspace. Archived ones only when asked for.
+pub async fn list_projects(
+    pool: &SqlitePool,
+    workspace_id: Option<&str>,
+    include_archived: bool,
+) -> Result<Vec<ProjectRow>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT p.id, p.name, p.description, p.status, p.created_at, p.updated_at,
+               w.id AS workspace_id, w.name AS workspace_name
+        FROM project p
+        JOIN workspace w ON w.id = p.workspace_id
+        WHERE p.deleted_at IS NULL
+          AND (?1 IS NULL OR p.workspace_id = ?1)
+          AND (?2 OR p.status <> 'archived')
+        ORDER BY w.name, p.name
+        "#
+    )
+    .bind(workspace_id)
+    .bind(include_archived)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(project_from_row).collect())
+}
+
+fn project_from_row(r: &SqliteRow) -> ProjectRow {
+    ProjectRow {
+        id: r.get("id"),
+        name: r.get("name"),
+        description: r.get::<Option<String>, _>("description"),
+        status: r.get("status"),
+        workspace_id: r.get("workspace_id"),
+        workspace_name: r.get("workspace_name"),
+        created_at: r.get("created_at"),
+        updated_at: r.get("updated_at"),
+    }
+}
+
+/// Resolve a project by id, by `Workspace/Project` name path, or by a bare
+/// project name when it is unique. Names match case-insensitively.
+pub async fn find_project(pool: &SqlitePool, spec: &str) -> Result<ProjectRow> {
+    let (ws_name, prj_name) = match spec.split_once('/') {
+        Some((ws, prj)) => (Some(ws.trim()), prj.trim()),
+        None => (None, spec.trim()),
+    };
+
+    let rows = sqlx::query(
+        r#"
+        SELECT p.id, p.name, p.description, p.status, p.created_at, p.updated_at,
+               w.id AS workspace_id, w.name AS workspace_name
+        FROM project p
+        JOIN workspace w ON w.id = p.workspace_id
+        WHERE p.deleted_at IS NULL
+          AND (p.id = ?1
+           OR (p.name = ?2 COLLATE NOCASE AND (?3 IS NULL OR w.name = ?3 COLLATE NOCASE)))
+        ORDER BY p.id = ?1 DESC, w.name, p.name
+        "#
+    )
+    .bind(spec)
+    .bind(prj_name)
+    .bind(ws_name)
+    .fetch_all(pool)
+    .await?;
+
+    match rows.as_slice() {
+        [] => Err(AppError::not_found("project", spec)),
+        [only] => Ok(project_from_row(only)),
+        [first, ..] if first.get::<String, _>("id") == spec => Ok(project_from_row(first)),
+        _ => Err(AppError::validation(
+            "project",
+            format!("'{spec}' matches several projects; use Workspace/Project"),
+        )),
+    }
+}
+
+pub async fn create_project(pool: &SqlitePool, input: CreateProjectInput) -> Result<String> {
+    let now = Utc::now();
+
+    let name = input.name.trim();
+    if name.is_empty() {
+        return Err(AppError::validation("name", "Project name is required"));
+    }
+
+    validate_status(&input.status)?;
+
+    // validate FK exists (nicer error than FK panic)
+    if !db::row_exists(pool, "workspace", &input.workspace_id).await? {
+        return Err(AppError::foreign_key("workspace", "workspace_id", &input.workspace_id));
+    }
+
+    let id = format!("prj_{}", Uuid::new_v4());
+
+    let mut tx = journal::begin(pool).await?;
+    let mut op = Recorder::new("Add project");
+    op.track(&mut tx, "project", &id).await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO project (id, workspace_id, name, description, status, created_at, updated_at)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+        "#
+    )
+    .bind(&id)
+    .bind(&input.workspace_id)
+    .bind(name)
+    .bind(&input.description)
+    .bind(&input.status)
+    .bind(now)
+    .bind(now)
+    .execute(&mut *tx)
+    .await?;
+
+    op.commit(&mut tx).await?;
+    tx.commit().await?;
+    events::publish(EntityKind::Project, &id, ChangeOp::Created);
+
+    Ok(id)
+}
+
+pub async fn update_workspace(pool: &SqlitePool, input: UpdateWorkspaceInput) -> Result<()> {
+    let cur = sqlx::query("SELECT name, kind FROM workspace WHERE id = ?1")
+        .bind(&input.id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::not_found("workspace", &input.id))?;
+
+    let name = input.name.unwrap_or_else(|| cur.get("name"));
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::validation("name", "Workspace name is required"));
+    }
+    let kind = input.kind.unwrap_or_else(|| cur.get("kind"));
+    if !matches!(kind.as_str(), "personal" | "work" | "custom") {
+        return Err(AppError::validation("kind", "Workspace kind must be personal, work or custom"));
+    }
+
+    let mut tx = journal::begin(pool).await?;
+    let mut op = Recorder::new("Edit workspace");
+    op.track(&mut tx, "workspace", &input.id).await?;
+
+    sqlx::query("UPDATE workspace SET name = ?1, kind = ?2, updated_at = ?3 WHERE id = ?4")
+        .bind(name)
+        .bind(&kind)
+        .bind(Utc::now())
+        .bind(&input.id)
+        .execute(&mut *tx)
+        .await?;
+
+    op.commit(&mut tx).await?;
+    tx.commit().await?;
+    events::publish(EntityKind::Workspace, &input.id, ChangeOp::Updated);
+
+    Ok(())
+}
+
+pub async fn update_project(pool: &SqlitePool, input: UpdateProjectInput) -> Result<()> {
+    let cur = sqlx::query("SELECT name, description, status FROM project WHERE id = ?1 AND deleted_at IS NULL")
+        .bind(&input.id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::not_found("project", &input.id))?;
+
+    let name = input.name.unwrap_or_else(|| cur.get("name"));
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::validation("name", "Project name is required"));
+    }
+    let description: Option<String> = input.description.or(cur.get("description"));
+    let status = input.status.unwrap_or_else(|| cur.get("status"));
+    validate_status(&status)?;
+
+    let mut tx = journal::begin(pool).await?;
+    let mut op = Recorder::new("Edit project");
+    op.track(&mut tx, "project", &input.id).await?;
+
+    sqlx::query("UPDATE project SET name = ?1, description = ?2, status = ?3, updated_at = ?4 WHERE id = ?5")
+        .bind(name)
+        .bind(&description)
+        .bind(&status)
+        .bind(Utc::now())
+        .bind(&input.id)
+        .execute(&mut *tx)
+        .await?;
+
+    op.commit(&mut tx).await?;
+    tx.commit().await?;
+    events::publish(EntityKind::Project, &input.id, ChangeOp::Updated);
+
+    Ok(())
+}
+
+/// Archive a project: hidden from default listings, its contents read-only.
+pub async fn archive_project(pool: &SqlitePool, id: &str) -> Result<()> {
+    set_project_status(pool, id, "archived", "Archive project").await
+}
+
+/// Bring an archived project back as active.
+pub async fn unarchive_project(pool: &SqlitePool, id: &str) -> Result<()> {
+    set_project_status(pool, id, "active", "Unarchive project").await
+}
+
+async fn set_project_status(pool: &SqlitePool, id: &str, status: &str, label: &'static str) -> Result<()> {
+    let mut tx = journal::begin(pool).await?;
+    let mut op = Recorder::new(label);
+    op.track(&mut tx, "project", id).await?;
+
+    let res = sqlx::query("UPDATE project SET status = ?1, updated_at = ?2 WHERE id = ?3 AND deleted_at IS NULL")
+        .bind(status)
+        .bind(Utc::now())
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+    if res.rows_affected() == 0 {
+        return Err(AppError::not_found("project", id));
+    }
+
+    op.commit(&mut tx).await?;
+    tx.commit().await?;
+    events::publish(EntityKind::Project, id, ChangeOp::Updated);
+
+    Ok(())
+}
+
+/// Move a project to another workspace. Its tasks' `workspace_id` follows.
+pub async fn move_project(pool: &SqlitePool, input: MoveProjectInput) -> Result<()> {
+    let live: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM project WHERE id = ?1 AND deleted_at IS NULL)")
+        .bind(&input.id)
+        .fetch_one(pool)
+        .await?;
+    if !live {
+        return Err(AppError::not_found("project", &input.id));
+    }
+    if !db::row_exists(pool, "workspace", &input.workspace_id).await? {
+        return Err(AppError::foreign_key("workspace", "workspace_id", &input.workspace_id));
+    }
+    ensure_project_writable(pool, &input.id).await?;
+
+    let mut tx = journal::begin(pool).await?;
+    let task_ids: Vec<String> = sqlx::query_scalar("SELECT id FROM tasks WHERE project_id = ?1")
+        .bind(&input.id)
+        .fetch_all(&mut *tx)
+        .await?;
+    let mut op = Recorder::new("Move project");
+    op.track(&mut tx, "project", &input.id).await?;
+    for t in &task_ids {
+        op.track(&mut tx, "tasks", t).await?;
+    }
+
+    let now = Utc::now();
+    sqlx::query("UPDATE project SET workspace_id = ?1, updated_at = ?2 WHERE id = ?3")
+        .bind(&input.workspace_id)
+        .bind(now)
+        .bind(&input.id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("UPDATE tasks SET workspace_id = ?1 WHERE project_id = ?2")
+        .bind(&input.workspace_id)
+        .bind(&input.id)
+        .execute(&mut *tx)
+        .await?;
+
+    op.commit(&mut tx).await?;
+    tx.commit().await?;
+    events::publish(EntityKind::Project, &input.id, ChangeOp::Updated);
+    for t in task_ids {
+        events::publish(EntityKind::Task, t, ChangeOp::Updated);
+    }
+
+    Ok(())
+}
+
+/// Refuse writes to a project's contents while it is archived.
+pub async fn ensure_project_writable(pool: &SqlitePool, project_id: &str) -> Result<()> {
+    let status: Option<String> = sqlx::query_scalar("SELECT status FROM project WHERE id = ?1")
+        .bind(project_id)
+        .fetch_optional(pool)
+        .await?;
+    if status.as_deref() == Some("archived") {
+        return Err(AppError::conflict("project", "status", "Project is archived; unarchive it to make changes"));
+    }
+    Ok(())
+}
+
+/// [`ensure_project_writable`] for the project owning row `id` of `table`
+/// (`tasks`, `idea` or `doc`). Missing rows pass; the caller reports those.
+pub async fn ensure_writable(pool: &SqlitePool, table: &str, id: &str) -> Result<()> {
+    let project: Option<Option<String>> = sqlx::query_scalar(&format!("SELECT project_id FROM {table} WHERE id = ?1"))
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+    match project.flatten() {
+        Some(p) => ensure_project_writable(pool, &p).await,
+        None => Ok(()),
+    }
+}
+
+/// Move a project to the Trash; see `services::trash` for restore and purge.
+pub async fn delete_project(pool: &SqlitePool, id: &str) -> Result<()> {
+    trash::delete(pool, EntityKind::Project, id).await
+}
@@ -0,0 +1,636 @@
+// src/services/recurrence.rs
+//! Recurring tasks. A `task_series` row holds the rule (see
+//! `services::rrule`) and the fields new occurrences start from; each
+//! occurrence is an ordinary task with `series_id` and `occurrence` set.
+//!
+//! Only the next occurrence exists up front. Completing or dropping it
+//! schedules the one after on the rule's next date, keeping the task's
+//! start-to-due span. Edits go to "this" occurrence only, or to "this and
+//! following": that also updates the series and any later open occurrences,
+//! and moving a later occurrence in time splits the series there.
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Executor, Row, Sqlite, SqliteConnection, SqlitePool};
+use uuid::Uuid;
+
+use crate::events::{self, ChangeOp, EntityKind};
+use crate::services::journal::{self, Recorder};
+use crate::services::projects;
+use crate::services::rrule::RRule;
+use crate::services::subtasks;
+use crate::services::tasks::{self, StatusChange, TaskEdit, TaskStatus, UpdateTaskInput};
+use crate::{AppError, Result};
+
+/// Most dates `preview_recurrence` returns.
+const MAX_PREVIEW: usize = 100;
+
+const SERIES_COLUMNS: &str = r#"
+    id, rrule, timezone, dtstart, anchor, last_occurrence,
+    name, category, short_summary, description, estimate_minutes
+"#;
+
+// ---------- Types ----------
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct RecurrenceInput {
+    pub rrule: String,    // RRULE value, e.g. "FREQ=WEEKLY;BYDAY=MO"
+    pub timezone: String, // IANA name the rule expands in
+}
+
+/// `TaskDetail.recurrence` of an occurrence.
+#[derive(Debug, Serialize)]
+pub struct Recurrence {
+    pub series_id: String,
+    pub rrule: String,
+    pub timezone: String,
+    pub occurrence: i64,                 // 1-based
+    pub next_at: Option<DateTime<Utc>>, // when the next one falls; None once the series ends
+}
+
+/// Which occurrences an edit applies to.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SeriesScope {
+    #[default]
+    This,
+    Following,
+}
+
+/// The task field a series' dates land on: start_at, or end_est_at for
+/// tasks that only have a due date.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Anchor {
+    Start,
+    Due,
+}
+
+impl Anchor {
+    fn as_str(self) -> &'static str {
+        match self {
+            Anchor::Start => "start",
+            Anchor::Due => "due",
+        }
+    }
+}
+
+/// A checked rule, ready to start a series with.
+pub(crate) struct Plan {
+    rule: RRule,
+    tz: Tz,
+    anchor: Anchor,
+    dtstart: DateTime<Utc>,
+}
+
+/// What each new occurrence starts from.
+pub(crate) struct Template {
+    pub name: String,
+    pub category: String,
+    pub short_summary: Option<String>,
+    pub description: Option<String>,
+    pub estimate_minutes: Option<i64>,
+}
+
+struct Series {
+    id: String,
+    rule: RRule,
+    tz: Tz,
+    dtstart: DateTime<Utc>,
+    anchor: Anchor,
+    last_occurrence: Option<i64>,
+    template: Template,
+}
+
+impl Series {
+    fn from_row(row: &SqliteRow) -> Result<Series> {
+        let tz: String = row.get("timezone");
+        Ok(Series {
+            id: row.get("id"),
+            rule: row.get::<String, _>("rrule").parse()?,
+            tz: tz.parse().map_err(|_| AppError::Other(format!("series time zone {tz}")))?,
+            dtstart: row.get("dtstart"),
+            anchor: if row.get::<String, _>("anchor") == "due" { Anchor::Due } else { Anchor::Start },
+            last_occurrence: row.get("last_occurrence"),
+            template: Template {
+                name: row.get("name"),
+                category: row.get("category"),
+                short_summary: row.get("short_summary"),
+                description: row.get("description"),
+                estimate_minutes: row.get("estimate_minutes"),
+            },
+        })
+    }
+
+    /// Date of occurrence `n`, or None past the end of the series.
+    fn nth(&self, n: i64) -> Option<DateTime<Utc>> {
+        if self.last_occurrence.is_some_and(|last| n > last) {
+            return None;
+        }
+        self.rule.nth(self.tz, self.dtstart, u32::try_from(n).ok()?)
+    }
+
+    /// Start and due of an occurrence on `at`, keeping the span of a task
+    /// that had `start`/`end` when it was on `was`.
+    fn place(
+        &self,
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+        was: DateTime<Utc>,
+        at: DateTime<Utc>,
+    ) -> (Option<DateTime<Utc>>, Option<DateTime<Utc>>) {
+        match self.anchor {
+            Anchor::Start => (Some(at), end.map(|e| at + (e - start.unwrap_or(was)))),
+            Anchor::Due => (start.map(|s| at - (end.unwrap_or(was) - s)), Some(at)),
+        }
+    }
+}
+
+// ---------- Helpers ----------
+
+fn parse_rule(input: &RecurrenceInput) -> Result<(RRule, Tz)> {
+    let rule: RRule = input.rrule.parse()?;
+    let tz: Tz = input
+        .timezone
+        .parse()
+        .map_err(|_| AppError::validation("timezone", format!("Unknown time zone {}", input.timezone)))?;
+    Ok((rule, tz))
+}
+
+/// Check a rule for a task with these dates; the first occurrence is the task.
+pub(crate) fn plan(input: &RecurrenceInput, start_at: Option<DateTime<Utc>>, end_est_at: Option<DateTime<Utc>>) -> Result<Plan> {
+    let (rule, tz) = parse_rule(input)?;
+    let (anchor, dtstart) = anchor_at(start_at, end_est_at)?;
+    Ok(Plan { rule, tz, anchor, dtstart })
+}
+
+fn anchor_at(start_at: Option<DateTime<Utc>>, end_est_at: Option<DateTime<Utc>>) -> Result<(Anchor, DateTime<Utc>)> {
+    match (start_at, end_est_at) {
+        (Some(s), _) => Ok((Anchor::Start, s)),
+        (None, Some(e)) => Ok((Anchor::Due, e)),
+        (None, None) => Err(AppError::validation("recurrence", "A repeating task needs a start or due date")),
+    }
+}
+
+/// Insert a series, inside the caller's transaction.
+pub(crate) async fn insert_series(conn: &mut SqliteConnection, id: &str, plan: &Plan, template: &Template) -> Result<()> {
+    let now = Utc::now();
+    sqlx::query(
+        r#"
+        INSERT INTO task_series (
+          id, rrule, timezone, dtstart, anchor, name, category, short_summary, description,
+          estimate_minutes, created_at, updated_at
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?11)
+        "#,
+    )
+    .bind(id)
+    .bind(plan.rule.to_string())
+    .bind(plan.tz.name())
+    .bind(plan.dtstart)
+    .bind(plan.anchor.as_str())
+    .bind(&template.name)
+    .bind(&template.category)
+    .bind(&template.short_summary)
+    .bind(&template.description)
+    .bind(template.estimate_minutes)
+    .bind(now)
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+async fn load_series<'e>(conn: impl Executor<'e, Database = Sqlite>, id: &str) -> Result<Series> {
+    let row = sqlx::query(&format!("SELECT {SERIES_COLUMNS} FROM task_series WHERE id = ?1"))
+        .bind(id)
+        .fetch_optional(conn)
+        .await?
+        .ok_or_else(|| AppError::not_found("series", id))?;
+    Series::from_row(&row)
+}
+
+/// The occurrence's series and number, if it is one.
+async fn occurrence_of(conn: &mut SqliteConnection, task_id: &str) -> Result<Option<(Series, i64)>> {
+    let row: Option<(Option<String>, Option<i64>)> =
+        sqlx::query_as("SELECT series_id, occurrence FROM tasks WHERE id = ?1")
+            .bind(task_id)
+            .fetch_optional(&mut *conn)
+            .await?;
+    let Some(row) = row else { return Err(AppError::not_found("task", task_id)) };
+    match row {
+        (Some(series), Some(n)) => Ok(Some((load_series(conn, &series).await?, n))),
+        _ => Ok(None),
+    }
+}
+
+/// Ids of a series' occurrences numbered `from` and up.
+async fn occurrences_from(conn: &mut SqliteConnection, series_id: &str, from: i64) -> Result<Vec<String>> {
+    Ok(sqlx::query_scalar("SELECT id FROM tasks WHERE series_id = ?1 AND occurrence >= ?2 ORDER BY occurrence")
+        .bind(series_id)
+        .bind(from)
+        .fetch_all(conn)
+        .await?)
+}
+
+/// End `old` before occurrence `n` and move `n` and later into `new`,
+/// renumbered from 1.
+async fn split(conn: &mut SqliteConnection, old: &str, n: i64, new: &str) -> Result<()> {
+    sqlx::query("UPDATE task_series SET last_occurrence = ?2, updated_at = ?3 WHERE id = ?1")
+        .bind(old)
+        .bind(n - 1)
+        .bind(Utc::now())
+        .execute(&mut *conn)
+        .await?;
+    sqlx::query("UPDATE tasks SET series_id = ?3, occurrence = occurrence - ?2 + 1 WHERE series_id = ?1 AND occurrence >= ?2")
+        .bind(old)
+        .bind(n)
+        .bind(new)
+        .execute(&mut *conn)
+        .await?;
+    Ok(())
+}
+
+/// Put the open occurrences after the first back on the rule's dates.
+async fn retime(conn: &mut SqliteConnection, series: &Series) -> Result<()> {
+    let rows = sqlx::query(
+        r#"
+        SELECT id, occurrence, start_at, end_est_at FROM tasks
+        WHERE series_id = ?1 AND occurrence > 1 AND status NOT IN ('completed', 'dropped')
+        "#,
+    )
+    .bind(&series.id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    for row in rows {
+        let Some(at) = series.nth(row.get("occurrence")) else { continue };
+        let (start, end): (Option<DateTime<Utc>>, Option<DateTime<Utc>>) = (row.get("start_at"), row.get("end_est_at"));
+        let was = match series.anchor {
+            Anchor::Start => start,
+            Anchor::Due => end,
+        };
+        let (start_at, end_est_at) = series.place(start, end, was.unwrap_or(at), at);
+        sqlx::query("UPDATE tasks SET start_at = ?2, end_est_at = ?3, updated_at = ?4 WHERE id = ?1")
+            .bind(row.get::<String, _>("id"))
+            .bind(start_at)
+            .bind(end_est_at)
+            .bind(Utc::now())
+            .execute(&mut *conn)
+            .await?;
+    }
+    Ok(())
+}
+
+// ---------- Queries ----------
+
+/// Recurrence of a task, or None when it doesn't repeat.
+pub async fn task_recurrence(pool: &SqlitePool, task_id: &str) -> Result<Option<Recurrence>> {
+    Ok(occurrence_of(&mut *pool.acquire().await?, task_id).await?.map(|(series, n)| Recurrence {
+        next_at: series.nth(n + 1),
+        rrule: series.rule.to_string(),
+        timezone: series.tz.name().to_string(),
+        series_id: series.id,
+        occurrence: n,
+    }))
+}
+
+/// The first `limit` dates of a rule starting at `dtstart`, to show before
+/// saving it.
+pub fn preview_recurrence(input: &RecurrenceInput, dtstart: DateTime<Utc>, limit: usize) -> Result<Vec<DateTime<Utc>>> {
+    let (rule, tz) = parse_rule(input)?;
+    Ok(rule.occurrences(tz, dtstart).take(limit.min(MAX_PREVIEW)).collect())
+}
+
+// ---------- Scheduling ----------
+
+/// After occurrence `task_id` is finished, create the next one from the
+/// series, with the finished task's project and stages, on the caller's
+/// transaction and op. Does nothing when the task doesn't repeat, the series
+/// has ended, or the next one exists. The caller publishes the new task.
+pub(crate) async fn schedule_next(conn: &mut SqliteConnection, op: &mut Recorder, task_id: &str) -> Result<Option<String>> {
+    let Some((series, n)) = occurrence_of(conn, task_id).await? else { return Ok(None) };
+    let taken: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM tasks WHERE series_id = ?1 AND occurrence = ?2)")
+        .bind(&series.id)
+        .bind(n + 1)
+        .fetch_one(&mut *conn)
+        .await?;
+    let Some(at) = series.nth(n + 1).filter(|_| !taken) else { return Ok(None) };
+
+    let row = sqlx::query("SELECT start_at, end_est_at, project_id, workspace_id, parent_id FROM tasks WHERE id = ?1")
+        .bind(task_id)
+        .fetch_one(&mut *conn)
+        .await?;
+    let was = series.nth(n).unwrap_or(at);
+    let (start_at, end_est_at) = series.place(row.get("start_at"), row.get("end_est_at"), was, at);
+    let parent: Option<String> = row.get("parent_id");
+    let ord = match &parent {
+        Some(p) => subtasks::placement(&mut *conn, p).await?.2,
+        None => 0,
+    };
+    let stages: Vec<(String, Option<String>, i64)> =
+        sqlx::query_as("SELECT name, summary, ord FROM task_stages WHERE task_id = ?1 ORDER BY ord")
+            .bind(task_id)
+            .fetch_all(&mut *conn)
+            .await?;
+
+    let id = Uuid::new_v4().to_string();
+    let stage_ids: Vec<String> = stages.iter().map(|_| Uuid::new_v4().to_string()).collect();
+    op.track(conn, "tasks", &id).await?;
+    for stage_id in &stage_ids {
+        op.track(conn, "task_stages", stage_id).await?;
+    }
+
+    let now = Utc::now();
+    let t = &series.template;
+    sqlx::query(
+        r#"
+        INSERT INTO tasks (
+          id, name, category, short_summary, description, status, start_at, end_est_at,
+          created_at, updated_at, project_id, workspace_id, parent_id, ord, estimate_minutes,
+          series_id, occurrence
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)
+        "#,
+    )
+    .bind(&id)
+    .bind(&t.name)
+    .bind(&t.category)
+    .bind(&t.short_summary)
+    .bind(&t.description)
+    .bind(TaskStatus::Todo.to_string())
+    .bind(start_at)
+    .bind(end_est_at)
+    .bind(now)
+    .bind(row.get::<Option<String>, _>("project_id"))
+    .bind(row.get::<Option<String>, _>("workspace_id"))
+    .bind(&parent)
+    .bind(ord)
+    .bind(t.estimate_minutes)
+    .bind(&series.id)
+    .bind(n + 1)
+    .execute(&mut *conn)
+    .await?;
+    for ((name, summary, ord), stage_id) in stages.iter().zip(&stage_ids) {
+        sqlx::query("INSERT INTO task_stages (id, task_id, name, summary, ord) VALUES (?1, ?2, ?3, ?4, ?5)")
+            .bind(stage_id)
+            .bind(&id)
+            .bind(name)
+            .bind(summary)
+            .bind(ord)
+            .execute(&mut *conn)
+            .await?;
+    }
+
+    Ok(Some(id))
+}
+
+// ---------- Editing ----------
+
+/// Make a task repeat, change its rule, or (with `None`) stop the series
+/// after it. On a later occurrence a new rule starts a new series there;
+/// earlier occurrences keep the old one.
+pub async fn set_task_recurrence(pool: &SqlitePool, task_id: &str, input: Option<RecurrenceInput>) -> Result<()> {
+    let row = sqlx::query(
+        r#"
+        SELECT name, category, short_summary, description, estimate_minutes, start_at, end_est_at, parent_id
+        FROM tasks WHERE id = ?1 AND deleted_at IS NULL
+        "#,
+    )
+    .bind(task_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::not_found("task", task_id))?;
+    let current = occurrence_of(&mut *pool.acquire().await?, task_id).await?;
+
+    let plan = match &input {
+        Some(input) => {
+            if row.get::<Option<String>, _>("parent_id").is_some() {
+                return Err(AppError::validation("recurrence", "Subtasks can't repeat; make their top-level task repeat"));
+            }
+            Some(plan(input, row.get("start_at"), row.get("end_est_at"))?)
+        }
+        None => None,
+    };
+    if plan.is_none() && current.is_none() {
+        return Ok(());
+    }
+    projects::ensure_writable(pool, "tasks", task_id).await?;
+
+    let new_id = Uuid::new_v4().to_string();
+    let mut tx = journal::begin(pool).await?;
+    let later = match &current {
+        Some((series, n)) => occurrences_from(&mut tx, &series.id, *n).await?,
+        None => vec![task_id.to_string()],
+    };
+    let mut op = Recorder::new(if plan.is_some() { "Set task recurrence" } else { "Stop task recurrence" });
+    if let Some((series, _)) = &current {
+        op.track(&mut tx, "task_series", &series.id).await?;
+    }
+    op.track(&mut tx, "task_series", &new_id).await?;
+    for id in &later {
+        op.track(&mut tx, "tasks", id).await?;
+    }
+
+    let now = Utc::now();
+    match (plan, &current) {
+        // first occurrence: the series itself changes
+        (Some(plan), Some((series, 1))) => {
+            sqlx::query(
+                r#"
+                UPDATE task_series SET rrule = ?2, timezone = ?3, dtstart = ?4, anchor = ?5,
+                  last_occurrence = NULL, updated_at = ?6
+                WHERE id = ?1
+                "#,
+            )
+            .bind(&series.id)
+            .bind(plan.rule.to_string())
+            .bind(plan.tz.name())
+            .bind(plan.dtstart)
+            .bind(plan.anchor.as_str())
+            .bind(now)
+            .execute(&mut *tx)
+            .await?;
+            let series = load_series(&mut *tx, &series.id).await?;
+            retime(&mut tx, &series).await?;
+        }
+        (Some(plan), Some((series, n))) => {
+            insert_series(&mut tx, &new_id, &plan, &series.template).await?;
+            split(&mut tx, &series.id, *n, &new_id).await?;
+            let series = load_series(&mut *tx, &new_id).await?;
+            retime(&mut tx, &series).await?;
+        }
+        (Some(plan), None) => {
+            let template = Template {
+                name: row.get("name"),
+                category: row.get("category"),
+                short_summary: row.get("short_summary"),
+                description: row.get("description"),
+                estimate_minutes: row.get("estimate_minutes"),
+            };
+            insert_series(&mut tx, &new_id, &plan, &template).await?;
+            sqlx::query("UPDATE tasks SET series_id = ?2, occurrence = 1, updated_at = ?3 WHERE id = ?1")
+                .bind(task_id)
+                .bind(&new_id)
+                .bind(now)
+                .execute(&mut *tx)
+                .await?;
+        }
+        // stop after this one; later ones already scheduled become plain tasks
+        (None, Some((series, n))) => {
+            sqlx::query("UPDATE task_series SET last_occurrence = ?2, updated_at = ?3 WHERE id = ?1")
+                .bind(&series.id)
+                .bind(n)
+                .bind(now)
+                .execute(&mut *tx)
+                .await?;
+            sqlx::query("UPDATE tasks SET series_id = NULL, occurrence = NULL, updated_at = ?3 WHERE series_id = ?1 AND occurrence > ?2")
+                .bind(&series.id)
+                .bind(n)
+                .bind(now)
+                .execute(&mut *tx)
+                .await?;
+        }
+        (None, None) => {}
+    }
+
+    op.commit(&mut tx).await?;
+    tx.commit().await?;
+    for id in &later {
+        events::publish(EntityKind::Task, id, ChangeOp::Updated);
+    }
+    Ok(())
+}
+
+/// Edit an occurrence, and with [`SeriesScope::Following`] the series and
+/// its later open occurrences too. Moving a later occurrence's start or due
+/// that way splits the series: this one becomes the first of a new series
+/// on the same rule, and the old one ends before it.
+pub async fn update_series_task(pool: &SqlitePool, input: UpdateTaskInput, scope: SeriesScope) -> Result<StatusChange> {
+    let current = match scope {
+        SeriesScope::This => None,
+        SeriesScope::Following => occurrence_of(&mut *pool.acquire().await?, &input.id).await?,
+    };
+    let Some((series, n)) = current else { return tasks::update_task(pool, input).await };
+
+    let template = (
+        input.name.clone(),
+        input.category.clone(),
+        input.short_summary.clone(),
+        input.description.clone(),
+        input.estimate_minutes,
+    );
+    let edit = TaskEdit::prepare(pool, input).await?;
+    let task_id = &edit.id;
+
+    let new_id = Uuid::new_v4().to_string();
+    let mut tx = journal::begin(pool).await?;
+    let before: (Option<DateTime<Utc>>, Option<DateTime<Utc>>) =
+        sqlx::query_as("SELECT start_at, end_est_at FROM tasks WHERE id = ?1")
+            .bind(task_id)
+            .fetch_one(&mut *tx)
+            .await?;
+    let moved = before != (edit.start_at, edit.end_est_at);
+    let later = occurrences_from(&mut tx, &series.id, n).await?;
+    let mut op = Recorder::new("Edit following occurrences");
+    op.track(&mut tx, "task_series", &series.id).await?;
+    op.track(&mut tx, "task_series", &new_id).await?;
+    for id in &later {
+        op.track(&mut tx, "tasks", id).await?;
+    }
+    edit.write(&mut tx, &mut op).await?;
+
+    let open: Vec<String> = sqlx::query_scalar(
+        r#"
+        SELECT id FROM tasks
+        WHERE series_id = ?1 AND occurrence > ?2 AND deleted_at IS NULL
+          AND status NOT IN ('completed', 'dropped')
+        "#,
+    )
+    .bind(&series.id)
+    .bind(n)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let now = Utc::now();
+    let (name, category, short_summary, description, estimate_minutes) = template;
+    sqlx::query(
+        r#"
+        UPDATE task_series SET
+          name = COALESCE(?2, name), category = COALESCE(?3, category),
+          short_summary = COALESCE(?4, short_summary), description = COALESCE(?5, description),
+          estimate_minutes = COALESCE(?6, estimate_minutes), updated_at = ?7
+        WHERE id = ?1
+        "#,
+    )
+    .bind(&series.id)
+    .bind(&name)
+    .bind(&category)
+    .bind(&short_summary)
+    .bind(&description)
+    .bind(estimate_minutes)
+    .bind(now)
+    .execute(&mut *tx)
+    .await?;
+    for id in &open {
+        sqlx::query(
+            r#"
+            UPDATE tasks SET
+              name = COALESCE(?2, name), category = COALESCE(?3, category),
+              short_summary = COALESCE(?4, short_summary), description = COALESCE(?5, description),
+              estimate_minutes = COALESCE(?6, estimate_minutes), updated_at = ?7
+            WHERE id = ?1
+            "#,
+        )
+        .bind(id)
+        .bind(&name)
+        .bind(&category)
+        .bind(&short_summary)
+        .bind(&description)
+        .bind(estimate_minutes)
+        .bind(now)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    if moved {
+        let (anchor, dtstart) = anchor_at(edit.start_at, edit.end_est_at)?;
+        if n == 1 {
+            sqlx::query("UPDATE task_series SET dtstart = ?2, anchor = ?3 WHERE id = ?1")
+                .bind(&series.id)
+                .bind(dtstart)
+                .bind(anchor.as_str())
+                .execute(&mut *tx)
+                .await?;
+            let series = load_series(&mut *tx, &series.id).await?;
+            retime(&mut tx, &series).await?;
+        } else {
+            let mut rule = series.rule.clone();
+            rule.count = rule.count.map(|c| c.saturating_sub(u32::try_from(n - 1).unwrap_or(0)).max(1));
+            let template = load_series(&mut *tx, &series.id).await?.template;
+            insert_series(&mut tx, &new_id, &Plan { rule, tz: series.tz, anchor, dtstart }, &template).await?;
+            split(&mut tx, &series.id, n, &new_id).await?;
+            let series = load_series(&mut *tx, &new_id).await?;
+            retime(&mut tx, &series).await?;
+        }
+    }
+
+    // finishing it schedules the next one, from the edited (maybe split) series
+    let (mut next, mut parents) = (None, Vec::new());
+    if edit.finishes() {
+        next = schedule_next(&mut tx, &mut op, task_id).await?;
+        parents = subtasks::complete_parents(&mut tx, &mut op, task_id).await?;
+    }
+
+    op.commit(&mut tx).await?;
+    tx.commit().await?;
+    for id in &later {
+        events::publish(EntityKind::Task, id, ChangeOp::Updated);
+    }
+    if let Some(id) = &next {
+        events::publish(EntityKind::Task, id, ChangeOp::Created);
+    }
+    tasks::publish_linked_ideas(pool, task_id).await?;
+    for parent in &parents {
+        events::publish(EntityKind::Task, parent, ChangeOp::Updated);
+        tasks::publish_linked_ideas(pool, parent).await?;
+    }
+    Ok(edit.change())
+}
@@ -0,0 +1,294 @@
+// src/services/reminders.rs
+//! Due-date reminders for the in-app notification center.
+//!
+//! Reminders aren't stored ahead of time: `run()` calls [`run_due`] every
+//! [`CHECK_EVERY`], and it works out which reminders of open tasks have come
+//! due from each task's `end_est_at` and the [`ReminderSettings`] offsets.
+//! The first pass after startup is the catch-up for anything missed while
+//! the app was closed; when several reminders of a task are due at once only
+//! the latest is shown, so a task that went overdue overnight says just that.
+//!
+//! A fired reminder gets a `reminder` row keyed by task, offset and due
+//! date, which keeps it from firing twice and holds its snooze/dismiss
+//! state. A newer reminder for a task replaces the one before it, and moving
+//! the due date starts over. Reminders aren't recorded for undo.
+use std::time::Duration as StdDuration;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqlitePool};
+use uuid::Uuid;
+
+use crate::services::settings;
+use crate::{AppError, Result};
+
+pub const SETTINGS_KEY: &str = "reminders";
+
+/// Tauri event carrying a [`Notification`] each time one is shown.
+pub const NOTIFICATION_EVENT: &str = "notification";
+
+/// How often `run()` looks for due reminders.
+pub const CHECK_EVERY: StdDuration = StdDuration::from_secs(30);
+
+/// A reminder shown this much after its time counts as missed.
+const GRACE: Duration = Duration::minutes(2);
+
+/// Longest reminder offset: 30 days.
+const MAX_OFFSET_MINUTES: u32 = 30 * 24 * 60;
+
+const COLUMNS: &str = r#"
+    r.id, r.task_id, t.name AS task_name, r.kind, r.offset_minutes, r.due_at, r.fire_at,
+    r.notified_at, r.snoozed_until
+"#;
+
+// ---------- Types ----------
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReminderSettings {
+    pub enabled: bool,
+    pub offsets_minutes: Vec<u32>, // before end_est_at
+    pub overdue: bool,             // also remind once the due time has passed
+}
+impl Default for ReminderSettings {
+    fn default() -> Self {
+        ReminderSettings { enabled: true, offsets_minutes: vec![24 * 60, 60], overdue: true }
+    }
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReminderKind {
+    DueSoon,
+    Overdue,
+}
+
+impl ReminderKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            ReminderKind::DueSoon => "due_soon",
+            ReminderKind::Overdue => "overdue",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct Notification {
+    pub id: String,
+    pub task_id: String,
+    pub task_name: String,
+    pub kind: ReminderKind,
+    pub offset_minutes: i64,
+    pub due_at: DateTime<Utc>,
+    pub fire_at: DateTime<Utc>,
+    pub snoozed_until: Option<DateTime<Utc>>,
+    pub missed: bool, // shown late, e.g. by the catch-up after a restart
+}
+
+impl Notification {
+    fn from_row(row: &sqlx::sqlite::SqliteRow) -> Notification {
+        let fire_at: DateTime<Utc> = row.get("fire_at");
+        let notified_at: DateTime<Utc> = row.get("notified_at");
+        Notification {
+            id: row.get("id"),
+            task_id: row.get("task_id"),
+            task_name: row.get("task_name"),
+            kind: if row.get::<String, _>("kind") == "overdue" { ReminderKind::Overdue } else { ReminderKind::DueSoon },
+            offset_minutes: row.get("offset_minutes"),
+            due_at: row.get("due_at"),
+            fire_at,
+            snoozed_until: row.get("snoozed_until"),
+            missed: notified_at > fire_at + GRACE,
+        }
+    }
+}
+
+// ---------- Settings ----------
+
+pub async fn get_settings(pool: &SqlitePool) -> Result<ReminderSettings> {
+    settings::get_or_default(pool, SETTINGS_KEY).await
+}
+
+/// Offsets are stored sorted, longest first, without repeats.
+pub async fn set_settings(pool: &SqlitePool, mut input: ReminderSettings) -> Result<()> {
+    if input.offsets_minutes.iter().any(|&m| m == 0 || m > MAX_OFFSET_MINUTES) {
+        return Err(AppError::validation("offsets_minutes", "Offsets must be between 1 minute and 30 days"));
+    }
+    input.offsets_minutes.sort_unstable_by(|a, b| b.cmp(a));
+    input.offsets_minutes.dedup();
+    settings::put(pool, SETTINGS_KEY, &input).await
+}
+
+// ---------- Scheduling ----------
+
+/// Fire the reminders due at `now` and bring back snoozed ones whose time
+/// is up; returns what to show, oldest due first.
+pub async fn run_due(pool: &SqlitePool, now: DateTime<Utc>) -> Result<Vec<Notification>> {
+    let cfg = get_settings(pool).await?;
+    if !cfg.enabled {
+        return Ok(Vec::new());
+    }
+    let lead = Duration::minutes(cfg.offsets_minutes.iter().copied().max().unwrap_or(0).into());
+    let tasks: Vec<(String, DateTime<Utc>)> = sqlx::query_as(
+        r#"
+        SELECT id, end_est_at FROM tasks
+        WHERE deleted_at IS NULL AND status NOT IN ('completed', 'dropped')
+          AND end_est_at IS NOT NULL AND datetime(end_est_at) <= datetime(?1)
+        ORDER BY datetime(end_est_at)
+        "#,
+    )
+    .bind(now + lead)
+    .fetch_all(pool)
+    .await?;
+
+    let mut fired = Vec::new();
+    let mut tx = pool.begin().await?;
+    for (task_id, due_at) in tasks {
+        let mut due: Vec<(ReminderKind, i64, DateTime<Utc>)> = cfg
+            .offsets_minutes
+            .iter()
+            .map(|&m| (ReminderKind::DueSoon, i64::from(m), due_at - Duration::minutes(m.into())))
+            .collect();
+        if cfg.overdue {
+            due.push((ReminderKind::Overdue, 0, due_at));
+        }
+        due.retain(|(_, _, at)| *at <= now);
+
+        let seen: Vec<(String, i64, DateTime<Utc>)> =
+            sqlx::query_as("SELECT kind, offset_minutes, fire_at FROM reminder WHERE task_id = ?1 AND due_at = ?2")
+                .bind(&task_id)
+                .bind(due_at)
+                .fetch_all(&mut *tx)
+                .await?;
+        due.retain(|(kind, offset, _)| !seen.iter().any(|(k, o, _)| k == kind.as_str() && o == offset));
+        let Some(latest) = due.iter().map(|(_, _, at)| *at).max() else { continue };
+        // only the newest reminder of a task stays up
+        let show = seen.iter().all(|(_, _, at)| *at <= latest);
+        if show {
+            sqlx::query("UPDATE reminder SET dismissed_at = ?2 WHERE task_id = ?1 AND dismissed_at IS NULL")
+                .bind(&task_id)
+                .bind(now)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        for (kind, offset, fire_at) in due {
+            let id = Uuid::new_v4().to_string();
+            let live = show && fire_at == latest;
+            sqlx::query(
+                r#"
+                INSERT INTO reminder (id, task_id, kind, offset_minutes, due_at, fire_at, notified_at, dismissed_at, created_at)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?7)
+                "#,
+            )
+            .bind(&id)
+            .bind(&task_id)
+            .bind(kind.as_str())
+            .bind(offset)
+            .bind(due_at)
+            .bind(fire_at)
+            .bind(now)
+            .bind((!live).then_some(now))
+            .execute(&mut *tx)
+            .await?;
+            if live {
+                fired.push(id);
+            }
+        }
+    }
+
+    let woke: Vec<String> = sqlx::query_scalar(
+        r#"
+        SELECT id FROM reminder
+        WHERE dismissed_at IS NULL AND snoozed_until IS NOT NULL
+          AND datetime(snoozed_until) <= datetime(?1) AND datetime(notified_at) < datetime(snoozed_until)
+        "#,
+    )
+    .bind(now)
+    .fetch_all(&mut *tx)
+    .await?;
+    for id in woke {
+        sqlx::query("UPDATE reminder SET notified_at = ?2, snoozed_until = NULL WHERE id = ?1")
+            .bind(&id)
+            .bind(now)
+            .execute(&mut *tx)
+            .await?;
+        fired.push(id);
+    }
+    tx.commit().await?;
+
+    let mut out = Vec::new();
+    for id in fired {
+        out.push(get_notification(pool, &id).await?);
+    }
+    out.sort_by_key(|n| n.due_at);
+    Ok(out)
+}
+
+// ---------- Notification center ----------
+
+async fn get_notification(pool: &SqlitePool, id: &str) -> Result<Notification> {
+    let row = sqlx::query(&format!("SELECT {COLUMNS} FROM reminder r JOIN tasks t ON t.id = r.task_id WHERE r.id = ?1"))
+        .bind(id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::not_found("notification", id))?;
+    Ok(Notification::from_row(&row))
+}
+
+/// Notifications to show at `now`: not dismissed or snoozed, for tasks that
+/// are still open and still due when the reminder fired. Newest first.
+pub async fn list_notifications(pool: &SqlitePool, now: DateTime<Utc>) -> Result<Vec<Notification>> {
+    let rows = sqlx::query(&format!(
+        r#"
+        SELECT {COLUMNS}
+        FROM reminder r
+        JOIN tasks t ON t.id = r.task_id
+        WHERE r.dismissed_at IS NULL
+          AND (r.snoozed_until IS NULL OR datetime(r.snoozed_until) <= datetime(?1))
+          AND t.deleted_at IS NULL AND t.status NOT IN ('completed', 'dropped')
+          AND datetime(t.end_est_at) = datetime(r.due_at)
+        ORDER BY datetime(r.fire_at) DESC
+        "#
+    ))
+    .bind(now)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows.iter().map(Notification::from_row).collect())
+}
+
+/// Hide a notification until `until`, when [`run_due`] shows it again.
+pub async fn snooze_notification(pool: &SqlitePool, id: &str, until: DateTime<Utc>) -> Result<()> {
+    if until <= Utc::now() {
+        return Err(AppError::validation("until", "Snooze until a time in the future"));
+    }
+    let done = sqlx::query("UPDATE reminder SET snoozed_until = ?2 WHERE id = ?1 AND dismissed_at IS NULL")
+        .bind(id)
+        .bind(until)
+        .execute(pool)
+        .await?;
+    if done.rows_affected() == 0 {
+        return Err(AppError::not_found("notification", id));
+    }
+    Ok(())
+}
+
+pub async fn dismiss_notification(pool: &SqlitePool, id: &str) -> Result<()> {
+    let done = sqlx::query("UPDATE reminder SET dismissed_at = ?2 WHERE id = ?1 AND dismissed_at IS NULL")
+        .bind(id)
+        .bind(Utc::now())
+        .execute(pool)
+        .await?;
+    if done.rows_affected() == 0 {
+        return Err(AppError::not_found("notification", id));
+    }
+    Ok(())
+}
+
+/// Clear the notification center; snoozed notifications stay snoozed.
+pub async fn dismiss_all_notifications(pool: &SqlitePool) -> Result<u64> {
+    let done = sqlx::query("UPDATE reminder SET dismissed_at = ?1 WHERE dismissed_at IS NULL AND snoozed_until IS NULL")
+        .bind(Utc::now())
+        .execute(pool)
+        .await?;
+    Ok(done.rows_affected())
+}
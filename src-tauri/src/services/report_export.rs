@@ -0,0 +1,213 @@
+// src/services/report_export.rs
+//! Render a [`Report`] as Markdown, CSV or a self-contained HTML page, and
+//! write it to a file or into a new doc.
+//!
+//! Markdown is the source format: the HTML page is the Markdown rendered by
+//! `services::markdown` inside a page with its styles inlined. CSV holds one
+//! table per section, separated by blank lines.
+use std::fs;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use serde::Deserialize;
+use sqlx::SqlitePool;
+
+use crate::services::docs::{self, DocInput};
+use crate::services::markdown::{self, escape};
+use crate::services::reports::Report;
+use crate::{AppError, Result};
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    #[serde(alias = "md")]
+    Markdown,
+    Csv,
+    Html,
+}
+
+impl ReportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ReportFormat::Markdown => "md",
+            ReportFormat::Csv => "csv",
+            ReportFormat::Html => "html",
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            ReportFormat::Markdown => "text/markdown; charset=utf-8",
+            ReportFormat::Csv => "text/csv; charset=utf-8",
+            ReportFormat::Html => "text/html; charset=utf-8",
+        }
+    }
+}
+
+const STYLE: &str = r#"
+body { font: 15px/1.5 -apple-system, "Segoe UI", Roboto, sans-serif; color: #1f2328; max-width: 760px; margin: 2rem auto; padding: 0 1rem; }
+h1 { font-size: 1.6rem; margin-bottom: .25rem; }
+h2 { font-size: 1.15rem; margin-top: 2rem; border-bottom: 1px solid #d0d7de; padding-bottom: .25rem; }
+table { border-collapse: collapse; width: 100%; margin: .5rem 0; }
+th, td { border: 1px solid #d0d7de; padding: .3rem .6rem; text-align: left; }
+th { background: #f6f8fa; }
+td:not(:first-child) { font-variant-numeric: tabular-nums; }
+em { color: #59636e; }
+"#;
+
+// ---------- Rendering ----------
+
+pub fn render(report: &Report, format: ReportFormat) -> String {
+    match format {
+        ReportFormat::Markdown => to_markdown(report),
+        ReportFormat::Csv => to_csv(report),
+        ReportFormat::Html => to_html(report),
+    }
+}
+
+fn title(report: &Report) -> String {
+    if report.from == report.to {
+        format!("Report for {}", report.from.format("%a %Y-%m-%d"))
+    } else {
+        format!("Report {} – {}", report.from.format("%Y-%m-%d"), report.to.format("%Y-%m-%d"))
+    }
+}
+
+fn duration(minutes: i64) -> String {
+    match (minutes / 60, minutes % 60) {
+        (0, m) => format!("{m}m"),
+        (h, 0) => format!("{h}h"),
+        (h, m) => format!("{h}h {m}m"),
+    }
+}
+
+fn local(report: &Report, at: DateTime<Utc>) -> String {
+    let tz: Tz = report.timezone.parse().unwrap_or(Tz::UTC);
+    at.with_timezone(&tz).format("%a %Y-%m-%d %H:%M").to_string()
+}
+
+pub fn to_markdown(report: &Report) -> String {
+    let t = &report.totals;
+    let mut md = vec![
+        format!("# {}", title(report)),
+        String::new(),
+        format!("_{} · generated {}_", escape(&report.timezone), report.generated_at.format("%Y-%m-%d %H:%M UTC")),
+        String::new(),
+        "## Summary".into(),
+        String::new(),
+        "| Tasks completed | Focus | Focus sessions | Words written |".into(),
+        "|---:|---:|---:|---:|".into(),
+        format!("| {} | {} | {} | {} |", t.tasks_completed, duration(t.focus_minutes), t.focus_sessions, t.doc_words),
+    ];
+
+    if report.days.len() > 1 {
+        md.extend(["", "## By day", "", "| Day | Tasks | Focus | Words |", "|---|---:|---:|---:|"].map(String::from));
+        for d in &report.days {
+            let day = d.date.format("%a %Y-%m-%d");
+            md.push(format!("| {day} | {} | {} | {} |", d.tasks_completed, duration(d.focus_minutes), d.doc_words));
+        }
+    }
+    if !report.completed.is_empty() {
+        md.extend(["", "## Completed tasks", ""].map(String::from));
+        for c in &report.completed {
+            md.push(format!("- {} ({}) — {}", escape(&c.name), escape(&c.category), local(report, c.completed_at)));
+        }
+    }
+    if !report.focus.is_empty() {
+        md.extend(["", "## Focus by task", "", "| Task | Focus | Sessions |", "|---|---:|---:|"].map(String::from));
+        for f in &report.focus {
+            md.push(format!("| {} | {} | {} |", escape(&f.name), duration(f.minutes), f.sessions));
+        }
+    }
+    if !report.docs.is_empty() {
+        md.extend(["", "## Writing", "", "| Doc | Words |", "|---|---:|"].map(String::from));
+        for d in &report.docs {
+            md.push(format!("| {} | {} |", escape(&d.title), d.words));
+        }
+    }
+    md.push(String::new());
+    md.join("\n")
+}
+
+fn csv_field(s: &str) -> String {
+    // a leading quote keeps spreadsheets from running names as formulas
+    let s = if s.starts_with(['=', '+', '-', '@']) { format!("'{s}") } else { s.to_string() };
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s
+    }
+}
+
+fn csv_row(out: &mut String, fields: &[&str]) {
+    let row: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+    out.push_str(&row.join(","));
+    out.push_str("\r\n");
+}
+
+pub fn to_csv(report: &Report) -> String {
+    let mut out = String::new();
+    csv_row(&mut out, &["day", "tasks_completed", "focus_minutes", "doc_words"]);
+    for d in &report.days {
+        let (date, tasks, focus, words) =
+            (d.date.to_string(), d.tasks_completed.to_string(), d.focus_minutes.to_string(), d.doc_words.to_string());
+        csv_row(&mut out, &[&date, &tasks, &focus, &words]);
+    }
+
+    out.push_str("\r\n");
+    csv_row(&mut out, &["completed_task_id", "name", "category", "project_id", "completed_at"]);
+    for c in &report.completed {
+        let at = c.completed_at.to_rfc3339();
+        csv_row(&mut out, &[&c.id, &c.name, &c.category, c.project_id.as_deref().unwrap_or_default(), &at]);
+    }
+
+    out.push_str("\r\n");
+    csv_row(&mut out, &["focus_task_id", "name", "minutes", "sessions"]);
+    for f in &report.focus {
+        csv_row(&mut out, &[&f.task_id, &f.name, &f.minutes.to_string(), &f.sessions.to_string()]);
+    }
+
+    out.push_str("\r\n");
+    csv_row(&mut out, &["doc_id", "title", "words"]);
+    for d in &report.docs {
+        csv_row(&mut out, &[&d.doc_id, &d.title, &d.words.to_string()]);
+    }
+    out
+}
+
+pub fn to_html(report: &Report) -> String {
+    let title = title(report).replace('&', "&amp;").replace('<', "&lt;");
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n<style>{STYLE}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+        markdown::to_html(&to_markdown(report))
+    )
+}
+
+// ---------- Output ----------
+
+/// Write the rendered report to `dest`, replacing any file there.
+pub fn export_report(report: &Report, format: ReportFormat, dest: &Path) -> Result<()> {
+    if dest.is_dir() {
+        return Err(AppError::validation("path", "Pick a file, not a folder"));
+    }
+    fs::write(dest, render(report, format))?;
+    Ok(())
+}
+
+/// Save the report as a new draft doc (Markdown) in `project_id`.
+pub async fn report_to_doc(pool: &SqlitePool, report: &Report, project_id: &str, title: Option<String>) -> Result<String> {
+    let title = title.filter(|t| !t.trim().is_empty()).unwrap_or_else(|| self::title(report));
+    docs::add_doc(
+        pool,
+        DocInput {
+            project_id: project_id.to_string(),
+            title,
+            slug: None,
+            body_md: to_markdown(report),
+            cover_path: None,
+            status: None,
+        },
+    )
+    .await
+}
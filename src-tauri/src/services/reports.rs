@@ -0,0 +1,376 @@
+// src/services/reports.rs
+//! Productivity reports over a range of days in a given time zone.
+//!
+//! A report counts tasks completed (from their status transitions), focus
+//! minutes (finished `time_entry` rows with source `timer`, cut at day
+//! boundaries) and doc words written (what each saved version added over
+//! the one before it; deletions don't count against it). Saved reports keep
+//! the generated JSON so they can be compared later.
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqlitePool};
+use uuid::Uuid;
+
+use crate::services::journal::{self, Recorder};
+use crate::{AppError, Result};
+
+/// Longest range a report may cover, in days.
+pub const MAX_DAYS: i64 = 366;
+
+// ---------- Types ----------
+
+#[derive(Deserialize)]
+pub struct ReportInput {
+    pub from: NaiveDate,
+    pub to: NaiveDate, // inclusive
+    pub timezone: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+pub struct ReportTotals {
+    pub tasks_completed: i64,
+    pub focus_minutes: i64,
+    pub focus_sessions: i64,
+    pub doc_words: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct DayStats {
+    pub date: NaiveDate,
+    pub tasks_completed: i64,
+    pub focus_minutes: i64,
+    pub doc_words: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CompletedTask {
+    pub id: String,
+    pub name: String,
+    pub category: String,
+    pub project_id: Option<String>,
+    pub completed_at: DateTime<Utc>, // last completion within the range
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TaskFocus {
+    pub task_id: String,
+    pub name: String,
+    pub minutes: i64,
+    pub sessions: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DocWords {
+    pub doc_id: String,
+    pub title: String,
+    pub words: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Report {
+    pub id: Option<String>, // set once saved
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub timezone: String,
+    pub generated_at: DateTime<Utc>,
+    pub totals: ReportTotals,
+    pub days: Vec<DayStats>, // every day of the range, oldest first
+    pub completed: Vec<CompletedTask>,
+    pub focus: Vec<TaskFocus>, // most minutes first
+    pub docs: Vec<DocWords>,   // most words first
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReportSummary {
+    pub id: String,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub timezone: String,
+    pub totals: ReportTotals,
+    pub created_at: DateTime<Utc>,
+}
+
+// ---------- Days ----------
+
+/// Where the local day `d` starts, in UTC. A day whose midnight is skipped by
+/// a DST change starts at its first existing hour.
+fn day_start(tz: &Tz, d: NaiveDate) -> DateTime<Utc> {
+    let midnight = d.and_time(NaiveTime::MIN);
+    (0..3)
+        .find_map(|h| tz.from_local_datetime(&(midnight + Duration::hours(h))).earliest())
+        .map(|t| t.with_timezone(&Utc))
+        .unwrap_or_else(|| midnight.and_utc())
+}
+
+struct Range {
+    tz: Tz,
+    from: NaiveDate,
+    to: NaiveDate,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>, // exclusive
+}
+
+impl Range {
+    fn new(input: &ReportInput) -> Result<Self> {
+        let tz: Tz = input
+            .timezone
+            .parse()
+            .map_err(|_| AppError::validation("timezone", format!("Unknown time zone {}", input.timezone)))?;
+        if input.to < input.from {
+            return Err(AppError::validation("to", "End date is before the start date"));
+        }
+        if (input.to - input.from).num_days() >= MAX_DAYS {
+            return Err(AppError::validation("to", format!("A report covers at most {MAX_DAYS} days")));
+        }
+        let next = input.to.succ_opt().ok_or_else(|| AppError::validation("to", "Date out of range"))?;
+        Ok(Range { start: day_start(&tz, input.from), end: day_start(&tz, next), tz, from: input.from, to: input.to })
+    }
+
+    fn day(&self, t: DateTime<Utc>) -> NaiveDate {
+        t.with_timezone(&self.tz).date_naive()
+    }
+
+    /// Seconds of `start..end` falling on each local day of the range.
+    fn split(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Vec<(NaiveDate, i64)> {
+        let (mut at, end) = (start.max(self.start), end.min(self.end));
+        let mut out = Vec::new();
+        while at < end {
+            let day = self.day(at);
+            let next = day.succ_opt().map(|d| day_start(&self.tz, d)).unwrap_or(end).min(end);
+            out.push((day, (next - at).num_seconds()));
+            at = next;
+        }
+        out
+    }
+}
+
+// ---------- Generation ----------
+
+/// Build the report for `input` without saving it.
+pub async fn generate_report(pool: &SqlitePool, input: &ReportInput) -> Result<Report> {
+    let range = Range::new(input)?;
+    let mut days: BTreeMap<NaiveDate, DayStats> = range
+        .from
+        .iter_days()
+        .take_while(|d| *d <= range.to)
+        .map(|date| (date, DayStats { date, tasks_completed: 0, focus_minutes: 0, doc_words: 0 }))
+        .collect();
+
+    // tasks: last completing transition per task inside the range
+    let rows = sqlx::query(
+        r#"
+        SELECT r.task_id, r.created_at, t.name, t.category, t.project_id
+        FROM status_reasons r JOIN tasks t ON t.id = r.task_id
+        WHERE r.status = 'completed' AND r.from_status IS NOT NULL AND t.deleted_at IS NULL
+          AND julianday(r.created_at) >= julianday(?1) AND julianday(r.created_at) < julianday(?2)
+        ORDER BY r.created_at, r.rowid
+        "#,
+    )
+    .bind(range.start)
+    .bind(range.end)
+    .fetch_all(pool)
+    .await?;
+    let mut completed: BTreeMap<String, CompletedTask> = BTreeMap::new();
+    for r in &rows {
+        let task = CompletedTask {
+            id: r.get("task_id"),
+            name: r.get("name"),
+            category: r.get("category"),
+            project_id: r.get("project_id"),
+            completed_at: r.get("created_at"),
+        };
+        completed.insert(task.id.clone(), task);
+    }
+    let mut completed: Vec<CompletedTask> = completed.into_values().collect();
+    completed.sort_by_key(|t| t.completed_at);
+    for t in &completed {
+        if let Some(d) = days.get_mut(&range.day(t.completed_at)) {
+            d.tasks_completed += 1;
+        }
+    }
+
+    // focus: finished timer sessions, cut to the range and split per day
+    let rows = sqlx::query(
+        r#"
+        SELECT e.task_id, t.name, e.started_at, e.ended_at
+        FROM time_entry e JOIN tasks t ON t.id = e.task_id
+        WHERE e.source = 'timer' AND e.ended_at IS NOT NULL AND t.deleted_at IS NULL
+          AND julianday(e.started_at) < julianday(?2) AND julianday(e.ended_at) > julianday(?1)
+        ORDER BY e.started_at
+        "#,
+    )
+    .bind(range.start)
+    .bind(range.end)
+    .fetch_all(pool)
+    .await?;
+    let mut day_secs: BTreeMap<NaiveDate, i64> = BTreeMap::new();
+    let mut by_task: BTreeMap<String, (String, i64, i64)> = BTreeMap::new(); // name, seconds, sessions
+    for r in &rows {
+        let (start, end): (DateTime<Utc>, DateTime<Utc>) = (r.get("started_at"), r.get("ended_at"));
+        let task = by_task.entry(r.get("task_id")).or_insert_with(|| (r.get("name"), 0, 0));
+        task.2 += 1;
+        for (day, secs) in range.split(start, end) {
+            *day_secs.entry(day).or_default() += secs;
+            task.1 += secs;
+        }
+    }
+    for (day, secs) in &day_secs {
+        if let Some(d) = days.get_mut(day) {
+            d.focus_minutes = (secs + 30) / 60;
+        }
+    }
+    let mut focus: Vec<TaskFocus> = by_task
+        .into_iter()
+        .map(|(task_id, (name, secs, sessions))| TaskFocus { task_id, name, minutes: (secs + 30) / 60, sessions })
+        .collect();
+    focus.sort_by_key(|f| Reverse(f.minutes));
+
+    // docs: words each version in the range added over the previous one
+    let rows = sqlx::query(
+        r#"
+        SELECT v.doc_id, d.title, v.body_md, v.created_at
+        FROM doc_version v JOIN doc d ON d.id = v.doc_id
+        WHERE d.deleted_at IS NULL AND julianday(v.created_at) < julianday(?2)
+          AND v.doc_id IN (
+            SELECT doc_id FROM doc_version
+            WHERE julianday(created_at) >= julianday(?1) AND julianday(created_at) < julianday(?2)
+          )
+        ORDER BY v.doc_id, v.created_at, v.rowid
+        "#,
+    )
+    .bind(range.start)
+    .bind(range.end)
+    .fetch_all(pool)
+    .await?;
+    let mut docs: Vec<DocWords> = Vec::new();
+    let mut prev: Option<(String, usize)> = None; // doc id, words of its previous version
+    for r in &rows {
+        let (doc_id, at): (String, DateTime<Utc>) = (r.get("doc_id"), r.get("created_at"));
+        let words = r.get::<String, _>("body_md").split_whitespace().count();
+        let before = match &prev {
+            Some((id, n)) if *id == doc_id => *n,
+            _ => 0,
+        };
+        prev = Some((doc_id.clone(), words));
+        if at < range.start {
+            continue;
+        }
+        let added = words.saturating_sub(before) as i64;
+        if docs.last().is_none_or(|d| d.doc_id != doc_id) {
+            docs.push(DocWords { doc_id, title: r.get("title"), words: 0 });
+        }
+        if let Some(d) = docs.last_mut() {
+            d.words += added;
+        }
+        if let Some(d) = days.get_mut(&range.day(at)) {
+            d.doc_words += added;
+        }
+    }
+    docs.retain(|d| d.words > 0);
+    docs.sort_by_key(|d| Reverse(d.words));
+
+    let days: Vec<DayStats> = days.into_values().collect();
+    let totals = ReportTotals {
+        tasks_completed: completed.len() as i64,
+        focus_minutes: (day_secs.values().sum::<i64>() + 30) / 60,
+        focus_sessions: focus.iter().map(|f| f.sessions).sum(),
+        doc_words: days.iter().map(|d| d.doc_words).sum(),
+    };
+
+    Ok(Report {
+        id: None,
+        from: range.from,
+        to: range.to,
+        timezone: input.timezone.clone(),
+        generated_at: Utc::now(),
+        totals,
+        days,
+        completed,
+        focus,
+        docs,
+    })
+}
+
+// ---------- Saved reports ----------
+
+/// Generate and keep a report; returns it with its new `id`.
+pub async fn save_report(pool: &SqlitePool, input: &ReportInput) -> Result<Report> {
+    let mut report = generate_report(pool, input).await?;
+    let id = Uuid::new_v4().to_string();
+    report.id = Some(id.clone());
+    let body = serde_json::to_string(&report).map_err(|e| AppError::Other(format!("report: {e}")))?;
+
+    let mut tx = journal::begin(pool).await?;
+    let mut op = Recorder::new("Save report");
+    op.track(&mut tx, "report", &id).await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO report (id, from_date, to_date, timezone, body, created_at)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+        "#,
+    )
+    .bind(&id)
+    .bind(report.from)
+    .bind(report.to)
+    .bind(&report.timezone)
+    .bind(body)
+    .bind(report.generated_at)
+    .execute(&mut *tx)
+    .await?;
+
+    op.commit(&mut tx).await?;
+    tx.commit().await?;
+    Ok(report)
+}
+
+pub async fn get_report(pool: &SqlitePool, id: &str) -> Result<Report> {
+    let body: String = sqlx::query_scalar("SELECT body FROM report WHERE id = ?1")
+        .bind(id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::not_found("report", id))?;
+    serde_json::from_str(&body).map_err(|e| AppError::Other(format!("report {id}: {e}")))
+}
+
+/// Saved reports, newest range first, with their totals for side-by-side use.
+pub async fn list_reports(pool: &SqlitePool) -> Result<Vec<ReportSummary>> {
+    let rows = sqlx::query("SELECT id, body, created_at FROM report ORDER BY from_date DESC, created_at DESC")
+        .fetch_all(pool)
+        .await?;
+
+    rows.iter()
+        .map(|r| {
+            let id: String = r.get("id");
+            let report: Report = serde_json::from_str(r.get("body"))
+                .map_err(|e| AppError::Other(format!("report {id}: {e}")))?;
+            Ok(ReportSummary {
+                id,
+                from: report.from,
+                to: report.to,
+                timezone: report.timezone,
+                totals: report.totals,
+                created_at: r.get("created_at"),
+            })
+        })
+        .collect()
+}
+
+pub async fn delete_report(pool: &SqlitePool, id: &str) -> Result<()> {
+    let mut tx = journal::begin(pool).await?;
+    let mut op = Recorder::new("Delete report");
+    op.track(&mut tx, "report", id).await?;
+
+    let res = sqlx::query("DELETE FROM report WHERE id = ?1").bind(id).execute(&mut *tx).await?;
+    if res.rows_affected() == 0 {
+        return Err(AppError::not_found("report", id));
+    }
+
+    op.commit(&mut tx).await?;
+    tx.commit().await?;
+    Ok(())
+}
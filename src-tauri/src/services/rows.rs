@@ -0,0 +1,137 @@
+// src/services/rows.rs
+//! Table-agnostic row access as JSON objects ("images"), shared by the vault
+//! archive and the undo journal. Table and column names come from our own
+//! constants and `pragma_table_info`, never from user input.
+use serde_json::{Map, Value};
+use sqlx::query::Query;
+use sqlx::sqlite::{Sqlite, SqliteArguments};
+use sqlx::{Row, SqliteConnection};
+
+use crate::{AppError, Result};
+
+/// A row as `{column: value}`; also used for primary-key filters.
+pub type Image = Map<String, Value>;
+
+/// Column names plus the primary-key columns, from `pragma_table_info`.
+pub async fn columns(conn: &mut SqliteConnection, table: &str) -> Result<(Vec<String>, Vec<String>)> {
+    let rows: Vec<(String, i64)> = sqlx::query_as("SELECT name, pk FROM pragma_table_info(?1) ORDER BY cid")
+        .bind(table)
+        .fetch_all(conn)
+        .await?;
+
+    let mut pk: Vec<(i64, String)> = rows.iter().filter(|(_, p)| *p > 0).map(|(n, p)| (*p, n.clone())).collect();
+    pk.sort();
+    Ok((rows.into_iter().map(|(n, _)| n).collect(), pk.into_iter().map(|(_, n)| n).collect()))
+}
+
+/// `json_object('a', "a", ...)` over the given columns.
+pub fn json_object_expr(cols: &[String]) -> String {
+    let pairs: Vec<String> = cols.iter().map(|c| format!("'{c}', \"{c}\"")).collect();
+    format!("json_object({})", pairs.join(", "))
+}
+
+pub fn bind_json<'q>(q: Query<'q, Sqlite, SqliteArguments<'q>>, v: &Value) -> Query<'q, Sqlite, SqliteArguments<'q>> {
+    match v {
+        Value::Null => q.bind(None::<String>),
+        Value::Bool(b) => q.bind(*b),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => q.bind(i),
+            None => q.bind(n.as_f64()),
+        },
+        Value::String(s) => q.bind(s.clone()),
+        other => q.bind(other.to_string()),
+    }
+}
+
+pub fn parse_image(raw: &str) -> Result<Image> {
+    serde_json::from_str(raw).map_err(|e| AppError::Other(format!("row image: {e}")))
+}
+
+fn where_clause(key: &Image) -> String {
+    key.keys().map(|c| format!("\"{c}\" = ?")).collect::<Vec<_>>().join(" AND ")
+}
+
+/// Current image of the row matching `key`, if any.
+pub async fn fetch_image(conn: &mut SqliteConnection, table: &str, key: &Image) -> Result<Option<Image>> {
+    let (cols, _) = columns(conn, table).await?;
+    let sql = format!("SELECT {} FROM {table} WHERE {}", json_object_expr(&cols), where_clause(key));
+    let mut q = sqlx::query(&sql);
+    for v in key.values() {
+        q = bind_json(q, v);
+    }
+    q.fetch_optional(conn)
+        .await?
+        .map(|row| parse_image(&row.get::<String, _>(0)))
+        .transpose()
+}
+
+/// Tables holding a row that points at `image` (a row of `table`) through a
+/// foreign key. Deleting it would cascade into those rows or cut them loose.
+pub async fn referenced_by(conn: &mut SqliteConnection, table: &str, image: &Image) -> Result<Vec<String>> {
+    let (_, pk) = columns(conn, table).await?;
+    let fks: Vec<(String, String, Option<String>)> = sqlx::query_as(
+        r#"
+        SELECT m.name, f."from", f."to"
+        FROM sqlite_master m, pragma_foreign_key_list(m.name) f
+        WHERE m.type = 'table' AND f."table" = ?1
+        "#,
+    )
+    .bind(table)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let mut users = Vec::new();
+    for (child, col, to) in fks {
+        let Some(v) = to.as_ref().or(pk.first()).and_then(|c| image.get(c)) else { continue };
+        let sql = format!("SELECT EXISTS(SELECT 1 FROM {child} WHERE \"{col}\" = ?)");
+        let used: bool = bind_json(sqlx::query(&sql), v).fetch_one(&mut *conn).await?.get(0);
+        if used && !users.contains(&child) {
+            users.push(child);
+        }
+    }
+    Ok(users)
+}
+
+/// Plain `INSERT` of an image; constraint errors surface as usual.
+pub async fn insert_image(conn: &mut SqliteConnection, table: &str, image: &Image) -> Result<()> {
+    let sql = format!(
+        "INSERT INTO {table} ({}) VALUES ({})",
+        image.keys().map(|c| format!("\"{c}\"")).collect::<Vec<_>>().join(", "),
+        vec!["?"; image.len()].join(", "),
+    );
+    let mut q = sqlx::query(&sql);
+    for v in image.values() {
+        q = bind_json(q, v);
+    }
+    q.execute(conn).await?;
+    Ok(())
+}
+
+/// Make the row at `key` look like `image`: update it, insert it, or delete it
+/// when `image` is `None`. Updates in place so `ON DELETE` cascades don't fire.
+pub async fn write_image(conn: &mut SqliteConnection, table: &str, key: &Image, image: Option<&Image>) -> Result<()> {
+    let exists = fetch_image(conn, table, key).await?.is_some();
+    match (image, exists) {
+        (None, false) => Ok(()),
+        (None, true) => {
+            let sql = format!("DELETE FROM {table} WHERE {}", where_clause(key));
+            let mut q = sqlx::query(&sql);
+            for v in key.values() {
+                q = bind_json(q, v);
+            }
+            q.execute(conn).await?;
+            Ok(())
+        }
+        (Some(img), false) => insert_image(conn, table, img).await,
+        (Some(img), true) => {
+            let sets = img.keys().map(|c| format!("\"{c}\" = ?")).collect::<Vec<_>>().join(", ");
+            let sql = format!("UPDATE {table} SET {sets} WHERE {}", where_clause(key));
+            let mut q = sqlx::query(&sql);
+            for v in img.values().chain(key.values()) {
+                q = bind_json(q, v);
+            }
+            q.execute(conn).await?;
+            Ok(())
+        }
+    }
+}
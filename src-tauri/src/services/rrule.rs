@@ -0,0 +1,496 @@
+// src/services/rrule.rs
+//! The part of RFC 5545 recurrence rules (RRULE) that tasks repeat on:
+//! DAILY, WEEKLY, MONTHLY and YEARLY with INTERVAL, COUNT, UNTIL, BYDAY
+//! (ordinals such as `-1FR` included), BYMONTHDAY, BYMONTH, BYSETPOS and WKST.
+//!
+//! Rules expand in the local time of an IANA zone, so "Mondays at 09:00"
+//! stays at 09:00 across DST changes. As in RFC 5545, DTSTART is the first
+//! occurrence and COUNT includes it. A local time that falls into a DST gap
+//! keeps the offset from before the gap; one that happens twice takes the
+//! first.
+use std::collections::VecDeque;
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{
+    DateTime, Datelike, Days, Duration, LocalResult, Months, NaiveDate, NaiveDateTime, Offset, TimeZone, Utc, Weekday,
+};
+use chrono_tz::Tz;
+
+use crate::{AppError, Result};
+
+/// Years in a row without an occurrence before a rule counts as used up,
+/// e.g. `FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=30`. The Gregorian calendar repeats
+/// every 400 years, so a rule that is quiet that long stays quiet.
+const MAX_EMPTY_YEARS: u32 = 400;
+
+const WEEKDAYS: [(&str, Weekday); 7] = [
+    ("MO", Weekday::Mon),
+    ("TU", Weekday::Tue),
+    ("WE", Weekday::Wed),
+    ("TH", Weekday::Thu),
+    ("FR", Weekday::Fri),
+    ("SA", Weekday::Sat),
+    ("SU", Weekday::Sun),
+];
+
+// ---------- Types ----------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Freq {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl Freq {
+    fn per_year(self) -> u32 {
+        match self {
+            Freq::Daily => 366,
+            Freq::Weekly => 53,
+            Freq::Monthly => 12,
+            Freq::Yearly => 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Until {
+    Date(NaiveDate),      // last local day, inclusive
+    Local(NaiveDateTime), // floating local time
+    Utc(DateTime<Utc>),
+}
+
+/// A BYDAY entry: `FR`, or `-1FR` for "the last Friday" of the month/year.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ByDay {
+    pub nth: Option<i32>,
+    pub weekday: Weekday,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RRule {
+    pub freq: Freq,
+    pub interval: u32,
+    pub count: Option<u32>,
+    pub until: Option<Until>,
+    pub by_day: Vec<ByDay>,
+    pub by_month_day: Vec<i32>,
+    pub by_month: Vec<u32>,
+    pub by_set_pos: Vec<i32>,
+    pub wkst: Weekday,
+}
+
+// ---------- Parsing ----------
+
+fn invalid(msg: impl Into<String>) -> AppError {
+    AppError::validation("rrule", msg)
+}
+
+/// A non-zero integer with `|n| <= max`, positive unless `signed`.
+fn number(key: &str, value: &str, max: i32, signed: bool) -> Result<i32> {
+    match value.parse::<i32>() {
+        Ok(n) if n != 0 && n.abs() <= max && (signed || n > 0) => Ok(n),
+        _ => Err(invalid(format!("{key}={value} is out of range"))),
+    }
+}
+
+fn weekday(value: &str) -> Result<Weekday> {
+    WEEKDAYS
+        .iter()
+        .find(|(code, _)| *code == value)
+        .map(|(_, d)| *d)
+        .ok_or_else(|| invalid(format!("Unknown weekday '{value}'")))
+}
+
+fn weekday_code(day: Weekday) -> &'static str {
+    WEEKDAYS[day.num_days_from_monday() as usize].0
+}
+
+fn by_day(value: &str) -> Result<ByDay> {
+    let split = value.len().checked_sub(2).filter(|&i| value.is_char_boundary(i));
+    let Some(split) = split else { return Err(invalid(format!("Unknown weekday '{value}'"))) };
+    let (nth, day) = value.split_at(split);
+    let nth = match nth {
+        "" => None,
+        n => Some(number("BYDAY", n.strip_prefix('+').unwrap_or(n), 53, true)?),
+    };
+    Ok(ByDay { nth, weekday: weekday(day)? })
+}
+
+fn until(value: &str) -> Result<Until> {
+    if let Ok(d) = NaiveDate::parse_from_str(value, "%Y%m%d") {
+        return Ok(Until::Date(d));
+    }
+    if let Some(utc) = value.strip_suffix('Z') {
+        if let Ok(t) = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S") {
+            return Ok(Until::Utc(t.and_utc()));
+        }
+    }
+    NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S")
+        .map(Until::Local)
+        .map_err(|_| invalid(format!("UNTIL={value} is not a date or date-time")))
+}
+
+fn list<T>(value: &str, item: impl Fn(&str) -> Result<T>) -> Result<Vec<T>> {
+    value.split(',').map(item).collect()
+}
+
+impl FromStr for RRule {
+    type Err = AppError;
+
+    /// Parse an RRULE value, with or without the `RRULE:` prefix.
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim().to_ascii_uppercase();
+        let s = s.strip_prefix("RRULE:").unwrap_or(&s);
+
+        let mut freq = None;
+        let mut rule = RRule {
+            freq: Freq::Daily,
+            interval: 1,
+            count: None,
+            until: None,
+            by_day: Vec::new(),
+            by_month_day: Vec::new(),
+            by_month: Vec::new(),
+            by_set_pos: Vec::new(),
+            wkst: Weekday::Mon,
+        };
+        let mut seen = Vec::new();
+        for part in s.split(';').filter(|p| !p.is_empty()) {
+            let (key, value) = part
+                .split_once('=')
+                .ok_or_else(|| invalid(format!("Expected NAME=VALUE, got '{part}'")))?;
+            if seen.contains(&key) {
+                return Err(invalid(format!("{key} is given twice")));
+            }
+            seen.push(key);
+
+            match key {
+                "FREQ" => {
+                    freq = Some(match value {
+                        "DAILY" => Freq::Daily,
+                        "WEEKLY" => Freq::Weekly,
+                        "MONTHLY" => Freq::Monthly,
+                        "YEARLY" => Freq::Yearly,
+                        "HOURLY" | "MINUTELY" | "SECONDLY" => return Err(invalid("Tasks repeat at most daily")),
+                        other => return Err(invalid(format!("Unknown FREQ '{other}'"))),
+                    })
+                }
+                "INTERVAL" => rule.interval = number(key, value, 10_000, false)? as u32,
+                "COUNT" => rule.count = Some(number(key, value, i32::MAX, false)? as u32),
+                "UNTIL" => rule.until = Some(until(value)?),
+                "BYDAY" => rule.by_day = list(value, by_day)?,
+                "BYMONTHDAY" => rule.by_month_day = list(value, |v| number(key, v, 31, true))?,
+                "BYMONTH" => rule.by_month = list(value, |v| number(key, v, 12, false).map(|m| m as u32))?,
+                "BYSETPOS" => rule.by_set_pos = list(value, |v| number(key, v, 366, true))?,
+                "WKST" => rule.wkst = weekday(value)?,
+                "BYHOUR" | "BYMINUTE" | "BYSECOND" | "BYYEARDAY" | "BYWEEKNO" => {
+                    return Err(invalid(format!("{key} is not supported")))
+                }
+                other => return Err(invalid(format!("Unknown rule part '{other}'"))),
+            }
+        }
+
+        rule.freq = freq.ok_or_else(|| invalid("FREQ is required"))?;
+        if rule.count.is_some() && rule.until.is_some() {
+            return Err(invalid("Use either COUNT or UNTIL, not both"));
+        }
+        if matches!(rule.freq, Freq::Daily | Freq::Weekly) && rule.by_day.iter().any(|d| d.nth.is_some()) {
+            return Err(invalid("Numbered BYDAY (like -1FR) needs FREQ=MONTHLY or YEARLY"));
+        }
+        if rule.freq == Freq::Weekly && !rule.by_month_day.is_empty() {
+            return Err(invalid("BYMONTHDAY can't be used with FREQ=WEEKLY"));
+        }
+        if !rule.by_set_pos.is_empty() && rule.by_day.is_empty() && rule.by_month_day.is_empty() && rule.by_month.is_empty() {
+            return Err(invalid("BYSETPOS needs another BY rule part"));
+        }
+        Ok(rule)
+    }
+}
+
+impl fmt::Display for RRule {
+    /// The rule in a fixed part order, without the `RRULE:` prefix.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let join = |items: Vec<String>| items.join(",");
+        let freq = match self.freq {
+            Freq::Daily => "DAILY",
+            Freq::Weekly => "WEEKLY",
+            Freq::Monthly => "MONTHLY",
+            Freq::Yearly => "YEARLY",
+        };
+        write!(f, "FREQ={freq}")?;
+        if self.interval != 1 {
+            write!(f, ";INTERVAL={}", self.interval)?;
+        }
+        if let Some(count) = self.count {
+            write!(f, ";COUNT={count}")?;
+        }
+        match self.until {
+            Some(Until::Date(d)) => write!(f, ";UNTIL={}", d.format("%Y%m%d"))?,
+            Some(Until::Local(t)) => write!(f, ";UNTIL={}", t.format("%Y%m%dT%H%M%S"))?,
+            Some(Until::Utc(t)) => write!(f, ";UNTIL={}", t.format("%Y%m%dT%H%M%SZ"))?,
+            None => {}
+        }
+        if !self.by_month.is_empty() {
+            write!(f, ";BYMONTH={}", join(self.by_month.iter().map(u32::to_string).collect()))?;
+        }
+        if !self.by_month_day.is_empty() {
+            write!(f, ";BYMONTHDAY={}", join(self.by_month_day.iter().map(i32::to_string).collect()))?;
+        }
+        if !self.by_day.is_empty() {
+            let days = self
+                .by_day
+                .iter()
+                .map(|d| format!("{}{}", d.nth.map(|n| n.to_string()).unwrap_or_default(), weekday_code(d.weekday)))
+                .collect();
+            write!(f, ";BYDAY={}", join(days))?;
+        }
+        if !self.by_set_pos.is_empty() {
+            write!(f, ";BYSETPOS={}", join(self.by_set_pos.iter().map(i32::to_string).collect()))?;
+        }
+        if self.wkst != Weekday::Mon {
+            write!(f, ";WKST={}", weekday_code(self.wkst))?;
+        }
+        Ok(())
+    }
+}
+
+// ---------- Expansion ----------
+
+/// `local` in `tz`; see the module docs for DST gaps and folds.
+fn resolve(tz: Tz, local: NaiveDateTime) -> DateTime<Utc> {
+    match tz.from_local_datetime(&local) {
+        LocalResult::Single(t) | LocalResult::Ambiguous(t, _) => t.with_timezone(&Utc),
+        LocalResult::None => {
+            let before = tz.offset_from_utc_datetime(&(local - Duration::days(1))).fix();
+            (local - Duration::seconds(before.local_minus_utc().into())).and_utc()
+        }
+    }
+}
+
+fn month_end(first: NaiveDate) -> NaiveDate {
+    first
+        .checked_add_months(Months::new(1))
+        .and_then(|d| d.pred_opt())
+        .unwrap_or(NaiveDate::MAX)
+}
+
+impl RRule {
+    /// Occurrences in order, starting with `dtstart` itself.
+    pub fn occurrences(&self, tz: Tz, dtstart: DateTime<Utc>) -> Occurrences<'_> {
+        Occurrences {
+            rule: self,
+            tz,
+            local: dtstart.with_timezone(&tz).naive_local(),
+            start: dtstart,
+            period: 0,
+            empty: 0,
+            max_empty: (MAX_EMPTY_YEARS * self.freq.per_year()).div_ceil(self.interval.max(1)),
+            queue: VecDeque::new(),
+            emitted: 0,
+            done: false,
+        }
+    }
+
+    /// The `n`th occurrence, counting `dtstart` as 1, if the rule gets there.
+    pub fn nth(&self, tz: Tz, dtstart: DateTime<Utc>, n: u32) -> Option<DateTime<Utc>> {
+        let n = usize::try_from(n).ok()?.checked_sub(1)?;
+        self.occurrences(tz, dtstart).nth(n)
+    }
+
+    /// Candidate days of period `k` (counted in FREQ * INTERVAL steps from
+    /// `first`), sorted, with BYSETPOS applied. None once dates run out.
+    fn dates(&self, first: NaiveDate, k: u32) -> Option<Vec<NaiveDate>> {
+        let step = k.checked_mul(self.interval)?;
+        let mut days = match self.freq {
+            Freq::Daily => {
+                let d = first.checked_add_days(Days::new(step.into()))?;
+                let by_day = self.by_day.is_empty() || self.by_day.iter().any(|b| b.weekday == d.weekday());
+                if by_day && self.month_ok(d) && self.month_day_ok(d) {
+                    vec![d]
+                } else {
+                    vec![]
+                }
+            }
+            Freq::Weekly => {
+                let back = (7 + first.weekday().num_days_from_monday() - self.wkst.num_days_from_monday()) % 7;
+                let week = first
+                    .checked_sub_days(Days::new(back.into()))?
+                    .checked_add_days(Days::new(u64::from(step) * 7))?;
+                let wanted: Vec<Weekday> = if self.by_day.is_empty() {
+                    vec![first.weekday()]
+                } else {
+                    self.by_day.iter().map(|b| b.weekday).collect()
+                };
+                (0..7)
+                    .filter_map(|i| week.checked_add_days(Days::new(i)))
+                    .filter(|d| wanted.contains(&d.weekday()) && self.month_ok(*d))
+                    .collect()
+            }
+            Freq::Monthly => {
+                let month = first.with_day(1)?.checked_add_months(Months::new(step))?;
+                if self.month_ok(month) {
+                    self.month_dates(month, first.day())
+                } else {
+                    vec![]
+                }
+            }
+            Freq::Yearly => {
+                let year = first.year().checked_add(i32::try_from(step).ok()?)?;
+                if !self.by_day.is_empty() && self.by_month.is_empty() && self.by_month_day.is_empty() {
+                    // weekdays across the year; ordinals count within it
+                    self.weekday_dates(NaiveDate::from_ymd_opt(year, 1, 1)?, NaiveDate::from_ymd_opt(year, 12, 31)?)
+                } else {
+                    let months: Vec<u32> = if !self.by_month.is_empty() {
+                        self.by_month.clone()
+                    } else if !self.by_month_day.is_empty() {
+                        (1..=12).collect()
+                    } else {
+                        vec![first.month()]
+                    };
+                    months
+                        .into_iter()
+                        .filter_map(|m| NaiveDate::from_ymd_opt(year, m, 1))
+                        .flat_map(|m| self.month_dates(m, first.day()))
+                        .collect()
+                }
+            }
+        };
+        days.sort();
+        days.dedup();
+        Some(self.set_pos(days))
+    }
+
+    fn month_ok(&self, d: NaiveDate) -> bool {
+        self.by_month.is_empty() || self.by_month.contains(&d.month())
+    }
+
+    fn month_day_ok(&self, d: NaiveDate) -> bool {
+        let len = month_end(d.with_day(1).unwrap_or(d)).day() as i32;
+        self.by_month_day.is_empty()
+            || self.by_month_day.iter().any(|&md| (if md < 0 { len + md + 1 } else { md }) == d.day() as i32)
+    }
+
+    /// Days of the month starting at `month` that match BYMONTHDAY and BYDAY,
+    /// or its `day`th when neither is set (skipped when the month is shorter).
+    fn month_dates(&self, month: NaiveDate, day: u32) -> Vec<NaiveDate> {
+        if self.by_month_day.is_empty() && self.by_day.is_empty() {
+            return month.with_day(day).into_iter().collect();
+        }
+        let last = month_end(month);
+        let mut days = if self.by_day.is_empty() {
+            month.iter_days().take_while(|d| *d <= last).collect()
+        } else {
+            self.weekday_dates(month, last)
+        };
+        days.retain(|d| self.month_day_ok(*d));
+        days
+    }
+
+    /// Days in `from..=to` matching BYDAY. An ordinal picks that occurrence
+    /// of its weekday within the range, counting from the end when negative.
+    fn weekday_dates(&self, from: NaiveDate, to: NaiveDate) -> Vec<NaiveDate> {
+        let mut out = Vec::new();
+        for b in &self.by_day {
+            let same: Vec<NaiveDate> = from
+                .iter_days()
+                .take_while(|d| *d <= to)
+                .filter(|d| d.weekday() == b.weekday)
+                .collect();
+            match b.nth {
+                None => out.extend(same),
+                Some(n) if n > 0 => out.extend(same.get(n as usize - 1)),
+                Some(n) => out.extend(same.len().checked_sub(n.unsigned_abs() as usize).and_then(|i| same.get(i))),
+            }
+        }
+        out
+    }
+
+    fn set_pos(&self, days: Vec<NaiveDate>) -> Vec<NaiveDate> {
+        if self.by_set_pos.is_empty() {
+            return days;
+        }
+        let mut picked: Vec<NaiveDate> = self
+            .by_set_pos
+            .iter()
+            .filter_map(|&p| {
+                let i = if p > 0 { Some(p as usize - 1) } else { days.len().checked_sub(p.unsigned_abs() as usize) };
+                i.and_then(|i| days.get(i)).copied()
+            })
+            .collect();
+        picked.sort();
+        picked.dedup();
+        picked
+    }
+}
+
+/// Iterator from [`RRule::occurrences`].
+pub struct Occurrences<'a> {
+    rule: &'a RRule,
+    tz: Tz,
+    local: NaiveDateTime, // dtstart in `tz`; every occurrence keeps its time of day
+    start: DateTime<Utc>,
+    period: u32,
+    empty: u32,
+    max_empty: u32, // MAX_EMPTY_YEARS in periods
+    queue: VecDeque<DateTime<Utc>>,
+    emitted: u32,
+    done: bool,
+}
+
+impl Occurrences<'_> {
+    fn past_until(&self, at: DateTime<Utc>) -> bool {
+        match self.rule.until {
+            Some(Until::Utc(u)) => at > u,
+            Some(Until::Local(u)) => at.with_timezone(&self.tz).naive_local() > u,
+            Some(Until::Date(u)) => at.with_timezone(&self.tz).date_naive() > u,
+            None => false,
+        }
+    }
+}
+
+impl Iterator for Occurrences<'_> {
+    type Item = DateTime<Utc>;
+
+    fn next(&mut self) -> Option<DateTime<Utc>> {
+        if self.done {
+            return None;
+        }
+        if self.emitted == 0 {
+            self.emitted = 1;
+            return Some(self.start);
+        }
+        loop {
+            if let Some(at) = self.queue.pop_front() {
+                if self.rule.count.is_some_and(|c| self.emitted >= c) || self.past_until(at) {
+                    self.done = true;
+                    return None;
+                }
+                self.emitted += 1;
+                return Some(at);
+            }
+            if self.empty >= self.max_empty {
+                self.done = true;
+                return None;
+            }
+            let Some(dates) = self.rule.dates(self.local.date(), self.period) else {
+                self.done = true;
+                return None;
+            };
+            self.period += 1;
+            let times: Vec<DateTime<Utc>> = dates
+                .into_iter()
+                .map(|d| resolve(self.tz, d.and_time(self.local.time())))
+                .filter(|at| *at > self.start)
+                .collect();
+            if times.is_empty() {
+                self.empty += 1;
+            } else {
+                self.empty = 0;
+                self.queue.extend(times);
+            }
+        }
+    }
+}
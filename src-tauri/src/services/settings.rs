@@ -0,0 +1,42 @@
+// src/services/settings.rs
+use chrono::Utc;
+use serde::{de::DeserializeOwned, Serialize};
+use sqlx::{Executor, Sqlite};
+
+use crate::{AppError, Result};
+
+/// Read a JSON setting, `None` if it was never written.
+pub async fn get<'e, T: DeserializeOwned>(db: impl Executor<'e, Database = Sqlite>, key: &str) -> Result<Option<T>> {
+    let raw: Option<String> = sqlx::query_scalar("SELECT value FROM app_setting WHERE key = ?1")
+        .bind(key)
+        .fetch_optional(db)
+        .await?;
+
+    raw.map(|s| serde_json::from_str(&s))
+        .transpose()
+        .map_err(|e| AppError::Other(format!("setting {key}: {e}")))
+}
+
+/// Read a setting or fall back to the type's default.
+pub async fn get_or_default<'e, T: DeserializeOwned + Default>(db: impl Executor<'e, Database = Sqlite>, key: &str) -> Result<T> {
+    Ok(get(db, key).await?.unwrap_or_default())
+}
+
+/// Upsert a JSON setting; pass `&mut *tx` to write it with other changes.
+pub async fn put<'e, T: Serialize>(db: impl Executor<'e, Database = Sqlite>, key: &str, value: &T) -> Result<()> {
+    let raw = serde_json::to_string(value).map_err(|e| AppError::Other(format!("setting {key}: {e}")))?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO app_setting (key, value, updated_at) VALUES (?1, ?2, ?3)
+        ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(key)
+    .bind(raw)
+    .bind(Utc::now())
+    .execute(db)
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,329 @@
+// src/services/stages.rs
+//! Ordered stages of a task (`task_stages`).
+//!
+//! `tasks.current_stage` holds the name of the last stage reached, so stage
+//! names are unique per task and renames carry over to it. `ord` is kept
+//! dense (0..n) and progress is read off it.
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqlitePool};
+use uuid::Uuid;
+
+use crate::events::{self, ChangeOp, EntityKind};
+use crate::services::journal::{self, Recorder};
+use crate::services::projects;
+use crate::services::tasks::{self, TaskStatus};
+use crate::{AppError, Result};
+
+// ---------- Types ----------
+
+#[derive(Debug, Serialize)]
+pub struct Stage {
+    pub id: String,
+    pub task_id: String,
+    pub name: String,
+    pub summary: Option<String>,
+    pub ord: i64,
+}
+
+#[derive(Deserialize)]
+pub struct StageInput {
+    pub task_id: String,
+    pub name: String,
+    pub summary: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct UpdateStageInput {
+    pub id: String,
+    pub name: Option<String>,
+    pub summary: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StageProgress {
+    pub task_id: String,
+    pub current_stage: Option<String>,
+    pub reached: usize, // stages up to and including the current one
+    pub total: usize,
+    pub percent: u8,
+}
+
+// ---------- Helpers ----------
+
+fn stage_from_row(r: &sqlx::sqlite::SqliteRow) -> Stage {
+    Stage {
+        id: r.get("id"),
+        task_id: r.get("task_id"),
+        name: r.get("name"),
+        summary: r.get("summary"),
+        ord: r.get("ord"),
+    }
+}
+
+async fn current_stage(pool: &SqlitePool, task_id: &str) -> Result<Option<String>> {
+    let row = sqlx::query("SELECT current_stage FROM tasks WHERE id = ?1")
+        .bind(task_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::not_found("task", task_id))?;
+    Ok(row.get("current_stage"))
+}
+
+async fn get_stage(pool: &SqlitePool, id: &str) -> Result<Stage> {
+    sqlx::query("SELECT id, task_id, name, summary, ord FROM task_stages WHERE id = ?1")
+        .bind(id)
+        .fetch_optional(pool)
+        .await?
+        .map(|r| stage_from_row(&r))
+        .ok_or_else(|| AppError::not_found("stage", id))
+}
+
+/// A trashed task's stages stay as they are until it is restored.
+async fn ensure_live(pool: &SqlitePool, task_id: &str) -> Result<()> {
+    if !tasks::is_live(pool, task_id).await? {
+        return Err(AppError::not_found("task", task_id));
+    }
+    Ok(())
+}
+
+fn check_name(stages: &[Stage], name: &str, except: Option<&str>) -> Result<()> {
+    if name.trim().is_empty() {
+        return Err(AppError::validation("name", "Stage name is required"));
+    }
+    if stages.iter().any(|s| s.name == name && Some(s.id.as_str()) != except) {
+        return Err(AppError::conflict("stage", "name", "This task already has a stage with that name"));
+    }
+    Ok(())
+}
+
+/// `current_stage` must name one of the task's stages once it has any.
+pub(crate) async fn check_current(pool: &SqlitePool, task_id: &str, name: &str) -> Result<()> {
+    let stages = list_stages(pool, task_id).await?;
+    if !stages.is_empty() && !stages.iter().any(|s| s.name == name) {
+        return Err(AppError::validation("current_stage", "Not one of this task's stages"));
+    }
+    Ok(())
+}
+
+fn progress(task_id: &str, stages: &[Stage], current: Option<String>) -> StageProgress {
+    let reached = current
+        .as_ref()
+        .and_then(|c| stages.iter().position(|s| &s.name == c))
+        .map_or(0, |i| i + 1);
+    let total = stages.len();
+    let percent = (reached * 100).checked_div(total).unwrap_or(0) as u8;
+    StageProgress { task_id: task_id.to_string(), current_stage: current, reached, total, percent }
+}
+
+// ---------- Queries ----------
+
+pub async fn list_stages(pool: &SqlitePool, task_id: &str) -> Result<Vec<Stage>> {
+    let rows = sqlx::query("SELECT id, task_id, name, summary, ord FROM task_stages WHERE task_id = ?1 ORDER BY ord")
+        .bind(task_id)
+        .fetch_all(pool)
+        .await?;
+    Ok(rows.iter().map(stage_from_row).collect())
+}
+
+pub async fn stage_progress(pool: &SqlitePool, task_id: &str) -> Result<StageProgress> {
+    let current = current_stage(pool, task_id).await?;
+    let stages = list_stages(pool, task_id).await?;
+    Ok(progress(task_id, &stages, current))
+}
+
+// ---------- Commands ----------
+
+/// Append a stage to the end of the task's list.
+pub async fn add_stage(pool: &SqlitePool, input: StageInput) -> Result<String> {
+    if !tasks::is_live(pool, &input.task_id).await? {
+        return Err(AppError::foreign_key("task", "task_id", input.task_id));
+    }
+    let stages = list_stages(pool, &input.task_id).await?;
+    check_name(&stages, input.name.trim(), None)?;
+    projects::ensure_writable(pool, "tasks", &input.task_id).await?;
+
+    let id = Uuid::new_v4().to_string();
+    let mut tx = journal::begin(pool).await?;
+    let mut op = Recorder::new("Add stage");
+    op.track(&mut tx, "task_stages", &id).await?;
+
+    sqlx::query("INSERT INTO task_stages (id, task_id, name, summary, ord) VALUES (?1, ?2, ?3, ?4, ?5)")
+        .bind(&id)
+        .bind(&input.task_id)
+        .bind(input.name.trim())
+        .bind(&input.summary)
+        .bind(stages.len() as i64)
+        .execute(&mut *tx)
+        .await?;
+
+    op.commit(&mut tx).await?;
+    tx.commit().await?;
+    events::publish(EntityKind::Task, &input.task_id, ChangeOp::Updated);
+
+    Ok(id)
+}
+
+/// Rename a stage or change its summary. Renaming the current stage renames
+/// `current_stage` with it.
+pub async fn update_stage(pool: &SqlitePool, input: UpdateStageInput) -> Result<()> {
+    let stage = get_stage(pool, &input.id).await?;
+    ensure_live(pool, &stage.task_id).await?;
+    let name = input.name.map(|n| n.trim().to_string()).unwrap_or_else(|| stage.name.clone());
+    let summary = input.summary.or(stage.summary);
+    check_name(&list_stages(pool, &stage.task_id).await?, &name, Some(&stage.id))?;
+    projects::ensure_writable(pool, "tasks", &stage.task_id).await?;
+    let is_current = current_stage(pool, &stage.task_id).await?.as_deref() == Some(stage.name.as_str());
+
+    let mut tx = journal::begin(pool).await?;
+    let mut op = Recorder::new("Edit stage");
+    op.track(&mut tx, "task_stages", &stage.id).await?;
+    op.track(&mut tx, "tasks", &stage.task_id).await?;
+
+    sqlx::query("UPDATE task_stages SET name = ?1, summary = ?2 WHERE id = ?3")
+        .bind(&name)
+        .bind(&summary)
+        .bind(&stage.id)
+        .execute(&mut *tx)
+        .await?;
+    if is_current && name != stage.name {
+        sqlx::query("UPDATE tasks SET current_stage = ?1, updated_at = ?2 WHERE id = ?3")
+            .bind(&name)
+            .bind(Utc::now())
+            .bind(&stage.task_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    op.commit(&mut tx).await?;
+    tx.commit().await?;
+    events::publish(EntityKind::Task, &stage.task_id, ChangeOp::Updated);
+
+    Ok(())
+}
+
+/// Put a task's stages in the given order; `ids` must list each exactly once.
+pub async fn reorder_stages(pool: &SqlitePool, task_id: &str, ids: &[String]) -> Result<()> {
+    ensure_live(pool, task_id).await?;
+    let stages = list_stages(pool, task_id).await?;
+    let mut given: Vec<&str> = ids.iter().map(String::as_str).collect();
+    let mut have: Vec<&str> = stages.iter().map(|s| s.id.as_str()).collect();
+    given.sort_unstable();
+    have.sort_unstable();
+    if given != have {
+        return Err(AppError::validation("ids", "List every stage of the task exactly once"));
+    }
+    projects::ensure_writable(pool, "tasks", task_id).await?;
+
+    let mut tx = journal::begin(pool).await?;
+    let mut op = Recorder::new("Reorder stages");
+    for s in &stages {
+        op.track(&mut tx, "task_stages", &s.id).await?;
+    }
+
+    for (ord, id) in ids.iter().enumerate() {
+        sqlx::query("UPDATE task_stages SET ord = ?1 WHERE id = ?2")
+            .bind(ord as i64)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    op.commit(&mut tx).await?;
+    tx.commit().await?;
+    events::publish(EntityKind::Task, task_id, ChangeOp::Updated);
+
+    Ok(())
+}
+
+/// Remove a stage. If it was the current one, the stage before it (if any)
+/// becomes current.
+pub async fn remove_stage(pool: &SqlitePool, id: &str) -> Result<()> {
+    let stage = get_stage(pool, id).await?;
+    ensure_live(pool, &stage.task_id).await?;
+    projects::ensure_writable(pool, "tasks", &stage.task_id).await?;
+    let stages = list_stages(pool, &stage.task_id).await?;
+    let is_current = current_stage(pool, &stage.task_id).await?.as_deref() == Some(stage.name.as_str());
+    let previous = stages.iter().rev().find(|s| s.ord < stage.ord).map(|s| s.name.clone());
+
+    let mut tx = journal::begin(pool).await?;
+    let mut op = Recorder::new("Remove stage");
+    for s in &stages {
+        op.track(&mut tx, "task_stages", &s.id).await?;
+    }
+    op.track(&mut tx, "tasks", &stage.task_id).await?;
+
+    sqlx::query("DELETE FROM task_stages WHERE id = ?1")
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("UPDATE task_stages SET ord = ord - 1 WHERE task_id = ?1 AND ord > ?2")
+        .bind(&stage.task_id)
+        .bind(stage.ord)
+        .execute(&mut *tx)
+        .await?;
+    if is_current {
+        sqlx::query("UPDATE tasks SET current_stage = ?1, updated_at = ?2 WHERE id = ?3")
+            .bind(&previous)
+            .bind(Utc::now())
+            .bind(&stage.task_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    op.commit(&mut tx).await?;
+    tx.commit().await?;
+    events::publish(EntityKind::Task, &stage.task_id, ChangeOp::Updated);
+
+    Ok(())
+}
+
+/// Mark the next stage reached: it becomes `current_stage` and the task goes
+/// to `stage-complete`, subject to the usual status transition checks.
+pub async fn advance_stage(pool: &SqlitePool, task_id: &str) -> Result<StageProgress> {
+    ensure_live(pool, task_id).await?;
+    let current = current_stage(pool, task_id).await?;
+    let stages = list_stages(pool, task_id).await?;
+    if stages.is_empty() {
+        return Err(AppError::validation("task_id", "This task has no stages"));
+    }
+    let next = match current.as_ref().and_then(|c| stages.iter().position(|s| &s.name == c)) {
+        Some(i) if i + 1 == stages.len() => {
+            return Err(AppError::conflict("task", "current_stage", "The last stage is already complete"));
+        }
+        Some(i) => &stages[i + 1],
+        None => &stages[0],
+    };
+    let from = tasks::current_status(pool, task_id).await?;
+    let to = TaskStatus::StageComplete;
+    if from != to {
+        tasks::check_transition(pool, from, to, None).await?;
+    }
+    projects::ensure_writable(pool, "tasks", task_id).await?;
+
+    let reason_id = Uuid::new_v4().to_string();
+    let mut tx = journal::begin(pool).await?;
+    let mut op = Recorder::new("Advance stage");
+    op.track(&mut tx, "tasks", task_id).await?;
+    if from != to {
+        op.track(&mut tx, "status_reasons", &reason_id).await?;
+    }
+
+    sqlx::query("UPDATE tasks SET current_stage = ?1, status = ?2, updated_at = ?3 WHERE id = ?4")
+        .bind(&next.name)
+        .bind(to.to_string())
+        .bind(Utc::now())
+        .bind(task_id)
+        .execute(&mut *tx)
+        .await?;
+    if from != to {
+        tasks::log_transition(&mut tx, &reason_id, task_id, from, to, None).await?;
+    }
+
+    op.commit(&mut tx).await?;
+    tx.commit().await?;
+    events::publish(EntityKind::Task, task_id, ChangeOp::Updated);
+    tasks::publish_linked_ideas(pool, task_id).await?;
+
+    Ok(progress(task_id, &stages, Some(next.name.clone())))
+}
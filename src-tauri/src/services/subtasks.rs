@@ -0,0 +1,316 @@
+// src/services/subtasks.rs
+//! Nested subtasks via `tasks.parent_id`, and progress rollup.
+//!
+//! A subtask always lives in its parent's project, so moving happens per
+//! tree (`tasks::move_task` on the root). Progress comes from the leaves: a
+//! completed task counts 100%, otherwise its stage progress; a parent
+//! averages its children that were not dropped, weighted by their estimates
+//! when every one of them has one.
+use std::collections::HashMap;
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sqlx::{Executor, Row, Sqlite, SqliteConnection, SqlitePool};
+use uuid::Uuid;
+
+use crate::events::{self, ChangeOp, EntityKind};
+use crate::services::journal::{self, Recorder};
+use crate::services::projects;
+use crate::services::tasks::{self, TaskStatus};
+use crate::services::time::ENTRY_MINUTES;
+use crate::{db, AppError, Result};
+
+/// Ids of task `?1` and all its descendants, trashed ones included. Usable as
+/// an `IN (...)` subquery.
+pub(crate) const SUBTREE: &str = r#"
+    WITH RECURSIVE sub(id) AS (
+      SELECT ?1
+      UNION
+      SELECT t.id FROM tasks t JOIN sub ON t.parent_id = sub.id
+    )
+    SELECT id FROM sub
+"#;
+
+// ---------- Types ----------
+
+#[derive(Debug, Serialize)]
+pub struct Subtask {
+    pub id: String,
+    pub name: String,
+    pub status: String,
+    pub ord: i64,
+    pub progress: u8, // 0..=100, rolled up from its own subtasks
+    pub estimate_minutes: Option<i64>, // own, else the sum of its subtasks'
+    pub actual_minutes: i64,           // logged on it and its subtasks
+    pub subtasks: Vec<Subtask>,
+}
+
+#[derive(Deserialize)]
+pub struct SetParentInput {
+    pub id: String,
+    pub parent_id: Option<String>, // None makes it a top-level task again
+}
+
+struct Node {
+    id: String,
+    parent_id: Option<String>,
+    name: String,
+    status: String,
+    ord: i64,
+    stage_pct: u8,
+    estimate: Option<i64>,
+    logged: f64, // minutes logged on this task itself
+}
+
+// ---------- Rollup ----------
+
+fn rollup(status: &str, stage_pct: u8, children: &[Subtask]) -> u8 {
+    if status == "completed" {
+        return 100;
+    }
+    let live: Vec<&Subtask> = children.iter().filter(|c| c.status != "dropped").collect();
+    if live.is_empty() {
+        return stage_pct;
+    }
+    let weighted = live.iter().all(|c| c.estimate_minutes.is_some_and(|e| e > 0));
+    let weight = |c: &Subtask| if weighted { c.estimate_minutes.unwrap_or(1) } else { 1 };
+    let total: i64 = live.iter().map(|c| weight(c)).sum();
+    let done: i64 = live.iter().map(|c| weight(c) * c.progress as i64).sum();
+    (done / total) as u8
+}
+
+/// Returns the node plus the unrounded minutes logged across its subtree.
+fn build(id: &str, nodes: &HashMap<String, Node>, children: &HashMap<String, Vec<String>>) -> (Subtask, f64) {
+    let n = &nodes[id];
+    let (subtasks, logged): (Vec<Subtask>, Vec<f64>) =
+        children.get(id).into_iter().flatten().map(|c| build(c, nodes, children)).unzip();
+    let logged = n.logged + logged.iter().sum::<f64>();
+    let estimate = n.estimate.or_else(|| {
+        let parts: Vec<i64> =
+            subtasks.iter().filter(|c| c.status != "dropped").filter_map(|c| c.estimate_minutes).collect();
+        (!parts.is_empty()).then(|| parts.iter().sum())
+    });
+    let node = Subtask {
+        id: n.id.clone(),
+        name: n.name.clone(),
+        status: n.status.clone(),
+        ord: n.ord,
+        progress: rollup(&n.status, n.stage_pct, &subtasks),
+        estimate_minutes: estimate,
+        actual_minutes: logged.round() as i64,
+        subtasks,
+    };
+    (node, logged)
+}
+
+/// Task `id` itself as the root of its live subtree, with rollups.
+pub async fn subtree(pool: &SqlitePool, id: &str) -> Result<Subtask> {
+    let rows = sqlx::query(&format!(
+        r#"
+        WITH RECURSIVE sub(id, depth) AS (
+          SELECT ?1, 0
+          UNION
+          SELECT t.id, sub.depth + 1 FROM tasks t JOIN sub ON t.parent_id = sub.id
+          WHERE t.deleted_at IS NULL
+        )
+        SELECT t.id, t.parent_id, t.name, t.status, t.ord, t.estimate_minutes,
+               (SELECT s.ord + 1 FROM task_stages s WHERE s.task_id = t.id AND s.name = t.current_stage) AS reached,
+               (SELECT COUNT(*) FROM task_stages s WHERE s.task_id = t.id) AS total,
+               (SELECT COALESCE(SUM({ENTRY_MINUTES}), 0.0) FROM time_entry e
+                WHERE e.task_id = t.id AND e.ended_at IS NOT NULL) AS logged
+        FROM sub JOIN tasks t ON t.id = sub.id
+        ORDER BY sub.depth, t.ord, t.created_at
+        "#
+    ))
+    .bind(id)
+    .fetch_all(pool)
+    .await?;
+
+    let mut nodes = HashMap::new();
+    let mut children: HashMap<String, Vec<String>> = HashMap::new();
+    for r in &rows {
+        let (reached, total): (Option<i64>, i64) = (r.get("reached"), r.get("total"));
+        let node = Node {
+            id: r.get("id"),
+            parent_id: r.get("parent_id"),
+            name: r.get("name"),
+            status: r.get("status"),
+            ord: r.get("ord"),
+            stage_pct: (reached.unwrap_or(0) * 100).checked_div(total).unwrap_or(0) as u8,
+            estimate: r.get("estimate_minutes"),
+            logged: r.get("logged"),
+        };
+        if node.id != id {
+            if let Some(p) = &node.parent_id {
+                children.entry(p.clone()).or_default().push(node.id.clone());
+            }
+        }
+        nodes.insert(node.id.clone(), node);
+    }
+    if !nodes.contains_key(id) {
+        return Err(AppError::not_found("task", id));
+    }
+
+    Ok(build(id, &nodes, &children).0)
+}
+
+// ---------- Commands ----------
+
+/// Where a new subtask of `parent_id` goes: its project, workspace and `ord`.
+pub(crate) async fn placement<'e>(conn: impl Executor<'e, Database = Sqlite>, parent_id: &str) -> Result<(Option<String>, Option<String>, i64)> {
+    let row = sqlx::query(
+        r#"
+        SELECT project_id, workspace_id,
+               (SELECT COALESCE(MAX(ord) + 1, 0) FROM tasks WHERE parent_id = ?1) AS next_ord
+        FROM tasks WHERE id = ?1 AND deleted_at IS NULL
+        "#,
+    )
+    .bind(parent_id)
+    .fetch_optional(conn)
+    .await?
+    .ok_or_else(|| AppError::foreign_key("task", "parent_id", parent_id))?;
+    Ok((row.get("project_id"), row.get("workspace_id"), row.get("next_ord")))
+}
+
+/// Nest a task under another one (appended last), or lift it to the top level.
+pub async fn set_task_parent(pool: &SqlitePool, input: SetParentInput) -> Result<()> {
+    let row = sqlx::query("SELECT project_id, workspace_id FROM tasks WHERE id = ?1")
+        .bind(&input.id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::not_found("task", &input.id))?;
+    let scope: (Option<String>, Option<String>) = (row.get("project_id"), row.get("workspace_id"));
+
+    let ord = match &input.parent_id {
+        Some(parent) => {
+            let (project_id, workspace_id, ord) = placement(pool, parent).await?;
+            let inside: bool = sqlx::query_scalar(&format!("SELECT ?2 IN ({SUBTREE})"))
+                .bind(&input.id)
+                .bind(parent)
+                .fetch_one(pool)
+                .await?;
+            if inside {
+                return Err(AppError::validation("parent_id", "A task can't go under itself or its own subtasks"));
+            }
+            if (project_id, workspace_id) != scope {
+                return Err(AppError::validation("parent_id", "Move the task to its new parent's project first"));
+            }
+            projects::ensure_writable(pool, "tasks", parent).await?;
+            ord
+        }
+        None => 0,
+    };
+    projects::ensure_writable(pool, "tasks", &input.id).await?;
+
+    let mut tx = journal::begin(pool).await?;
+    let mut op = Recorder::new("Nest task");
+    op.track(&mut tx, "tasks", &input.id).await?;
+
+    sqlx::query("UPDATE tasks SET parent_id = ?1, ord = ?2, updated_at = ?3 WHERE id = ?4")
+        .bind(&input.parent_id)
+        .bind(ord)
+        .bind(Utc::now())
+        .bind(&input.id)
+        .execute(&mut *tx)
+        .await?;
+
+    op.commit(&mut tx).await?;
+    tx.commit().await?;
+    events::publish(EntityKind::Task, &input.id, ChangeOp::Updated);
+
+    Ok(())
+}
+
+/// Put a task's direct subtasks in the given order; `ids` must list each
+/// live one exactly once.
+pub async fn reorder_subtasks(pool: &SqlitePool, parent_id: &str, ids: &[String]) -> Result<()> {
+    if !db::row_exists(pool, "tasks", parent_id).await? {
+        return Err(AppError::not_found("task", parent_id));
+    }
+    let mut have: Vec<String> = sqlx::query_scalar("SELECT id FROM tasks WHERE parent_id = ?1 AND deleted_at IS NULL")
+        .bind(parent_id)
+        .fetch_all(pool)
+        .await?;
+    let mut given = ids.to_vec();
+    have.sort_unstable();
+    given.sort_unstable();
+    if given != have {
+        return Err(AppError::validation("ids", "List every subtask of the task exactly once"));
+    }
+    projects::ensure_writable(pool, "tasks", parent_id).await?;
+
+    let mut tx = journal::begin(pool).await?;
+    let mut op = Recorder::new("Reorder subtasks");
+    for id in ids {
+        op.track(&mut tx, "tasks", id).await?;
+    }
+
+    for (ord, id) in ids.iter().enumerate() {
+        sqlx::query("UPDATE tasks SET ord = ?1 WHERE id = ?2")
+            .bind(ord as i64)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    op.commit(&mut tx).await?;
+    tx.commit().await?;
+    events::publish(EntityKind::Task, parent_id, ChangeOp::Updated);
+
+    Ok(())
+}
+
+/// With `complete_parent` on in the status rules, complete each ancestor of
+/// `id` whose live subtasks are now all completed or dropped (and not all
+/// dropped), as part of the caller's status change and op. Stops at the
+/// first parent that is not done or can't move. Returns the parents it
+/// completed; the caller publishes them after committing.
+pub(crate) async fn complete_parents(conn: &mut SqliteConnection, op: &mut Recorder, id: &str) -> Result<Vec<String>> {
+    let mut done = Vec::new();
+    if !tasks::get_status_rules(&mut *conn).await?.complete_parent {
+        return Ok(done);
+    }
+
+    let mut child = id.to_string();
+    loop {
+        let parent: Option<String> = sqlx::query_scalar("SELECT parent_id FROM tasks WHERE id = ?1")
+            .bind(&child)
+            .fetch_optional(&mut *conn)
+            .await?
+            .flatten();
+        let Some(parent) = parent else { return Ok(done) };
+
+        let (open, completed): (i64, i64) = sqlx::query_as(
+            r#"
+            SELECT COALESCE(SUM(status NOT IN ('completed', 'dropped')), 0),
+                   COALESCE(SUM(status = 'completed'), 0)
+            FROM tasks WHERE parent_id = ?1 AND deleted_at IS NULL
+            "#,
+        )
+        .bind(&parent)
+        .fetch_one(&mut *conn)
+        .await?;
+        // the note below satisfies any note rule, and the parent shares the
+        // child's project, which the caller already found writable
+        let from = tasks::current_status(&mut *conn, &parent).await?;
+        if open > 0 || completed == 0 || from == TaskStatus::Completed || !from.can_move_to(TaskStatus::Completed) {
+            return Ok(done);
+        }
+
+        let reason_id = Uuid::new_v4().to_string();
+        op.track(conn, "tasks", &parent).await?;
+        op.track(conn, "status_reasons", &reason_id).await?;
+
+        sqlx::query("UPDATE tasks SET status = ?1, updated_at = ?2 WHERE id = ?3")
+            .bind(TaskStatus::Completed.to_string())
+            .bind(Utc::now())
+            .bind(&parent)
+            .execute(&mut *conn)
+            .await?;
+        let note = Some("All subtasks done");
+        tasks::log_transition(conn, &reason_id, &parent, from, TaskStatus::Completed, note).await?;
+
+        done.push(parent.clone());
+        child = parent;
+    }
+}
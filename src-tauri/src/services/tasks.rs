@@ -0,0 +1,886 @@
+use std::{fmt, str::FromStr};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{ Executor, Sqlite, SqlitePool, SqliteConnection, Row};
+use uuid::Uuid;
+
+use crate::events::{self, ChangeOp, EntityKind};
+use crate::services::journal::{self, Recorder};
+use crate::services::dependencies::{self, LinkedTask};
+use crate::services::projects;
+use crate::services::recurrence::{self, Recurrence, RecurrenceInput, Template};
+use crate::services::settings;
+use crate::services::stages;
+use crate::services::subtasks::{self, Subtask};
+use crate::services::trash;
+use crate::{db, AppError, Result};
+
+/// Where tasks created without a project or workspace land.
+pub const DEFAULT_PROJECT: &str = "prj_personal_general";
+
+pub const STATUS_RULES_KEY: &str = "status_rules";
+
+// ---------- Types ----------
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum TaskStatus {
+    Todo,
+    Started,
+    InProgress,
+    StageComplete,
+    Completed,
+    Dropped,
+}
+impl fmt::Display for TaskStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TaskStatus::Todo => "todo",
+            TaskStatus::Started => "started",
+            TaskStatus::InProgress => "in-progress",
+            TaskStatus::StageComplete => "stage-complete",
+            TaskStatus::Completed => "completed",
+            TaskStatus::Dropped => "dropped",
+        })
+    }
+}
+
+impl FromStr for TaskStatus {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "todo" => Ok(TaskStatus::Todo),
+            "started" => Ok(TaskStatus::Started),
+            "in-progress" => Ok(TaskStatus::InProgress),
+            "stage-complete" => Ok(TaskStatus::StageComplete),
+            "completed" => Ok(TaskStatus::Completed),
+            "dropped" => Ok(TaskStatus::Dropped),
+            other => Err(AppError::validation("status", format!("Unknown task status '{other}'"))),
+        }
+    }
+}
+
+impl TaskStatus {
+    /// The state machine: which statuses a task may move to from `self`.
+    /// Finished tasks can only be reopened.
+    pub fn can_move_to(self, to: TaskStatus) -> bool {
+        use TaskStatus::*;
+        match self {
+            Todo => matches!(to, Started | InProgress | StageComplete | Completed | Dropped),
+            Started => matches!(to, Todo | InProgress | StageComplete | Completed | Dropped),
+            InProgress => matches!(to, Started | StageComplete | Completed | Dropped),
+            StageComplete => matches!(to, InProgress | Completed | Dropped),
+            Completed => matches!(to, Todo | InProgress),
+            Dropped => matches!(to, Todo),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct TaskInput {
+    pub name: String,
+    pub category: String,
+    pub short_summary: Option<String>,
+    pub description: Option<String>,
+    pub status: TaskStatus,            // default to todo on the frontend if you want
+    pub current_stage: Option<String>, // stage name
+    pub start_at: Option<String>,      // ISO 8601
+    pub end_est_at: Option<String>,    // ISO 8601
+    #[serde(default)]
+    pub project_id: Option<String>,    // workspace follows the project
+    #[serde(default)]
+    pub workspace_id: Option<String>,  // workspace-level task when no project is given
+    #[serde(default)]
+    pub parent_id: Option<String>,     // makes it a subtask; project comes from the parent
+    #[serde(default)]
+    pub estimate_minutes: Option<i64>,
+    #[serde(default)]
+    pub recurrence: Option<RecurrenceInput>, // repeats from start_at, else end_est_at
+}
+
+#[derive(Debug, Serialize)]
+pub struct TaskRow {
+    pub id: String,
+    pub name: String,
+    pub category: String,
+    pub status: String,
+    pub current_stage: Option<String>,
+    pub start_at: Option<DateTime<Utc>>,
+    pub end_est_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+    pub project_id: Option<String>,
+    pub workspace_id: Option<String>,
+    pub parent_id: Option<String>,
+    pub estimate_minutes: Option<i64>,
+    pub series_id: Option<String>, // set on occurrences of a recurring task
+    pub blocked: bool,             // some predecessor isn't completed yet
+}
+
+#[derive(Debug, Serialize)]
+pub struct TaskDetail {
+    pub id: String,
+    pub name: String,
+    pub category: String,
+    pub short_summary: Option<String>,
+    pub description: Option<String>,
+    pub status: String,
+    pub current_stage: Option<String>,
+    pub start_at: Option<DateTime<Utc>>,
+    pub end_est_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub project_id: Option<String>,
+    pub workspace_id: Option<String>,
+    pub origin_idea: Option<OriginIdea>, // set when created from an idea
+    pub parent_id: Option<String>,
+    pub progress: u8,            // 0..=100, rolled up from subtasks
+    pub subtasks: Vec<Subtask>,  // whole live subtree, in order
+    pub estimate_minutes: Option<i64>, // this task's own estimate
+    pub time: TimeSummary,
+    pub recurrence: Option<Recurrence>,
+    pub blocked: bool,
+    pub blocked_by: Vec<LinkedTask>, // predecessors, finished or not
+    pub blocks: Vec<LinkedTask>,
+}
+
+/// Estimate vs actual over a task's subtree. The estimate is the task's own,
+/// or the sum of its subtasks' when it has none; actual counts all logged work.
+#[derive(Debug, Serialize)]
+pub struct TimeSummary {
+    pub estimate_minutes: Option<i64>,
+    pub actual_minutes: i64,
+    pub variance_minutes: Option<i64>, // actual - estimate; positive = over
+}
+
+#[derive(Debug, Serialize)]
+pub struct OriginIdea {
+    pub id: String,
+    pub title: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StatusReason {
+    pub id: String,
+    pub task_id: String,
+    pub from_status: Option<String>, // None for notes added with add_reason
+    pub status: String,
+    pub note: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// What a status change has to say besides succeeding.
+#[derive(Debug, Serialize, Default)]
+pub struct StatusChange {
+    pub warning: Option<String>, // e.g. started while still blocked
+}
+
+/// A transition that needs a note; an unset end matches any status.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NoteRule {
+    pub from: Option<TaskStatus>,
+    pub to: Option<TaskStatus>,
+}
+
+/// User-editable status rules, stored under [`STATUS_RULES_KEY`].
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StatusRules {
+    pub require_note: Vec<NoteRule>,
+    #[serde(default)]
+    pub complete_parent: bool, // complete a task once all its subtasks are
+    #[serde(default)]
+    pub note_to_start_blocked: bool, // starting a task with open predecessors takes a note
+}
+impl Default for StatusRules {
+    fn default() -> Self {
+        StatusRules {
+            require_note: vec![
+                NoteRule { from: None, to: Some(TaskStatus::Dropped) },
+                NoteRule { from: Some(TaskStatus::Completed), to: None },
+            ],
+            complete_parent: false,
+            note_to_start_blocked: false,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ReasonInput {
+    pub task_id: String,
+    pub status: TaskStatus,
+    pub note: String,
+}
+
+/// `list_tasks` filters; all optional and combined with AND. `from`/`to`
+/// match tasks whose start..due window overlaps the range, so undated tasks
+/// drop out once either bound is set.
+#[derive(Deserialize, Default)]
+pub struct TaskFilter {
+    pub project_id: Option<String>,
+    pub workspace_id: Option<String>,
+    pub status: Option<TaskStatus>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub blocked: Option<bool>,
+}
+
+#[derive(Deserialize)]
+pub struct MoveTaskInput {
+    pub id: String,
+    pub project_id: String,
+}
+
+#[derive(Deserialize)]
+pub struct UpdateTaskInput {
+    pub id: String,
+    pub name: Option<String>,
+    pub category: Option<String>,
+    pub short_summary: Option<String>,
+    pub description: Option<String>,
+    pub status: Option<TaskStatus>,
+    pub current_stage: Option<String>,
+    pub start_at:  Option<DateTime<Utc>>,
+    pub end_est_at:  Option<DateTime<Utc>>,
+    pub estimate_minutes: Option<i64>,
+    pub status_note: Option<String>, // reason for a status change, when the rules ask for one
+}
+
+pub async fn add_task(pool: &SqlitePool, input: TaskInput) -> Result<String> {
+    let id = Uuid::new_v4().to_string();
+    let now = Utc::now();
+    let status_str = input.status.to_string();
+
+    if input.name.trim().is_empty() {
+        return Err(AppError::validation("name", "Task name is required"));
+    }
+    if input.category.trim().is_empty() {
+        return Err(AppError::validation("category", "Task category is required"));
+    }
+    check_estimate(input.estimate_minutes)?;
+    let plan = match &input.recurrence {
+        Some(_) if input.parent_id.is_some() => {
+            return Err(AppError::validation("recurrence", "Subtasks can't repeat; make their top-level task repeat"));
+        }
+        Some(r) => Some(recurrence::plan(r, parse_time("start_at", &input.start_at)?, parse_time("end_est_at", &input.end_est_at)?)?),
+        None => None,
+    };
+
+    // a subtask takes its parent's project and workspace
+    let (mut project_in, mut workspace_in, mut ord) = (input.project_id.clone(), input.workspace_id.clone(), 0);
+    if let Some(parent) = &input.parent_id {
+        let (pid, ws, next) = subtasks::placement(pool, parent).await?;
+        if project_in.is_some_and(|p| Some(p) != pid) || workspace_in.is_some_and(|w| Some(w) != ws) {
+            return Err(AppError::validation("parent_id", "Subtasks live in their parent's project"));
+        }
+        projects::ensure_writable(pool, "tasks", parent).await?;
+        (project_in, workspace_in, ord) = (pid, ws, next);
+    }
+
+    // same default the 20250817 backfill used, so no task is left unfiled
+    let project_id = match (&project_in, &workspace_in) {
+        (None, None) => Some(DEFAULT_PROJECT.to_string()),
+        (pid, _) => pid.clone(),
+    };
+    let workspace_id = match &project_id {
+        Some(pid) => {
+            let ws = project_workspace(pool, pid).await?;
+            projects::ensure_project_writable(pool, pid).await?;
+            if workspace_in.as_ref().is_some_and(|w| *w != ws) {
+                return Err(AppError::validation("workspace_id", "Project belongs to a different workspace"));
+            }
+            ws
+        }
+        None => {
+            let ws = workspace_in.unwrap_or_default();
+            if !db::row_exists(pool, "workspace", &ws).await? {
+                return Err(AppError::foreign_key("workspace", "workspace_id", ws));
+            }
+            ws
+        }
+    };
+
+    let series_id = plan.as_ref().map(|_| Uuid::new_v4().to_string());
+    let mut tx = journal::begin(pool).await?;
+    let mut op = Recorder::new("Add task");
+    if let Some(sid) = &series_id {
+        op.track(&mut tx, "task_series", sid).await?;
+    }
+    op.track(&mut tx, "tasks", &id).await?;
+
+    if let (Some(plan), Some(sid)) = (&plan, &series_id) {
+        let template = Template {
+            name: input.name.clone(),
+            category: input.category.clone(),
+            short_summary: input.short_summary.clone(),
+            description: input.description.clone(),
+            estimate_minutes: input.estimate_minutes,
+        };
+        recurrence::insert_series(&mut tx, sid, plan, &template).await?;
+    }
+    sqlx::query(
+        r#"
+        INSERT INTO tasks (
+          id, name, category, short_summary, description, status, current_stage,
+          start_at, end_est_at, created_at, updated_at, project_id, workspace_id, parent_id, ord,
+          estimate_minutes, series_id, occurrence
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18)
+        "#,
+    )
+    .bind(&id)
+    .bind(&input.name)
+    .bind(&input.category)
+    .bind(&input.short_summary)
+    .bind(&input.description)
+    .bind(&status_str)
+    .bind(&input.current_stage)
+    .bind(&input.start_at)
+    .bind(&input.end_est_at)
+    .bind(now)
+    .bind(now)
+    .bind(&project_id)
+    .bind(&workspace_id)
+    .bind(&input.parent_id)
+    .bind(ord)
+    .bind(input.estimate_minutes)
+    .bind(&series_id)
+    .bind(series_id.as_ref().map(|_| 1))
+    .execute(&mut *tx)
+    .await?;
+
+    op.commit(&mut tx).await?;
+    tx.commit().await?;
+    events::publish(EntityKind::Task, &id, ChangeOp::Created);
+
+    Ok(id)
+}
+
+pub async fn list_tasks(pool: &SqlitePool, filter: &TaskFilter) -> Result<Vec<TaskRow>> {
+    if let (Some(from), Some(to)) = (filter.from, filter.to) {
+        if from > to {
+            return Err(AppError::validation("to", "End of range is before its start"));
+        }
+    }
+
+    let rows = sqlx::query(&format!(
+        r#"
+        SELECT * FROM (
+          SELECT id, name, category, status, current_stage, start_at, end_est_at, updated_at,
+                 project_id, workspace_id, parent_id, estimate_minutes, series_id,
+                 {} AS blocked
+          FROM tasks t
+          WHERE deleted_at IS NULL
+            AND (?1 IS NULL OR project_id = ?1)
+            AND (?2 IS NULL OR workspace_id = ?2)
+            AND (?3 IS NULL OR status = ?3)
+            AND (?4 IS NULL OR datetime(COALESCE(end_est_at, start_at)) >= datetime(?4))
+            AND (?5 IS NULL OR datetime(COALESCE(start_at, end_est_at)) <= datetime(?5))
+        )
+        WHERE ?6 IS NULL OR blocked = ?6
+        ORDER BY updated_at DESC
+        "#,
+        dependencies::BLOCKED
+    ))
+    .bind(&filter.project_id)
+    .bind(&filter.workspace_id)
+    .bind(filter.status.map(|s| s.to_string()))
+    .bind(filter.from)
+    .bind(filter.to)
+    .bind(filter.blocked)
+    .fetch_all(pool)
+    .await?;
+
+
+    let tasks: Vec<TaskRow> = rows.into_iter().map(|row| TaskRow {
+        id: row.get("id"),
+        name: row.get("name"),
+        category: row.get("category"),
+        status: row.get("status"),
+        current_stage: row.get::<Option<String>, _>("current_stage"),
+        start_at: row.get("start_at"),
+        end_est_at: row.get("end_est_at"),
+        updated_at: row.get("updated_at"),
+        project_id: row.get("project_id"),
+        workspace_id: row.get("workspace_id"),
+        parent_id: row.get("parent_id"),
+        estimate_minutes: row.get("estimate_minutes"),
+        series_id: row.get("series_id"),
+        blocked: row.get("blocked"),
+    }).collect();
+
+    Ok(tasks)
+}
+
+
+pub async fn get_task(pool: &SqlitePool, id: &str) -> Result<TaskDetail> {
+    let row = sqlx::query(
+        r#"
+        SELECT t.id, t.name, t.category, t.short_summary, t.description, t.status, t.current_stage,
+               t.start_at, t.end_est_at, t.created_at, t.updated_at, t.project_id, t.workspace_id, t.parent_id,
+               t.estimate_minutes, i.id AS origin_idea_id, i.title AS origin_idea_title
+        FROM tasks t
+        LEFT JOIN idea i ON i.id = t.origin_idea_id
+        WHERE t.id = ?
+        "#
+    )
+    .bind(id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::not_found("task", id))?;
+    let tree = subtasks::subtree(pool, id).await?;
+    let recurrence = recurrence::task_recurrence(pool, id).await?;
+    let blocked_by = dependencies::blocked_by(pool, id).await?;
+    let blocks = dependencies::blocks(pool, id).await?;
+
+    let task = TaskDetail {
+        id: row.get::<String, _>("id"),
+        name: row.get::<String, _>("name"),
+        category: row.get::<String, _>("category"),
+        short_summary: row.get::<Option<String>, _>("short_summary"),
+        description: row.get::<Option<String>, _>("description"),
+        status: row.get::<String, _>("status"),
+        current_stage: row.get::<Option<String>, _>("current_stage"),
+        start_at: row.get::<Option<chrono::DateTime<chrono::Utc>>, _>("start_at"),
+        end_est_at: row.get::<Option<chrono::DateTime<chrono::Utc>>, _>("end_est_at"),
+        created_at: row.get::<chrono::DateTime<chrono::Utc>, _>("created_at"),
+        updated_at: row.get::<chrono::DateTime<chrono::Utc>, _>("updated_at"),
+        project_id: row.get::<Option<String>, _>("project_id"),
+        workspace_id: row.get::<Option<String>, _>("workspace_id"),
+        origin_idea: row.get::<Option<String>, _>("origin_idea_id").map(|id| OriginIdea {
+            id,
+            title: row.get("origin_idea_title"),
+        }),
+        parent_id: row.get::<Option<String>, _>("parent_id"),
+        progress: tree.progress,
+        estimate_minutes: row.get::<Option<i64>, _>("estimate_minutes"),
+        time: TimeSummary {
+            estimate_minutes: tree.estimate_minutes,
+            actual_minutes: tree.actual_minutes,
+            variance_minutes: tree.estimate_minutes.map(|e| tree.actual_minutes - e),
+        },
+        subtasks: tree.subtasks,
+        recurrence,
+        blocked: blocked_by.iter().any(|t| t.status != TaskStatus::Completed.to_string()),
+        blocked_by,
+        blocks,
+    };
+
+    Ok(task)
+}
+
+/// Move a task to `status`, logging the transition (and `note`) in
+/// `status_reasons`. Setting the current status again is a no-op.
+pub async fn set_task_status(
+    pool: &SqlitePool,
+    id: &str,
+    status: TaskStatus,
+    note: Option<String>,
+) -> Result<StatusChange> {
+    let now = Utc::now();
+    let status_str = status.to_string(); // avoid temporary drop issue
+
+    let from = current_status(pool, id).await?;
+    if from == status {
+        return Ok(StatusChange::default());
+    }
+    check_transition(pool, from, status, note.as_deref()).await?;
+    let warning = dependencies::check_start(pool, id, status, note.as_deref()).await?;
+    projects::ensure_writable(pool, "tasks", id).await?;
+
+    let reason_id = Uuid::new_v4().to_string();
+    let mut tx = journal::begin(pool).await?;
+    let mut op = Recorder::new("Change task status");
+    op.track(&mut tx, "tasks", id).await?;
+    op.track(&mut tx, "status_reasons", &reason_id).await?;
+
+    sqlx::query!(
+        r#"UPDATE tasks SET status = ?1, updated_at = ?2 WHERE id = ?3"#,
+        status_str,
+        now,
+        id
+    )
+    .execute(&mut *tx)
+    .await?;
+    log_transition(&mut tx, &reason_id, id, from, status, note.as_deref()).await?;
+    let (mut next, mut parents) = (None, Vec::new());
+    if matches!(status, TaskStatus::Completed | TaskStatus::Dropped) {
+        next = recurrence::schedule_next(&mut tx, &mut op, id).await?;
+        parents = subtasks::complete_parents(&mut tx, &mut op, id).await?;
+    }
+
+    op.commit(&mut tx).await?;
+    tx.commit().await?;
+    events::publish(EntityKind::Task, id, ChangeOp::Updated);
+    if let Some(next) = &next {
+        events::publish(EntityKind::Task, next, ChangeOp::Created);
+    }
+    publish_linked_ideas(pool, id).await?;
+    for parent in &parents {
+        events::publish(EntityKind::Task, parent, ChangeOp::Updated);
+        publish_linked_ideas(pool, parent).await?;
+    }
+
+    Ok(StatusChange { warning })
+}
+
+pub async fn add_reason(
+    pool: &SqlitePool,
+    input: ReasonInput
+) -> Result<()> {
+    let id = Uuid::new_v4().to_string();
+    let now = Utc::now();
+    let status_str =  input.status.to_string();
+
+    if !db::row_exists(pool, "tasks", &input.task_id).await? {
+        return Err(AppError::foreign_key("task", "task_id", input.task_id));
+    }
+
+    projects::ensure_writable(pool, "tasks", &input.task_id).await?;
+
+    let mut tx = journal::begin(pool).await?;
+    let mut op = Recorder::new("Add status reason");
+    op.track(&mut tx, "status_reasons", &id).await?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO status_reasons (id, task_id, status, note, created_at)
+        VALUES (?1, ?2, ?3, ?4, ?5)
+        "#,
+        id,
+        input.task_id,
+        status_str,
+        input.note,
+        now
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    op.commit(&mut tx).await?;
+    tx.commit().await?;
+    events::publish(EntityKind::Task, &input.task_id, ChangeOp::Updated);
+
+    Ok(())
+}
+
+pub async fn update_task(pool: &SqlitePool, input: UpdateTaskInput) -> Result<StatusChange> {
+    let edit = TaskEdit::prepare(pool, input).await?;
+
+    let mut tx = journal::begin(pool).await?;
+    let mut op = Recorder::new("Edit task");
+    edit.write(&mut tx, &mut op).await?;
+    let (mut next, mut parents) = (None, Vec::new());
+    if edit.finishes() {
+        next = recurrence::schedule_next(&mut tx, &mut op, &edit.id).await?;
+        parents = subtasks::complete_parents(&mut tx, &mut op, &edit.id).await?;
+    }
+    op.commit(&mut tx).await?;
+    tx.commit().await?;
+    events::publish(EntityKind::Task, &edit.id, ChangeOp::Updated);
+    if let Some(next) = &next {
+        events::publish(EntityKind::Task, next, ChangeOp::Created);
+    }
+    publish_linked_ideas(pool, &edit.id).await?;
+    for parent in &parents {
+        events::publish(EntityKind::Task, parent, ChangeOp::Updated);
+        publish_linked_ideas(pool, parent).await?;
+    }
+
+    Ok(edit.change())
+}
+
+/// An `UpdateTaskInput` merged over the task's current values and checked,
+/// ready to write on the caller's transaction.
+pub(crate) struct TaskEdit {
+    pub(crate) id: String,
+    name: String,
+    category: String,
+    short_summary: Option<String>,
+    description: Option<String>,
+    from: TaskStatus,
+    status: TaskStatus,
+    status_note: Option<String>,
+    current_stage: Option<String>,
+    pub(crate) start_at: Option<DateTime<Utc>>,
+    pub(crate) end_est_at: Option<DateTime<Utc>>,
+    estimate_minutes: Option<i64>,
+    reason_id: String,
+    warning: Option<String>,
+}
+
+impl TaskEdit {
+    pub(crate) async fn prepare(pool: &SqlitePool, input: UpdateTaskInput) -> Result<TaskEdit> {
+        // 1) Fetch current values
+        let cur = sqlx::query(
+            r#"
+            SELECT
+              name, category, short_summary, description, status, current_stage,
+              start_at, end_est_at, estimate_minutes
+            FROM tasks
+            WHERE id = ? AND deleted_at IS NULL
+            "#
+        )
+        .bind(&input.id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::not_found("task", &input.id))?;
+
+        // 2) Merge incoming fields with current ones
+        let from: TaskStatus = cur.get::<String, _>("status").parse()?;
+        let status = input.status.unwrap_or(from);
+        if let Some(stage) = &input.current_stage {
+            stages::check_current(pool, &input.id, stage).await?;
+        }
+        check_estimate(input.estimate_minutes)?;
+        let mut warning = None;
+        if status != from {
+            check_transition(pool, from, status, input.status_note.as_deref()).await?;
+            warning = dependencies::check_start(pool, &input.id, status, input.status_note.as_deref()).await?;
+        }
+        projects::ensure_writable(pool, "tasks", &input.id).await?;
+
+        Ok(TaskEdit {
+            name: input.name.unwrap_or_else(|| cur.get("name")),
+            category: input.category.unwrap_or_else(|| cur.get("category")),
+            short_summary: input.short_summary.or(cur.get("short_summary")),
+            description: input.description.or(cur.get("description")),
+            from,
+            status,
+            status_note: input.status_note,
+            current_stage: input.current_stage.or(cur.get("current_stage")),
+            start_at: input.start_at.or(cur.get("start_at")),
+            end_est_at: input.end_est_at.or(cur.get("end_est_at")),
+            estimate_minutes: input.estimate_minutes.or(cur.get("estimate_minutes")),
+            id: input.id,
+            reason_id: Uuid::new_v4().to_string(),
+            warning,
+        })
+    }
+
+    /// Update the task, logging any status change with it.
+    pub(crate) async fn write(&self, conn: &mut SqliteConnection, op: &mut Recorder) -> Result<()> {
+        let moves = self.status != self.from;
+        op.track(conn, "tasks", &self.id).await?;
+        if moves {
+            op.track(conn, "status_reasons", &self.reason_id).await?;
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE tasks SET
+              name = ?, category = ?, short_summary = ?, description = ?,
+              status = ?, current_stage = ?, start_at = ?, end_est_at = ?,
+              estimate_minutes = ?, updated_at = ?
+            WHERE id = ?
+            "#
+        )
+        .bind(&self.name)
+        .bind(&self.category)
+        .bind(&self.short_summary)
+        .bind(&self.description)
+        .bind(self.status.to_string())
+        .bind(&self.current_stage)
+        .bind(self.start_at)
+        .bind(self.end_est_at)
+        .bind(self.estimate_minutes)
+        .bind(Utc::now())
+        .bind(&self.id)
+        .execute(&mut *conn)
+        .await?;
+        if moves {
+            log_transition(conn, &self.reason_id, &self.id, self.from, self.status, self.status_note.as_deref()).await?;
+        }
+        Ok(())
+    }
+
+    /// What to tell the caller about the status change, if any.
+    pub(crate) fn change(&self) -> StatusChange {
+        StatusChange { warning: self.warning.clone() }
+    }
+
+    /// Whether the edit completes or drops the task.
+    pub(crate) fn finishes(&self) -> bool {
+        self.status != self.from && matches!(self.status, TaskStatus::Completed | TaskStatus::Dropped)
+    }
+}
+
+/// Move a task and its subtasks to another project; their workspace follows.
+pub async fn move_task(pool: &SqlitePool, input: MoveTaskInput) -> Result<()> {
+    let parent: Option<String> = sqlx::query_scalar("SELECT parent_id FROM tasks WHERE id = ?1 AND deleted_at IS NULL")
+        .bind(&input.id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::not_found("task", &input.id))?;
+    if parent.is_some() {
+        return Err(AppError::validation("id", "Subtasks move with their top-level task"));
+    }
+    let workspace_id = project_workspace(pool, &input.project_id).await?;
+
+    projects::ensure_writable(pool, "tasks", &input.id).await?;
+    projects::ensure_project_writable(pool, &input.project_id).await?;
+
+    let mut tx = journal::begin(pool).await?;
+    let tree: Vec<String> = sqlx::query_scalar(subtasks::SUBTREE).bind(&input.id).fetch_all(&mut *tx).await?;
+    let mut op = Recorder::new("Move task");
+    for id in &tree {
+        op.track(&mut tx, "tasks", id).await?;
+    }
+
+    sqlx::query(&format!(
+        "UPDATE tasks SET project_id = ?2, workspace_id = ?3, updated_at = ?4 WHERE id IN ({})",
+        subtasks::SUBTREE
+    ))
+    .bind(&input.id)
+    .bind(&input.project_id)
+    .bind(&workspace_id)
+    .bind(Utc::now())
+    .execute(&mut *tx)
+    .await?;
+
+    op.commit(&mut tx).await?;
+    tx.commit().await?;
+    for id in &tree {
+        events::publish(EntityKind::Task, id, ChangeOp::Updated);
+    }
+
+    Ok(())
+}
+
+// ---------- Status transitions ----------
+
+/// Status log of a task, oldest first: transitions plus notes from `add_reason`.
+pub async fn list_status_reasons(pool: &SqlitePool, task_id: &str) -> Result<Vec<StatusReason>> {
+    if !db::row_exists(pool, "tasks", task_id).await? {
+        return Err(AppError::not_found("task", task_id));
+    }
+    let rows = sqlx::query(
+        r#"
+        SELECT id, task_id, from_status, status, note, created_at
+        FROM status_reasons
+        WHERE task_id = ?1
+        ORDER BY created_at, rowid
+        "#,
+    )
+    .bind(task_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|r| StatusReason {
+            id: r.get("id"),
+            task_id: r.get("task_id"),
+            from_status: r.get("from_status"),
+            status: r.get("status"),
+            note: r.get("note"),
+            created_at: r.get("created_at"),
+        })
+        .collect())
+}
+
+pub async fn get_status_rules<'e>(db: impl Executor<'e, Database = Sqlite>) -> Result<StatusRules> {
+    settings::get_or_default(db, STATUS_RULES_KEY).await
+}
+
+pub async fn set_status_rules(pool: &SqlitePool, rules: &StatusRules) -> Result<()> {
+    settings::put(pool, STATUS_RULES_KEY, rules).await
+}
+
+pub(crate) async fn current_status<'e>(db: impl Executor<'e, Database = Sqlite>, id: &str) -> Result<TaskStatus> {
+    let status: String = sqlx::query_scalar("SELECT status FROM tasks WHERE id = ?1 AND deleted_at IS NULL")
+        .bind(id)
+        .fetch_optional(db)
+        .await?
+        .ok_or_else(|| AppError::not_found("task", id))?;
+    status.parse()
+}
+
+/// Whether `id` is a task that isn't in the trash.
+pub(crate) async fn is_live(pool: &SqlitePool, id: &str) -> Result<bool> {
+    Ok(sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM tasks WHERE id = ?1 AND deleted_at IS NULL)")
+        .bind(id)
+        .fetch_one(pool)
+        .await?)
+}
+
+/// Check `from -> to` against the state machine and the note rules.
+pub(crate) async fn check_transition(pool: &SqlitePool, from: TaskStatus, to: TaskStatus, note: Option<&str>) -> Result<()> {
+    if !from.can_move_to(to) {
+        return Err(AppError::validation("status", format!("A task can't go from {from} to {to}")));
+    }
+    let rules = get_status_rules(pool).await?;
+    let needs_note = rules
+        .require_note
+        .iter()
+        .any(|r| r.from.is_none_or(|f| f == from) && r.to.is_none_or(|t| t == to));
+    if needs_note && note.is_none_or(|n| n.trim().is_empty()) {
+        return Err(AppError::validation("note", format!("Add a note to move from {from} to {to}")));
+    }
+    Ok(())
+}
+
+/// Log a transition in `status_reasons`, inside the caller's transaction.
+pub(crate) async fn log_transition(
+    conn: &mut SqliteConnection,
+    reason_id: &str,
+    task_id: &str,
+    from: TaskStatus,
+    to: TaskStatus,
+    note: Option<&str>,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO status_reasons (id, task_id, from_status, status, note, created_at)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+        "#,
+    )
+    .bind(reason_id)
+    .bind(task_id)
+    .bind(from.to_string())
+    .bind(to.to_string())
+    .bind(note.map(str::trim).unwrap_or_default())
+    .bind(Utc::now())
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+/// An ISO 8601 input field, where a rule needs the actual time.
+fn parse_time(field: &'static str, value: &Option<String>) -> Result<Option<DateTime<Utc>>> {
+    value
+        .as_deref()
+        .map(|v| v.parse().map_err(|_| AppError::validation(field, format!("'{v}' is not an ISO 8601 date-time"))))
+        .transpose()
+}
+
+fn check_estimate(minutes: Option<i64>) -> Result<()> {
+    if minutes.is_some_and(|m| m < 0) {
+        return Err(AppError::validation("estimate_minutes", "Estimate can't be negative"));
+    }
+    Ok(())
+}
+
+/// Workspace of a live project; the project must exist and not be trashed.
+async fn project_workspace(pool: &SqlitePool, project_id: &str) -> Result<String> {
+    sqlx::query_scalar("SELECT workspace_id FROM project WHERE id = ?1 AND deleted_at IS NULL")
+        .bind(project_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::foreign_key("project", "project_id", project_id))
+}
+
+/// A task's status feeds its ideas' `ready_to_ship`, so tell their views too.
+pub(crate) async fn publish_linked_ideas(pool: &SqlitePool, task_id: &str) -> Result<()> {
+    let ideas: Vec<String> = sqlx::query_scalar("SELECT idea_id FROM idea_task_map WHERE task_id = ?1")
+        .bind(task_id)
+        .fetch_all(pool)
+        .await?;
+    for idea in ideas {
+        events::publish(EntityKind::Idea, idea, ChangeOp::Updated);
+    }
+    Ok(())
+}
+
+/// Move a task to the Trash; see `services::trash` for restore and purge.
+pub async fn delete_task(pool: &SqlitePool, id: &str) -> Result<()> {
+    trash::delete(pool, EntityKind::Task, id).await
+}
@@ -0,0 +1,473 @@
+// src/services/time.rs
+//! Work sessions logged against tasks (`time_entry`), the "actual" side of
+//! estimates and the input of reports.
+//!
+//! An entry belongs to a task and may also point at the idea or doc the work
+//! was for. It is either finished or running (`ended_at` NULL); at most one
+//! runs at a time, whether started here or by the focus timer; only the
+//! timer's have source `timer`, which reports count as focus time. Entries
+//! never overlap: a write that would is rejected, or with [`Overlap::Trim`]
+//! cut back to the free time around its neighbours.
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Row, SqliteConnection, SqlitePool};
+use uuid::Uuid;
+
+use crate::events::{self, ChangeOp, EntityKind};
+use crate::services::journal::{self, Recorder};
+use crate::services::projects;
+use crate::{db, AppError, Result};
+
+/// Minutes of a finished entry `e`, as SQL.
+pub(crate) const ENTRY_MINUTES: &str = "(julianday(e.ended_at) - julianday(e.started_at)) * 1440";
+
+const COLUMNS: &str = r#"
+    e.id, e.task_id, e.idea_id, e.doc_id, e.started_at, e.ended_at, e.source, e.note, e.created_at
+"#;
+
+// ---------- Types ----------
+
+#[derive(Debug, Serialize)]
+pub struct TimeEntry {
+    pub id: String,
+    pub task_id: String,
+    pub idea_id: Option<String>,
+    pub doc_id: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: Option<DateTime<Utc>>,
+    pub minutes: i64, // 0 while running
+    pub source: String,
+    pub note: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// What to do when a write would overlap other entries.
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Overlap {
+    #[default]
+    Reject,
+    Trim, // shrink the written entry to the free time; still rejected if none is left
+}
+
+#[derive(Deserialize)]
+pub struct LogTimeInput {
+    pub task_id: String,
+    pub idea_id: Option<String>,
+    pub doc_id: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: DateTime<Utc>,
+    pub note: Option<String>,
+    #[serde(default)]
+    pub overlap: Overlap,
+}
+
+#[derive(Deserialize)]
+pub struct StartSessionInput {
+    pub task_id: String,
+    pub idea_id: Option<String>,
+    pub doc_id: Option<String>,
+    pub note: Option<String>,
+}
+
+/// `None` leaves a field as is; an empty `idea_id`/`doc_id` unlinks it.
+#[derive(Deserialize)]
+pub struct UpdateTimeEntryInput {
+    pub id: String,
+    pub task_id: Option<String>,
+    pub idea_id: Option<String>,
+    pub doc_id: Option<String>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub ended_at: Option<DateTime<Utc>>,
+    pub note: Option<String>,
+    #[serde(default)]
+    pub overlap: Overlap,
+}
+
+fn entry(r: &SqliteRow) -> TimeEntry {
+    TimeEntry {
+        id: r.get("id"),
+        task_id: r.get("task_id"),
+        idea_id: r.get("idea_id"),
+        doc_id: r.get("doc_id"),
+        started_at: r.get("started_at"),
+        ended_at: r.get("ended_at"),
+        minutes: r.get("minutes"),
+        source: r.get("source"),
+        note: r.get("note"),
+        created_at: r.get("created_at"),
+    }
+}
+
+// ---------- Queries ----------
+
+/// A task's own entries, oldest first.
+pub async fn list_time_entries(pool: &SqlitePool, task_id: &str) -> Result<Vec<TimeEntry>> {
+    if !db::row_exists(pool, "tasks", task_id).await? {
+        return Err(AppError::not_found("task", task_id));
+    }
+    let rows = sqlx::query(&format!(
+        r#"
+        SELECT {COLUMNS}, CAST(ROUND(COALESCE({ENTRY_MINUTES}, 0)) AS INTEGER) AS minutes
+        FROM time_entry e
+        WHERE e.task_id = ?1
+        ORDER BY e.started_at
+        "#
+    ))
+    .bind(task_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(entry).collect())
+}
+
+pub async fn get_time_entry(pool: &SqlitePool, id: &str) -> Result<TimeEntry> {
+    let row = sqlx::query(&format!(
+        r#"
+        SELECT {COLUMNS}, CAST(ROUND(COALESCE({ENTRY_MINUTES}, 0)) AS INTEGER) AS minutes
+        FROM time_entry e WHERE e.id = ?1
+        "#
+    ))
+    .bind(id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::not_found("time_entry", id))?;
+    Ok(entry(&row))
+}
+
+/// The session running right now, if any.
+pub async fn running_session(pool: &SqlitePool) -> Result<Option<TimeEntry>> {
+    let row = sqlx::query(&format!(
+        "SELECT {COLUMNS}, 0 AS minutes FROM time_entry e WHERE e.ended_at IS NULL LIMIT 1"
+    ))
+    .fetch_optional(pool)
+    .await?;
+    Ok(row.as_ref().map(entry))
+}
+
+// ---------- Checks ----------
+
+async fn check_links(pool: &SqlitePool, task_id: &str, idea_id: Option<&str>, doc_id: Option<&str>) -> Result<()> {
+    if !db::row_exists(pool, "tasks", task_id).await? {
+        return Err(AppError::foreign_key("time_entry", "task_id", task_id));
+    }
+    for (table, field, id) in [("idea", "idea_id", idea_id), ("doc", "doc_id", doc_id)] {
+        if let Some(id) = id.filter(|id| !id.is_empty()) {
+            if !db::row_exists(pool, table, id).await? {
+                return Err(AppError::foreign_key("time_entry", field, id));
+            }
+        }
+    }
+    projects::ensure_writable(pool, "tasks", task_id).await
+}
+
+/// Fit `start..end` between the entries other than `skip`, per `mode`. A
+/// running entry counts as going on until now. Run it on the write's
+/// transaction so no entry can land in the gap it found.
+async fn fit(
+    conn: &mut SqliteConnection,
+    mut start: DateTime<Utc>,
+    mut end: DateTime<Utc>,
+    skip: &[String],
+    mode: Overlap,
+) -> Result<(DateTime<Utc>, DateTime<Utc>)> {
+    if end <= start {
+        return Err(AppError::validation("ended_at", "End must be after the start"));
+    }
+    let rows = sqlx::query(
+        r#"
+        SELECT id, started_at, ended_at FROM time_entry
+        WHERE julianday(started_at) < julianday(?2)
+          AND julianday(COALESCE(ended_at, ?3)) > julianday(?1)
+        ORDER BY started_at
+        "#,
+    )
+    .bind(start)
+    .bind(end)
+    .bind(Utc::now())
+    .fetch_all(conn)
+    .await?;
+
+    let now = Utc::now();
+    for r in rows.iter().filter(|r| !skip.contains(&r.get::<String, _>("id"))) {
+        let (from, to): (DateTime<Utc>, Option<DateTime<Utc>>) = (r.get("started_at"), r.get("ended_at"));
+        let to = to.unwrap_or(now);
+        let clash = || AppError::conflict("time_entry", "started_at", format!("Overlaps the entry from {from} to {to}"));
+        if mode == Overlap::Reject {
+            return Err(clash());
+        }
+        if from <= start {
+            start = start.max(to);
+        } else if from < end {
+            if to < end {
+                return Err(clash()); // sits inside: trimming would drop the time after it
+            }
+            end = from;
+        }
+        if end <= start {
+            return Err(clash());
+        }
+    }
+    Ok((start, end))
+}
+
+fn publish(task_ids: &[&str]) {
+    for id in task_ids {
+        events::publish(EntityKind::Task, *id, ChangeOp::Updated);
+    }
+}
+
+// ---------- Commands ----------
+
+/// Log a finished stretch of work by hand.
+pub async fn log_time(pool: &SqlitePool, input: LogTimeInput) -> Result<String> {
+    check_links(pool, &input.task_id, input.idea_id.as_deref(), input.doc_id.as_deref()).await?;
+
+    let id = Uuid::new_v4().to_string();
+    let mut tx = journal::begin(pool).await?;
+    let (start, end) = fit(&mut tx, input.started_at, input.ended_at, &[], input.overlap).await?;
+    let mut op = Recorder::new("Log time");
+    op.track(&mut tx, "time_entry", &id).await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO time_entry (id, task_id, idea_id, doc_id, started_at, ended_at, source, note, created_at)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, 'manual', ?7, ?8)
+        "#,
+    )
+    .bind(&id)
+    .bind(&input.task_id)
+    .bind(input.idea_id.filter(|s| !s.is_empty()))
+    .bind(input.doc_id.filter(|s| !s.is_empty()))
+    .bind(start)
+    .bind(end)
+    .bind(&input.note)
+    .bind(Utc::now())
+    .execute(&mut *tx)
+    .await?;
+
+    op.commit(&mut tx).await?;
+    tx.commit().await?;
+    publish(&[&input.task_id]);
+
+    Ok(id)
+}
+
+/// Start a stopwatch session now.
+pub async fn start_session(pool: &SqlitePool, input: StartSessionInput) -> Result<TimeEntry> {
+    if let Some(running) = running_session(pool).await? {
+        return Err(AppError::conflict("time_entry", "ended_at", format!("Session {} is still running", running.id)));
+    }
+    check_links(pool, &input.task_id, input.idea_id.as_deref(), input.doc_id.as_deref()).await?;
+
+    let id = Uuid::new_v4().to_string();
+    let now = Utc::now();
+    let mut tx = journal::begin(pool).await?;
+    let mut op = Recorder::new("Start session");
+    op.track(&mut tx, "time_entry", &id).await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO time_entry (id, task_id, idea_id, doc_id, started_at, ended_at, source, note, created_at)
+        VALUES (?1, ?2, ?3, ?4, ?5, NULL, 'manual', ?6, ?5)
+        "#,
+    )
+    .bind(&id)
+    .bind(&input.task_id)
+    .bind(input.idea_id.filter(|s| !s.is_empty()))
+    .bind(input.doc_id.filter(|s| !s.is_empty()))
+    .bind(now)
+    .bind(&input.note)
+    .execute(&mut *tx)
+    .await?;
+
+    op.commit(&mut tx).await?;
+    tx.commit().await?;
+    publish(&[&input.task_id]);
+
+    get_time_entry(pool, &id).await
+}
+
+/// Stop a running session now.
+pub async fn stop_session(pool: &SqlitePool, id: &str) -> Result<TimeEntry> {
+    let current = get_time_entry(pool, id).await?;
+    if current.ended_at.is_some() {
+        return Err(AppError::validation("id", "This session isn't running"));
+    }
+    projects::ensure_writable(pool, "tasks", &current.task_id).await?;
+
+    let mut tx = journal::begin(pool).await?;
+    let mut op = Recorder::new("Stop session");
+    op.track(&mut tx, "time_entry", id).await?;
+
+    sqlx::query("UPDATE time_entry SET ended_at = ?1 WHERE id = ?2")
+        .bind(Utc::now())
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+    op.commit(&mut tx).await?;
+    tx.commit().await?;
+    publish(&[&current.task_id]);
+
+    get_time_entry(pool, id).await
+}
+
+/// Edit an entry. Its times are checked for overlaps again; a running entry
+/// keeps running unless `ended_at` is given.
+pub async fn update_time_entry(pool: &SqlitePool, input: UpdateTimeEntryInput) -> Result<TimeEntry> {
+    let current = get_time_entry(pool, &input.id).await?;
+    let task_id = input.task_id.clone().unwrap_or_else(|| current.task_id.clone());
+    let mut started_at = input.started_at.unwrap_or(current.started_at);
+    let mut ended_at = input.ended_at.or(current.ended_at);
+    check_links(pool, &task_id, input.idea_id.as_deref(), input.doc_id.as_deref()).await?;
+    projects::ensure_writable(pool, "tasks", &current.task_id).await?;
+
+    let mut tx = journal::begin(pool).await?;
+    if input.started_at.is_some() || input.ended_at.is_some() {
+        let end = ended_at.unwrap_or_else(Utc::now);
+        let (start, fitted) = fit(&mut tx, started_at, end, std::slice::from_ref(&input.id), input.overlap).await?;
+        started_at = start;
+        ended_at = ended_at.map(|_| fitted);
+    }
+    let mut op = Recorder::new("Edit time entry");
+    op.track(&mut tx, "time_entry", &input.id).await?;
+
+    sqlx::query(
+        r#"
+        UPDATE time_entry SET
+          task_id = ?1,
+          idea_id = CASE WHEN ?2 IS NULL THEN idea_id ELSE NULLIF(?2, '') END,
+          doc_id = CASE WHEN ?3 IS NULL THEN doc_id ELSE NULLIF(?3, '') END,
+          started_at = ?4,
+          ended_at = ?5,
+          note = COALESCE(?6, note)
+        WHERE id = ?7
+        "#,
+    )
+    .bind(&task_id)
+    .bind(&input.idea_id)
+    .bind(&input.doc_id)
+    .bind(started_at)
+    .bind(ended_at)
+    .bind(&input.note)
+    .bind(&input.id)
+    .execute(&mut *tx)
+    .await?;
+
+    op.commit(&mut tx).await?;
+    tx.commit().await?;
+    publish(&[&current.task_id, &task_id]);
+
+    get_time_entry(pool, &input.id).await
+}
+
+/// Cut a finished entry in two at `at`; returns the id of the second part.
+pub async fn split_time_entry(pool: &SqlitePool, id: &str, at: DateTime<Utc>) -> Result<String> {
+    let current = get_time_entry(pool, id).await?;
+    let Some(ended_at) = current.ended_at else {
+        return Err(AppError::validation("id", "Stop the session before splitting it"));
+    };
+    if at <= current.started_at || at >= ended_at {
+        return Err(AppError::validation("at", "Split point must fall inside the entry"));
+    }
+    projects::ensure_writable(pool, "tasks", &current.task_id).await?;
+
+    let second = Uuid::new_v4().to_string();
+    let mut tx = journal::begin(pool).await?;
+    let mut op = Recorder::new("Split time entry");
+    op.track(&mut tx, "time_entry", id).await?;
+    op.track(&mut tx, "time_entry", &second).await?;
+
+    sqlx::query("UPDATE time_entry SET ended_at = ?1 WHERE id = ?2")
+        .bind(at)
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query(
+        r#"
+        INSERT INTO time_entry (id, task_id, idea_id, doc_id, started_at, ended_at, source, note, created_at)
+        SELECT ?1, task_id, idea_id, doc_id, ?2, ?3, source, note, ?4 FROM time_entry WHERE id = ?5
+        "#,
+    )
+    .bind(&second)
+    .bind(at)
+    .bind(ended_at)
+    .bind(Utc::now())
+    .bind(id)
+    .execute(&mut *tx)
+    .await?;
+
+    op.commit(&mut tx).await?;
+    tx.commit().await?;
+    publish(&[&current.task_id]);
+
+    Ok(second)
+}
+
+/// Join finished entries of one task into the earliest of them, spanning
+/// first start to last end. Gaps between them count as worked, so no other
+/// entry may sit in one. Returns the kept id.
+pub async fn merge_time_entries(pool: &SqlitePool, ids: &[String]) -> Result<String> {
+    if ids.len() < 2 {
+        return Err(AppError::validation("ids", "Pick at least two entries to merge"));
+    }
+    let mut entries = Vec::with_capacity(ids.len());
+    for id in ids {
+        entries.push(get_time_entry(pool, id).await?);
+    }
+    entries.sort_by_key(|e| e.started_at);
+    let first = &entries[0];
+    if entries.iter().any(|e| e.task_id != first.task_id) {
+        return Err(AppError::validation("ids", "Only entries of the same task can be merged"));
+    }
+    let Some(end) = entries.iter().map(|e| e.ended_at).collect::<Option<Vec<_>>>().and_then(|e| e.into_iter().max())
+    else {
+        return Err(AppError::validation("ids", "Stop the running session before merging it"));
+    };
+    projects::ensure_writable(pool, "tasks", &first.task_id).await?;
+
+    let notes: Vec<&str> = entries.iter().filter_map(|e| e.note.as_deref()).filter(|n| !n.trim().is_empty()).collect();
+    let note = (!notes.is_empty()).then(|| notes.join("\n"));
+
+    let mut tx = journal::begin(pool).await?;
+    fit(&mut tx, first.started_at, end, ids, Overlap::Reject).await?;
+    let mut op = Recorder::new("Merge time entries");
+    for e in &entries {
+        op.track(&mut tx, "time_entry", &e.id).await?;
+    }
+
+    sqlx::query("UPDATE time_entry SET ended_at = ?1, note = ?2 WHERE id = ?3")
+        .bind(end)
+        .bind(&note)
+        .bind(&first.id)
+        .execute(&mut *tx)
+        .await?;
+    for e in &entries[1..] {
+        sqlx::query("DELETE FROM time_entry WHERE id = ?1").bind(&e.id).execute(&mut *tx).await?;
+    }
+
+    op.commit(&mut tx).await?;
+    tx.commit().await?;
+    publish(&[&first.task_id]);
+
+    Ok(first.id.clone())
+}
+
+pub async fn delete_time_entry(pool: &SqlitePool, id: &str) -> Result<()> {
+    let current = get_time_entry(pool, id).await?;
+    projects::ensure_writable(pool, "tasks", &current.task_id).await?;
+
+    let mut tx = journal::begin(pool).await?;
+    let mut op = Recorder::new("Delete time entry");
+    op.track(&mut tx, "time_entry", id).await?;
+
+    sqlx::query("DELETE FROM time_entry WHERE id = ?1").bind(id).execute(&mut *tx).await?;
+
+    op.commit(&mut tx).await?;
+    tx.commit().await?;
+    publish(&[&current.task_id]);
+
+    Ok(())
+}
@@ -0,0 +1,402 @@
+// src/services/timer.rs
+//! Pomodoro timer: focus, short-break and long-break phases.
+//!
+//! The [`Timer`] lives in the app process, not the webview, so it keeps
+//! running across reloads and with the window closed; views read it with
+//! `get_timer` and follow the `timer` event. `run()` calls [`Timer::tick`]
+//! every second. Every change is saved under [`STATE_KEY`] together with the
+//! `time_entry` writes it implies, and [`Timer::load`] picks it up after a
+//! restart.
+//!
+//! Focus time is logged as `time_entry` rows with source `timer`: one opens
+//! when focus (re)starts and closes on pause, skip, stop or when the phase
+//! ends. Those rows aren't recorded for undo.
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{SqliteConnection, SqlitePool};
+use tokio::sync::{broadcast, Mutex};
+use uuid::Uuid;
+
+use crate::events::{self, ChangeOp, EntityKind};
+use crate::services::{projects, settings};
+use crate::{AppError, Result};
+
+pub const SETTINGS_KEY: &str = "timer";
+pub const STATE_KEY: &str = "timer_state";
+
+/// Tauri event carrying the [`TimerState`], every second while running and
+/// after every change.
+pub const TIMER_EVENT: &str = "timer";
+/// Tauri event carrying a [`PhaseEnded`].
+pub const PHASE_EVENT: &str = "timer-phase";
+
+/// A phase that ended longer ago than this (app closed, machine asleep)
+/// doesn't auto-start the next one.
+const LATE: Duration = Duration::seconds(5);
+
+// ---------- Types ----------
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct TimerSettings {
+    pub focus_minutes: u32,
+    pub short_break_minutes: u32,
+    pub long_break_minutes: u32,
+    pub long_break_every: u32, // focus intervals per long break
+    pub auto_start_breaks: bool,
+    pub auto_start_focus: bool,
+}
+impl Default for TimerSettings {
+    fn default() -> Self {
+        TimerSettings {
+            focus_minutes: 25,
+            short_break_minutes: 5,
+            long_break_minutes: 15,
+            long_break_every: 4,
+            auto_start_breaks: true,
+            auto_start_focus: false,
+        }
+    }
+}
+
+impl TimerSettings {
+    fn length(&self, phase: Phase) -> Duration {
+        let minutes = match phase {
+            Phase::Idle => 0,
+            Phase::Focus => self.focus_minutes,
+            Phase::ShortBreak => self.short_break_minutes,
+            Phase::LongBreak => self.long_break_minutes,
+        };
+        Duration::minutes(minutes.into())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Phase {
+    #[default]
+    Idle,
+    Focus,
+    ShortBreak,
+    LongBreak,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+pub struct TimerState {
+    pub phase: Phase,
+    pub task_id: Option<String>, // kept through breaks for the next focus
+    pub running: bool,
+    pub ends_at: Option<DateTime<Utc>>, // set while running
+    pub remaining_secs: i64,
+    pub focus_done: u32, // completed focus intervals since the timer started
+    pub entry_id: Option<String>, // open time entry of a running focus
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct PhaseEnded {
+    pub phase: Phase,
+    pub completed: bool, // false when skipped
+    pub next: Phase,
+    pub task_id: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub enum TimerEvent {
+    State(TimerState),
+    PhaseEnded(PhaseEnded),
+}
+
+// ---------- Settings ----------
+
+pub async fn get_settings(pool: &SqlitePool) -> Result<TimerSettings> {
+    settings::get_or_default(pool, SETTINGS_KEY).await
+}
+
+/// New lengths apply from the next phase on.
+pub async fn set_settings(pool: &SqlitePool, input: TimerSettings) -> Result<()> {
+    for (field, minutes) in [
+        ("focus_minutes", input.focus_minutes),
+        ("short_break_minutes", input.short_break_minutes),
+        ("long_break_minutes", input.long_break_minutes),
+    ] {
+        if minutes == 0 {
+            return Err(AppError::validation(field, "Must be at least one minute"));
+        }
+    }
+    if input.long_break_every == 0 {
+        return Err(AppError::validation("long_break_every", "Must be at least 1"));
+    }
+    settings::put(pool, SETTINGS_KEY, &input).await
+}
+
+// ---------- Entries ----------
+
+async fn open_entry(conn: &mut SqliteConnection, task_id: &str, at: DateTime<Utc>) -> Result<String> {
+    let id = Uuid::new_v4().to_string();
+    sqlx::query(
+        r#"
+        INSERT INTO time_entry (id, task_id, started_at, ended_at, source, note, created_at)
+        VALUES (?1, ?2, ?3, NULL, 'timer', NULL, ?4)
+        "#,
+    )
+    .bind(&id)
+    .bind(task_id)
+    .bind(at)
+    .bind(Utc::now())
+    .execute(conn)
+    .await?;
+    Ok(id)
+}
+
+async fn close_entry(conn: &mut SqliteConnection, id: &str, at: DateTime<Utc>) -> Result<()> {
+    // gone if the task was deleted for good meanwhile
+    sqlx::query("UPDATE time_entry SET ended_at = ?1 WHERE id = ?2 AND ended_at IS NULL")
+        .bind(at)
+        .bind(id)
+        .execute(conn)
+        .await?;
+    Ok(())
+}
+
+/// Focus can't start while a stopwatch session (`services::time`) runs.
+async fn ensure_no_session(conn: &mut SqliteConnection) -> Result<()> {
+    let running: Option<String> = sqlx::query_scalar("SELECT id FROM time_entry WHERE ended_at IS NULL LIMIT 1")
+        .fetch_optional(conn)
+        .await?;
+    match running {
+        Some(id) => Err(AppError::conflict("time_entry", "ended_at", format!("Session {id} is still running"))),
+        None => Ok(()),
+    }
+}
+
+// ---------- Transitions ----------
+
+/// Start the clock on `s` at `at` for its remaining time.
+async fn run(conn: &mut SqliteConnection, s: &mut TimerState, at: DateTime<Utc>) -> Result<()> {
+    s.running = true;
+    s.ends_at = Some(at + Duration::seconds(s.remaining_secs));
+    if let (Phase::Focus, Some(task_id)) = (s.phase, &s.task_id) {
+        s.entry_id = Some(open_entry(conn, task_id, at).await?);
+    }
+    Ok(())
+}
+
+/// Stop the clock on `s` at `at`, keeping what is left.
+async fn halt(conn: &mut SqliteConnection, s: &mut TimerState, at: DateTime<Utc>) -> Result<()> {
+    if let Some(ends_at) = s.ends_at.take() {
+        s.remaining_secs = (ends_at - at).num_seconds().max(0);
+    }
+    s.running = false;
+    if let Some(id) = s.entry_id.take() {
+        close_entry(conn, &id, at).await?;
+    }
+    Ok(())
+}
+
+/// End the current phase at `at` and set up the next one, started when `auto`
+/// and the settings allow it, paused at full length otherwise.
+async fn advance(
+    conn: &mut SqliteConnection,
+    s: &mut TimerState,
+    cfg: &TimerSettings,
+    at: DateTime<Utc>,
+    completed: bool,
+    auto: bool,
+) -> Result<PhaseEnded> {
+    let phase = s.phase;
+    halt(conn, s, at).await?;
+    let next = match phase {
+        Phase::Focus if completed => {
+            s.focus_done += 1;
+            if s.focus_done.is_multiple_of(cfg.long_break_every) {
+                Phase::LongBreak
+            } else {
+                Phase::ShortBreak
+            }
+        }
+        Phase::Focus => Phase::ShortBreak,
+        Phase::ShortBreak | Phase::LongBreak | Phase::Idle => Phase::Focus,
+    };
+
+    s.phase = next;
+    s.remaining_secs = cfg.length(next).num_seconds();
+    let start = match next {
+        Phase::Focus => cfg.auto_start_focus,
+        _ => cfg.auto_start_breaks,
+    };
+    if auto && start {
+        run(conn, s, at).await?;
+    }
+    Ok(PhaseEnded { phase, completed, next, task_id: s.task_id.clone() })
+}
+
+enum Action<'a> {
+    Start(&'a str),
+    Pause,
+    Resume,
+    Skip,
+    Stop,
+}
+
+async fn apply(
+    conn: &mut SqliteConnection,
+    s: &mut TimerState,
+    cfg: &TimerSettings,
+    action: Action<'_>,
+    now: DateTime<Utc>,
+) -> Result<Option<PhaseEnded>> {
+    match action {
+        Action::Start(task_id) => {
+            if s.phase == Phase::Focus {
+                halt(conn, s, now).await?;
+            } else {
+                s.phase = Phase::Focus;
+                s.remaining_secs = cfg.length(Phase::Focus).num_seconds();
+            }
+            s.task_id = Some(task_id.to_string());
+            ensure_no_session(conn).await?;
+            run(conn, s, now).await?;
+        }
+        Action::Pause => halt(conn, s, now).await?,
+        Action::Resume => match s.phase {
+            Phase::Idle => return Err(AppError::validation("timer", "Start a focus interval first")),
+            _ if s.running => {}
+            Phase::Focus => {
+                ensure_no_session(conn).await?;
+                run(conn, s, now).await?;
+            }
+            _ => run(conn, s, now).await?,
+        },
+        Action::Skip if s.phase == Phase::Idle => {}
+        Action::Skip => return Ok(Some(advance(conn, s, cfg, now, false, true).await?)),
+        Action::Stop => {
+            halt(conn, s, now).await?;
+            *s = TimerState::default();
+        }
+    }
+    Ok(None)
+}
+
+// ---------- Engine ----------
+
+pub struct Timer {
+    state: Mutex<TimerState>,
+    tx: broadcast::Sender<TimerEvent>,
+}
+
+impl Default for Timer {
+    fn default() -> Self {
+        Timer { state: Mutex::new(TimerState::default()), tx: broadcast::channel(64).0 }
+    }
+}
+
+impl Timer {
+    pub fn subscribe(&self) -> broadcast::Receiver<TimerEvent> {
+        self.tx.subscribe()
+    }
+
+    /// Current state, `remaining_secs` counted down to now.
+    pub async fn state(&self) -> TimerState {
+        snapshot(&*self.state.lock().await, Utc::now())
+    }
+
+    /// Take over the saved state, e.g. after a restart or restore, finishing a
+    /// phase that ran out meanwhile.
+    pub async fn load(&self, pool: &SqlitePool) -> Result<TimerState> {
+        *self.state.lock().await = settings::get_or_default(pool, STATE_KEY).await?;
+        self.tick(pool, Utc::now()).await?;
+        Ok(self.state().await)
+    }
+
+    /// Focus on `task_id`: a fresh interval, or the current one switched over
+    /// to this task.
+    pub async fn start(&self, pool: &SqlitePool, task_id: &str) -> Result<TimerState> {
+        let task: Option<Option<String>> =
+            sqlx::query_scalar("SELECT deleted_at FROM tasks WHERE id = ?1").bind(task_id).fetch_optional(pool).await?;
+        if !matches!(task, Some(None)) {
+            return Err(AppError::foreign_key("task", "task_id", task_id));
+        }
+        projects::ensure_writable(pool, "tasks", task_id).await?;
+        self.update(pool, Action::Start(task_id)).await
+    }
+
+    pub async fn pause(&self, pool: &SqlitePool) -> Result<TimerState> {
+        self.update(pool, Action::Pause).await
+    }
+
+    pub async fn resume(&self, pool: &SqlitePool) -> Result<TimerState> {
+        self.update(pool, Action::Resume).await
+    }
+
+    /// End the current phase now without counting it.
+    pub async fn skip(&self, pool: &SqlitePool) -> Result<TimerState> {
+        self.update(pool, Action::Skip).await
+    }
+
+    /// Back to idle; the focus count starts over.
+    pub async fn stop(&self, pool: &SqlitePool) -> Result<TimerState> {
+        self.update(pool, Action::Stop).await
+    }
+
+    /// Scheduler step: end phases that ran out by `now` and announce the state.
+    pub async fn tick(&self, pool: &SqlitePool, now: DateTime<Utc>) -> Result<()> {
+        let mut s = self.state.lock().await;
+        let Some(ends_at) = s.ends_at.filter(|at| s.running && *at <= now) else {
+            if s.running {
+                self.tx.send(TimerEvent::State(snapshot(&s, now))).ok();
+            }
+            return Ok(());
+        };
+
+        let cfg = get_settings(pool).await?;
+        let mut next = s.clone();
+        let mut tx = pool.begin().await?;
+        let ended = advance(&mut tx, &mut next, &cfg, ends_at, true, now - ends_at <= LATE).await?;
+        settings::put(&mut *tx, STATE_KEY, &next).await?;
+        tx.commit().await?;
+
+        *s = next;
+        self.announce(&s, Some(ended), now);
+        Ok(())
+    }
+
+    /// Apply `action` to a copy of the state and save it with its entry
+    /// writes in one transaction; memory only changes once that commits.
+    async fn update(&self, pool: &SqlitePool, action: Action<'_>) -> Result<TimerState> {
+        let cfg = get_settings(pool).await?;
+        let now = Utc::now();
+        let mut s = self.state.lock().await;
+        let mut next = s.clone();
+
+        let mut tx = pool.begin().await?;
+        let ended = apply(&mut tx, &mut next, &cfg, action, now).await?;
+        settings::put(&mut *tx, STATE_KEY, &next).await?;
+        tx.commit().await?;
+
+        let before = s.task_id.clone();
+        *s = next;
+        if let Some(id) = before.filter(|id| s.task_id.as_ref() != Some(id)) {
+            events::publish(EntityKind::Task, id, ChangeOp::Updated);
+        }
+        self.announce(&s, ended, now);
+        Ok(snapshot(&s, now))
+    }
+
+    fn announce(&self, s: &TimerState, ended: Option<PhaseEnded>, now: DateTime<Utc>) {
+        if let Some(ended) = ended {
+            self.tx.send(TimerEvent::PhaseEnded(ended)).ok();
+        }
+        if let Some(id) = &s.task_id {
+            // an entry opened or closed: the task's logged time moved
+            events::publish(EntityKind::Task, id, ChangeOp::Updated);
+        }
+        self.tx.send(TimerEvent::State(snapshot(s, now))).ok();
+    }
+}
+
+fn snapshot(s: &TimerState, now: DateTime<Utc>) -> TimerState {
+    let mut out = s.clone();
+    if let Some(ends_at) = s.ends_at.filter(|_| s.running) {
+        out.remaining_secs = (ends_at - now).num_seconds().max(0);
+    }
+    out
+}
@@ -0,0 +1,534 @@
+// src/services/trash.rs
+//! Soft delete, the Trash, and permanent purge.
+//!
+//! Deleting sets `deleted_at`; the row stays put (and undoable) until it is
+//! purged. Trashing a project stamps its live ideas, docs and tasks with the
+//! same time (a task does the same for its subtasks), so the Trash lists just
+//! the project and restore brings the whole set back. Purge is a real
+//! `DELETE`, letting the schema's cascades remove child rows, and then
+//! removes the attachment files we stored on disk.
+//!
+//! Workspaces skip the Trash: [`preview_delete`] counts what would go and
+//! [`delete_permanently`] only proceeds with the token from that preview.
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeMap;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{Row, SqlitePool};
+
+use crate::events::{self, ChangeOp, EntityKind};
+use crate::services::journal::{self, Recorder};
+use crate::services::subtasks::SUBTREE;
+use crate::services::{projects, tasks};
+use crate::{db, AppError, ErrorCode, Result};
+
+// ---------- Types ----------
+
+#[derive(Debug, Serialize)]
+pub struct TrashItem {
+    pub kind: EntityKind,
+    pub id: String,
+    pub title: String,
+    pub project_id: Option<String>, // None for projects
+    pub deleted_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct PurgeReport {
+    pub purged: usize,        // trash items removed
+    pub files_removed: usize, // attachment files deleted from disk
+}
+
+/// Where each trashable kind lives.
+struct Spec {
+    table: &'static str,
+    entity: &'static str,
+    delete_label: &'static str,
+    restore_label: &'static str,
+}
+
+fn spec(kind: EntityKind) -> Result<Spec> {
+    let (table, entity, delete_label, restore_label) = match kind {
+        EntityKind::Task => ("tasks", "task", "Delete task", "Restore task"),
+        EntityKind::Idea => ("idea", "idea", "Delete idea", "Restore idea"),
+        EntityKind::Doc => ("doc", "doc", "Delete doc", "Restore doc"),
+        EntityKind::Project => ("project", "project", "Delete project", "Restore project"),
+        _ => return Err(AppError::validation("kind", "Only tasks, ideas, docs and projects can be deleted")),
+    };
+    Ok(Spec { table, entity, delete_label, restore_label })
+}
+
+/// Tables whose rows belong to a project and follow it into the Trash.
+const PROJECT_CHILDREN: [(&str, EntityKind); 3] =
+    [("tasks", EntityKind::Task), ("idea", EntityKind::Idea), ("doc", EntityKind::Doc)];
+
+/// Rows that go into the Trash with `kind` `?1`, as (table, kind, condition).
+/// The condition is on the child rows and may reference `?1`.
+fn children_of(kind: EntityKind) -> Vec<(&'static str, EntityKind, String)> {
+    match kind {
+        EntityKind::Project => PROJECT_CHILDREN
+            .iter()
+            .map(|(table, k)| (*table, *k, "project_id = ?1".to_string()))
+            .collect(),
+        EntityKind::Task => vec![("tasks", EntityKind::Task, format!("id <> ?1 AND id IN ({SUBTREE})"))],
+        _ => Vec::new(),
+    }
+}
+
+/// The row's `deleted_at`, `None` while it is live.
+async fn deleted_at(pool: &SqlitePool, s: &Spec, id: &str) -> Result<Option<String>> {
+    let row = sqlx::query(&format!("SELECT deleted_at FROM {} WHERE id = ?1", s.table))
+        .bind(id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::not_found(s.entity, id))?;
+    Ok(row.get("deleted_at"))
+}
+
+// ---------- Delete / restore ----------
+
+/// Move an entity to the Trash. Deleting something already there is a no-op.
+pub async fn delete(pool: &SqlitePool, kind: EntityKind, id: &str) -> Result<()> {
+    let s = spec(kind)?;
+    if deleted_at(pool, &s, id).await?.is_some() {
+        return Ok(());
+    }
+    if kind != EntityKind::Project {
+        projects::ensure_writable(pool, s.table, id).await?;
+    }
+    let now = Utc::now();
+
+    // children are listed inside the transaction so the journal covers exactly what is trashed
+    let mut tx = journal::begin(pool).await?;
+    let mut children = Vec::new();
+    for (table, child_kind, cond) in children_of(kind) {
+        let ids: Vec<String> =
+            sqlx::query_scalar(&format!("SELECT id FROM {table} WHERE {cond} AND deleted_at IS NULL"))
+                .bind(id)
+                .fetch_all(&mut *tx)
+                .await?;
+        children.extend(ids.into_iter().map(|c| (table, child_kind, c)));
+    }
+
+    let mut op = Recorder::new(s.delete_label);
+    op.track(&mut tx, s.table, id).await?;
+    for (table, _, child) in &children {
+        op.track(&mut tx, table, child).await?;
+    }
+
+    sqlx::query(&format!("UPDATE {} SET deleted_at = ?1 WHERE id = ?2", s.table))
+        .bind(now)
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+    for (table, _, cond) in children_of(kind) {
+        sqlx::query(&format!("UPDATE {table} SET deleted_at = ?2 WHERE {cond} AND deleted_at IS NULL"))
+            .bind(id)
+            .bind(now)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    op.commit(&mut tx).await?;
+    tx.commit().await?;
+    events::publish(kind, id, ChangeOp::Deleted);
+    for (_, child_kind, child) in children {
+        events::publish(child_kind, child, ChangeOp::Deleted);
+    }
+
+    Ok(())
+}
+
+/// Take an entity out of the Trash. A project brings back the rows that were
+/// trashed with it; anything else needs its project to be live first.
+pub async fn restore(pool: &SqlitePool, kind: EntityKind, id: &str) -> Result<()> {
+    let s = spec(kind)?;
+    if deleted_at(pool, &s, id).await?.is_none() {
+        return Ok(());
+    }
+
+    if kind != EntityKind::Project {
+        let parent_trashed: Option<bool> = sqlx::query_scalar(&format!(
+            r#"
+            SELECT p.deleted_at IS NOT NULL
+            FROM {} x JOIN project p ON p.id = x.project_id
+            WHERE x.id = ?1
+            "#,
+            s.table
+        ))
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+        if parent_trashed == Some(true) {
+            return Err(AppError::conflict(s.entity, "project_id", "Restore its project from the Trash first"));
+        }
+        if kind == EntityKind::Task {
+            let parent_trashed: Option<bool> = sqlx::query_scalar(
+                "SELECT p.deleted_at IS NOT NULL FROM tasks x JOIN tasks p ON p.id = x.parent_id WHERE x.id = ?1",
+            )
+            .bind(id)
+            .fetch_optional(pool)
+            .await?;
+            if parent_trashed == Some(true) {
+                return Err(AppError::conflict(s.entity, "parent_id", "Restore its parent task from the Trash first"));
+            }
+        }
+        projects::ensure_writable(pool, s.table, id).await?;
+    }
+
+    // only the rows trashed together with it, not ones deleted on their own
+    let same_time = format!("deleted_at = (SELECT deleted_at FROM {} WHERE id = ?1)", s.table);
+    let mut tx = journal::begin(pool).await?;
+    let mut children = Vec::new();
+    for (table, child_kind, cond) in children_of(kind) {
+        let ids: Vec<String> = sqlx::query_scalar(&format!("SELECT id FROM {table} WHERE {cond} AND {same_time}"))
+            .bind(id)
+            .fetch_all(&mut *tx)
+            .await?;
+        children.extend(ids.into_iter().map(|c| (table, child_kind, c)));
+    }
+
+    let mut op = Recorder::new(s.restore_label);
+    op.track(&mut tx, s.table, id).await?;
+    for (table, _, child) in &children {
+        op.track(&mut tx, table, child).await?;
+    }
+
+    for (table, _, cond) in children_of(kind) {
+        sqlx::query(&format!("UPDATE {table} SET deleted_at = NULL WHERE {cond} AND {same_time}"))
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+    }
+    sqlx::query(&format!("UPDATE {} SET deleted_at = NULL WHERE id = ?1", s.table))
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+    op.commit(&mut tx).await?;
+    tx.commit().await?;
+    events::publish(kind, id, ChangeOp::Created);
+    for (_, child_kind, child) in children {
+        events::publish(child_kind, child, ChangeOp::Created);
+    }
+
+    Ok(())
+}
+
+// ---------- Trash ----------
+
+/// Everything in the Trash, most recently deleted first. Rows that went in
+/// with their project are listed under the project only.
+pub async fn list_trash(pool: &SqlitePool) -> Result<Vec<TrashItem>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT 'task' AS kind, t.id, t.name AS title, t.project_id, t.deleted_at
+        FROM tasks t
+        LEFT JOIN project p ON p.id = t.project_id
+        LEFT JOIN tasks pt ON pt.id = t.parent_id
+        WHERE t.deleted_at IS NOT NULL
+          AND (p.deleted_at IS NULL OR p.deleted_at <> t.deleted_at)
+          AND (pt.deleted_at IS NULL OR pt.deleted_at <> t.deleted_at)
+        UNION ALL
+        SELECT 'idea', i.id, i.title, i.project_id, i.deleted_at
+        FROM idea i JOIN project p ON p.id = i.project_id
+        WHERE i.deleted_at IS NOT NULL AND (p.deleted_at IS NULL OR p.deleted_at <> i.deleted_at)
+        UNION ALL
+        SELECT 'doc', d.id, d.title, d.project_id, d.deleted_at
+        FROM doc d JOIN project p ON p.id = d.project_id
+        WHERE d.deleted_at IS NOT NULL AND (p.deleted_at IS NULL OR p.deleted_at <> d.deleted_at)
+        UNION ALL
+        SELECT 'project', id, name, NULL, deleted_at
+        FROM project
+        WHERE deleted_at IS NOT NULL
+        ORDER BY deleted_at DESC
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|r| TrashItem {
+            kind: match r.get::<&str, _>("kind") {
+                "task" => EntityKind::Task,
+                "idea" => EntityKind::Idea,
+                "doc" => EntityKind::Doc,
+                _ => EntityKind::Project,
+            },
+            id: r.get("id"),
+            title: r.get("title"),
+            project_id: r.get("project_id"),
+            deleted_at: r.get("deleted_at"),
+        })
+        .collect())
+}
+
+// ---------- Purge ----------
+
+/// Permanently delete a trashed entity, its cascaded children and the
+/// attachment files kept for them under `app_dir`. Not undoable.
+pub async fn purge(pool: &SqlitePool, app_dir: &Path, kind: EntityKind, id: &str) -> Result<PurgeReport> {
+    let s = spec(kind)?;
+    if deleted_at(pool, &s, id).await?.is_none() {
+        return Err(AppError::conflict(s.entity, "deleted_at", "Move it to the Trash before deleting it permanently"));
+    }
+    remove(pool, app_dir, kind, id).await
+}
+
+/// Purge everything in the Trash.
+pub async fn empty_trash(pool: &SqlitePool, app_dir: &Path) -> Result<PurgeReport> {
+    let mut report = PurgeReport::default();
+    for item in list_trash(pool).await? {
+        let r = match purge(pool, app_dir, item.kind, &item.id).await {
+            Ok(r) => r,
+            // purging a project or parent task already took this row with it
+            Err(e) if e.code() == ErrorCode::NotFound => continue,
+            Err(e) => return Err(e),
+        };
+        report.purged += r.purged;
+        report.files_removed += r.files_removed;
+    }
+    Ok(report)
+}
+
+// ---------- Delete with preview ----------
+
+/// What a permanent delete would take with it. `token` has to be echoed back
+/// to [`delete_permanently`]; it changes whenever the numbers do.
+#[derive(Debug, Serialize)]
+pub struct DeletePreview {
+    pub kind: EntityKind,
+    pub id: String,
+    pub rows: BTreeMap<String, i64>, // table -> rows removed, zero counts left out
+    pub files: usize,                // stored attachment files under attachments/
+    pub file_bytes: u64,
+    pub token: String,
+}
+
+/// Rows removed along with an entity, as `SELECT id` subqueries bound to `?1`.
+/// Tasks are listed explicitly: the schema only nulls their `project_id`.
+struct Scope {
+    table: &'static str,
+    entity: &'static str,
+    projects: &'static str,
+    ideas: &'static str,
+    docs: &'static str,
+    tasks: &'static str,
+}
+
+const NONE: &str = "SELECT NULL AS id WHERE 0";
+
+fn scope(kind: EntityKind) -> Result<Scope> {
+    Ok(match kind {
+        EntityKind::Workspace => Scope {
+            table: "workspace",
+            entity: "workspace",
+            projects: "SELECT id FROM project WHERE workspace_id = ?1",
+            ideas: "SELECT id FROM idea WHERE project_id IN (SELECT id FROM project WHERE workspace_id = ?1)",
+            docs: "SELECT id FROM doc WHERE project_id IN (SELECT id FROM project WHERE workspace_id = ?1)",
+            tasks: r#"
+                SELECT id FROM tasks
+                WHERE workspace_id = ?1 OR project_id IN (SELECT id FROM project WHERE workspace_id = ?1)
+            "#,
+        },
+        EntityKind::Project => Scope {
+            table: "project",
+            entity: "project",
+            projects: "SELECT id FROM project WHERE id = ?1",
+            ideas: "SELECT id FROM idea WHERE project_id = ?1",
+            docs: "SELECT id FROM doc WHERE project_id = ?1",
+            tasks: "SELECT id FROM tasks WHERE project_id = ?1",
+        },
+        EntityKind::Idea => Scope {
+            table: "idea",
+            entity: "idea",
+            projects: NONE,
+            ideas: "SELECT id FROM idea WHERE id = ?1",
+            docs: NONE,
+            tasks: NONE,
+        },
+        EntityKind::Doc => Scope {
+            table: "doc",
+            entity: "doc",
+            projects: NONE,
+            ideas: NONE,
+            docs: "SELECT id FROM doc WHERE id = ?1",
+            tasks: NONE,
+        },
+        EntityKind::Task => Scope {
+            table: "tasks",
+            entity: "task",
+            projects: NONE,
+            ideas: NONE,
+            docs: NONE,
+            tasks: SUBTREE,
+        },
+        _ => return Err(AppError::validation("kind", "Only workspaces, projects, ideas, docs and tasks can be deleted")),
+    })
+}
+
+#[derive(Clone, Copy)]
+enum Parent {
+    Idea,
+    Doc,
+    Task,
+}
+
+/// Child tables that go by cascade, keyed by the parent column(s).
+const CASCADES: [(&str, &[(&str, Parent)]); 14] = [
+    ("idea_note", &[("idea_id", Parent::Idea)]),
+    ("idea_link", &[("idea_id", Parent::Idea)]),
+    ("idea_attachment", &[("idea_id", Parent::Idea)]),
+    ("idea_tag", &[("idea_id", Parent::Idea)]),
+    ("idea_doc_map", &[("idea_id", Parent::Idea), ("doc_id", Parent::Doc)]),
+    ("idea_task_map", &[("idea_id", Parent::Idea), ("task_id", Parent::Task)]),
+    ("doc_version", &[("doc_id", Parent::Doc)]),
+    ("doc_attachment", &[("doc_id", Parent::Doc)]),
+    ("doc_tag", &[("doc_id", Parent::Doc)]),
+    ("status_reasons", &[("task_id", Parent::Task)]),
+    ("task_stages", &[("task_id", Parent::Task)]),
+    ("time_entry", &[("task_id", Parent::Task)]),
+    ("reminder", &[("task_id", Parent::Task)]),
+    ("task_dependency", &[("task_id", Parent::Task), ("depends_on", Parent::Task)]),
+];
+
+impl Scope {
+    fn parent(&self, p: Parent) -> &'static str {
+        match p {
+            Parent::Idea => self.ideas,
+            Parent::Doc => self.docs,
+            Parent::Task => self.tasks,
+        }
+    }
+}
+
+async fn count(pool: &SqlitePool, sql: &str, id: &str) -> Result<i64> {
+    Ok(sqlx::query_scalar(sql).bind(id).fetch_one(pool).await?)
+}
+
+/// Attachment paths in scope; gathered before the cascade takes their rows.
+async fn attachment_paths(pool: &SqlitePool, sc: &Scope, id: &str) -> Result<Vec<String>> {
+    let sql = format!(
+        r#"
+        SELECT path FROM idea_attachment WHERE idea_id IN ({})
+        UNION ALL
+        SELECT path FROM doc_attachment WHERE doc_id IN ({})
+        "#,
+        sc.ideas, sc.docs
+    );
+    Ok(sqlx::query_scalar(&sql).bind(id).fetch_all(pool).await?)
+}
+
+/// Count everything a permanent delete of `kind`/`id` would remove.
+pub async fn preview_delete(pool: &SqlitePool, app_dir: &Path, kind: EntityKind, id: &str) -> Result<DeletePreview> {
+    let sc = scope(kind)?;
+    let exists = count(pool, &format!("SELECT COUNT(*) FROM {} WHERE id = ?1", sc.table), id).await?;
+    if exists == 0 {
+        return Err(AppError::not_found(sc.entity, id));
+    }
+
+    let mut rows = BTreeMap::new();
+    if kind == EntityKind::Workspace {
+        rows.insert("workspace".to_string(), 1);
+    }
+    for (table, sql) in [("project", sc.projects), ("idea", sc.ideas), ("doc", sc.docs), ("tasks", sc.tasks)] {
+        rows.insert(table.to_string(), count(pool, &format!("SELECT COUNT(*) FROM ({sql})"), id).await?);
+    }
+    for (table, parents) in CASCADES {
+        let cond = parents
+            .iter()
+            .map(|(col, p)| format!("{col} IN ({})", sc.parent(*p)))
+            .collect::<Vec<_>>()
+            .join(" OR ");
+        rows.insert(table.to_string(), count(pool, &format!("SELECT COUNT(*) FROM {table} WHERE {cond}"), id).await?);
+    }
+    rows.retain(|_, n| *n > 0);
+
+    let (mut files, mut file_bytes) = (0, 0);
+    for f in attachment_paths(pool, &sc, id).await? {
+        let path = Path::new(&f);
+        if let (true, Ok(meta)) = (db::is_stored_attachment(app_dir, path), fs::metadata(path)) {
+            files += 1;
+            file_bytes += meta.len();
+        }
+    }
+
+    let mut h = DefaultHasher::new();
+    (format!("{kind:?}"), id, &rows, files, file_bytes).hash(&mut h);
+    Ok(DeletePreview { kind, id: id.to_string(), rows, files, file_bytes, token: format!("{:016x}", h.finish()) })
+}
+
+/// Permanently delete what [`preview_delete`] showed. Fails with a conflict if
+/// `token` is not the one a fresh preview gives, i.e. something changed since.
+pub async fn delete_permanently(
+    pool: &SqlitePool,
+    app_dir: &Path,
+    kind: EntityKind,
+    id: &str,
+    token: &str,
+) -> Result<PurgeReport> {
+    let preview = preview_delete(pool, app_dir, kind, id).await?;
+    if preview.token != token {
+        return Err(AppError::conflict(scope(kind)?.entity, "token", "This changed since the preview; review it again"));
+    }
+    remove(pool, app_dir, kind, id).await
+}
+
+/// The shared `DELETE` behind purge and [`delete_permanently`]. New tasks
+/// land in the default project, so it (and its workspace) must stay.
+async fn remove(pool: &SqlitePool, app_dir: &Path, kind: EntityKind, id: &str) -> Result<PurgeReport> {
+    let sc = scope(kind)?;
+    let holds_default: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM ({}) WHERE id = ?2", sc.projects))
+        .bind(id)
+        .bind(tasks::DEFAULT_PROJECT)
+        .fetch_one(pool)
+        .await?;
+    if holds_default > 0 {
+        return Err(AppError::conflict(sc.entity, "id", "The default project cannot be deleted permanently"));
+    }
+    let files = attachment_paths(pool, &sc, id).await?;
+
+    let mut tx = pool.begin().await?;
+    sqlx::query(&format!("DELETE FROM tasks WHERE id IN ({})", sc.tasks))
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query(&format!("DELETE FROM {} WHERE id = ?1", sc.table))
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    let files_removed = remove_files(app_dir, &files)?;
+    events::publish(kind, id, ChangeOp::Deleted);
+
+    Ok(PurgeReport { purged: 1, files_removed })
+}
+
+/// Delete attachment files, but only ones we stored under `attachments/`:
+/// anything else was linked by the user and is left alone. Emptied
+/// per-entity directories under `attachments/` go too.
+fn remove_files(app_dir: &Path, files: &[String]) -> Result<usize> {
+    let attachments = db::attachments_dir(app_dir);
+    let mut removed = 0;
+    for f in files {
+        let path = Path::new(f);
+        if !db::is_stored_attachment(app_dir, path) {
+            continue;
+        }
+        match fs::remove_file(path) {
+            Ok(()) => removed += 1,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+        if let Some(dir) = path.parent().filter(|d| d.starts_with(&attachments) && *d != attachments) {
+            fs::remove_dir(dir).ok(); // fails while other files remain, which is fine
+        }
+    }
+    Ok(removed)
+}
@@ -0,0 +1,437 @@
+// src/services/vault.rs
+//! Whole-vault export/import as a zip archive.
+//!
+//! Layout:
+//! ```text
+//! manifest.json            format, versions, row counts
+//! data/<table>.json        one JSON object per row
+//! attachments/...          copy of app_dir/attachments
+//! ```
+//! Attachment rows store paths relative to the app dir inside the archive
+//! and are re-rooted on import, so archives move between machines.
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{SqliteConnection, SqlitePool};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::services::journal;
+use crate::services::markdown;
+use crate::services::rows::{self, Image};
+use crate::{db, events, AppError, Result};
+
+pub const FORMAT: &str = "deepfocus-vault";
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Parents before children, so rows can be inserted in this order.
+const TABLES: &[&str] = &[
+    "workspace",
+    "project",
+    "tag",
+    "idea", // tasks.origin_idea_id points here
+    "task_series",
+    "tasks",
+    "status_reasons",
+    "task_stages",
+    "task_dependency",
+    "idea_tag",
+    "idea_note",
+    "idea_link",
+    "idea_attachment",
+    "idea_task_map",
+    "doc",
+    "doc_version",
+    "doc_tag",
+    "doc_attachment",
+    "idea_doc_map",
+    "time_entry", // may point at an idea or doc
+    "report",
+];
+
+/// Tables whose `path` column points into `app_dir/attachments`.
+const FILE_TABLES: &[&str] = &["idea_attachment", "doc_attachment"];
+
+const ATTACHMENTS: &str = "attachments";
+
+// ---------- Types ----------
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Manifest {
+    pub format: String,
+    pub format_version: u32,
+    pub schema_version: i64, // newest migration applied at export time
+    pub exported_at: DateTime<Utc>,
+    pub counts: BTreeMap<String, usize>,
+    pub attachments: usize,
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportMode {
+    Merge,   // keep local rows, add what is missing, report clashes
+    Replace, // wipe the vault first
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportConflict {
+    pub table: String,
+    pub id: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize, Default)]
+pub struct ImportReport {
+    pub inserted: BTreeMap<String, usize>,
+    pub unchanged: usize,
+    pub conflicts: Vec<ImportConflict>,
+    pub attachments: usize,
+}
+
+fn archive_err(message: impl Into<String>) -> AppError {
+    AppError::validation("archive", message)
+}
+
+// ---------- Schema helpers ----------
+
+async fn schema_version(conn: &mut SqliteConnection) -> Result<i64> {
+    Ok(sqlx::query_scalar("SELECT COALESCE(MAX(version), 0) FROM _sqlx_migrations WHERE success = 1")
+        .fetch_one(conn)
+        .await?)
+}
+
+fn row_label(row: &Image, pk: &[String]) -> String {
+    pk.iter()
+        .map(|c| match row.get(c) {
+            Some(Value::String(s)) => s.clone(),
+            Some(v) => v.to_string(),
+            None => String::new(),
+        })
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+// ---------- Export ----------
+
+/// Write the whole vault to `dest` and return its manifest.
+pub async fn export_vault(pool: &SqlitePool, app_dir: &Path, dest: &Path) -> Result<Manifest> {
+    // one read transaction, so every table comes from the same snapshot
+    let mut tx = pool.begin().await?;
+
+    let mut data = BTreeMap::new();
+    for &table in TABLES {
+        let (cols, _) = rows::columns(&mut tx, table).await?;
+        let raw: Vec<String> = sqlx::query_scalar(&format!("SELECT {} FROM {table} ORDER BY rowid", rows::json_object_expr(&cols)))
+            .fetch_all(&mut *tx)
+            .await?;
+
+        let mut images = Vec::with_capacity(raw.len());
+        for r in raw {
+            let mut row = rows::parse_image(&r)?;
+            if FILE_TABLES.contains(&table) {
+                relativize_path(&mut row, app_dir);
+            }
+            images.push(Value::Object(row));
+        }
+        data.insert(table, images);
+    }
+    let schema_version = schema_version(&mut tx).await?;
+    tx.commit().await?;
+
+    let files = attachment_files(app_dir)?;
+    let manifest = Manifest {
+        format: FORMAT.into(),
+        format_version: FORMAT_VERSION,
+        schema_version,
+        exported_at: Utc::now(),
+        counts: data.iter().map(|(t, images)| (t.to_string(), images.len())).collect(),
+        attachments: files.len(),
+    };
+
+    let mut zip = ZipWriter::new(File::create(dest)?);
+    let opts = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    zip.start_file("manifest.json", opts).map_err(zip_err)?;
+    zip.write_all(&to_json(&manifest)?)?;
+    for (table, images) in &data {
+        zip.start_file(format!("data/{table}.json"), opts).map_err(zip_err)?;
+        zip.write_all(&to_json(images)?)?;
+    }
+    for (rel, abs) in &files {
+        zip.start_file(format!("{ATTACHMENTS}/{rel}"), opts).map_err(zip_err)?;
+        zip.write_all(&fs::read(abs)?)?;
+    }
+    zip.finish().map_err(zip_err)?;
+
+    Ok(manifest)
+}
+
+fn to_json<T: Serialize + ?Sized>(v: &T) -> Result<Vec<u8>> {
+    serde_json::to_vec_pretty(v).map_err(|e| AppError::Other(e.to_string()))
+}
+
+fn zip_err(e: zip::result::ZipError) -> AppError {
+    match e {
+        zip::result::ZipError::Io(io) => AppError::Io(io),
+        other => archive_err(other.to_string()),
+    }
+}
+
+fn relativize_path(row: &mut Image, app_dir: &Path) {
+    if let Some(Value::String(p)) = row.get_mut("path") {
+        if let Ok(rel) = Path::new(p.as_str()).strip_prefix(app_dir) {
+            *p = rel.components().map(|c| c.as_os_str().to_string_lossy()).collect::<Vec<_>>().join("/");
+        }
+    }
+}
+
+/// Turn an archived `path` back into a local one. Relative paths must name a
+/// file under `attachments/`; absolute ones are the user's own files linked
+/// from elsewhere, so they may not point into `app_dir`, where purge would
+/// take them for ours. Anything else makes the archive unsafe to load.
+fn absolutize_path(row: &mut Image, app_dir: &Path) -> Result<()> {
+    let Some(Value::String(p)) = row.get_mut("path") else { return Ok(()) };
+    let path = Path::new(p.as_str());
+    let local = if path.is_relative() {
+        let abs = app_dir.join(path);
+        (path.starts_with(ATTACHMENTS) && db::is_stored_attachment(app_dir, &abs)).then_some(abs)
+    } else {
+        let climbs = path.components().any(|c| c == Component::ParentDir);
+        (!climbs && !path.starts_with(app_dir)).then(|| path.to_path_buf())
+    };
+    match local {
+        Some(local) => {
+            *p = local.to_string_lossy().into_owned();
+            Ok(())
+        }
+        None => Err(archive_err(format!("Unsafe attachment path in archive: {p}"))),
+    }
+}
+
+/// Render an archived doc's `body_html` from its Markdown rather than trust
+/// the exported copy.
+fn render_body(row: &mut Image) {
+    if let Some(md) = row.get("body_md").and_then(Value::as_str) {
+        let html = markdown::to_html(md);
+        row.insert("body_html".into(), Value::String(html));
+    }
+}
+
+/// `(path relative to attachments/, absolute path)` for every file, sorted.
+fn attachment_files(app_dir: &Path) -> Result<Vec<(String, PathBuf)>> {
+    fn walk(dir: &Path, prefix: &str, out: &mut Vec<(String, PathBuf)>) -> std::io::Result<()> {
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let rel = if prefix.is_empty() { name } else { format!("{prefix}/{name}") };
+            if entry.file_type()?.is_dir() {
+                walk(&entry.path(), &rel, out)?;
+            } else {
+                out.push((rel, entry.path()));
+            }
+        }
+        Ok(())
+    }
+
+    let root = app_dir.join(ATTACHMENTS);
+    let mut out = Vec::new();
+    if root.is_dir() {
+        walk(&root, "", &mut out)?;
+    }
+    out.sort();
+    Ok(out)
+}
+
+// ---------- Import ----------
+
+/// Read and check the manifest without touching the database.
+pub fn read_manifest(src: &Path) -> Result<Manifest> {
+    let mut zip = ZipArchive::new(File::open(src)?).map_err(zip_err)?;
+    let manifest: Manifest = read_json(&mut zip, "manifest.json")?
+        .ok_or_else(|| archive_err("Not a DeepFocus archive: manifest.json is missing"))?;
+
+    if manifest.format != FORMAT {
+        return Err(archive_err(format!("Unknown archive format '{}'", manifest.format)));
+    }
+    if manifest.format_version != FORMAT_VERSION {
+        return Err(archive_err(format!(
+            "Archive format v{} is not supported (expected v{FORMAT_VERSION})",
+            manifest.format_version
+        )));
+    }
+    Ok(manifest)
+}
+
+fn read_json<T: serde::de::DeserializeOwned>(zip: &mut ZipArchive<File>, name: &str) -> Result<Option<T>> {
+    let mut file = match zip.by_name(name) {
+        Ok(f) => f,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(zip_err(e)),
+    };
+    let mut buf = Vec::new();
+    file.read_to_end(&mut buf)?;
+    serde_json::from_slice(&buf)
+        .map(Some)
+        .map_err(|e| archive_err(format!("{name}: {e}")))
+}
+
+/// Load an archive into the vault.
+///
+/// `Replace` runs in one transaction and fails as a whole. `Merge` keeps every
+/// local row; incoming rows that clash with one are listed in the report.
+pub async fn import_vault(pool: &SqlitePool, app_dir: &Path, src: &Path, mode: ImportMode) -> Result<ImportReport> {
+    let manifest = read_manifest(src)?;
+    let mut zip = ZipArchive::new(File::open(src)?).map_err(zip_err)?;
+
+    let mut report = ImportReport::default();
+    let mut tx = pool.begin().await?;
+
+    let current = schema_version(&mut tx).await?;
+    if manifest.schema_version > current {
+        return Err(archive_err(format!(
+            "Archive comes from a newer version of DeepFocus (schema {} > {current}); update the app first",
+            manifest.schema_version
+        )));
+    }
+
+    if mode == ImportMode::Replace {
+        for table in TABLES.iter().rev() {
+            sqlx::query(&format!("DELETE FROM {table}")).execute(&mut *tx).await?;
+        }
+        // history refers to rows that no longer exist
+        journal::clear(&mut tx).await?;
+    }
+
+    for &table in TABLES {
+        let mut incoming: Vec<Image> = read_json(&mut zip, &format!("data/{table}.json"))?.unwrap_or_default();
+        if table == "tasks" {
+            parents_first(&mut incoming);
+        }
+        let (cols, pk) = rows::columns(&mut tx, table).await?;
+
+        for mut row in incoming {
+            // older archives may lack newer columns; unknown ones are dropped
+            row.retain(|k, _| cols.contains(k));
+            if FILE_TABLES.contains(&table) {
+                absolutize_path(&mut row, app_dir)?;
+            }
+            if table == "doc" {
+                render_body(&mut row);
+            }
+
+            match rows::insert_image(&mut tx, table, &row).await {
+                Ok(_) => *report.inserted.entry(table.to_string()).or_default() += 1,
+                Err(e) if mode == ImportMode::Replace => return Err(e),
+                Err(AppError::Conflict { .. }) => {
+                    if existing_matches(&mut tx, table, &pk, &row).await? {
+                        report.unchanged += 1;
+                    } else {
+                        report.conflicts.push(ImportConflict {
+                            table: table.into(),
+                            id: row_label(&row, &pk),
+                            reason: "differs from the local copy; kept local".into(),
+                        });
+                    }
+                }
+                Err(AppError::ForeignKey { .. }) => report.conflicts.push(ImportConflict {
+                    table: table.into(),
+                    id: row_label(&row, &pk),
+                    reason: "references a row that was not imported; skipped".into(),
+                }),
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    tx.commit().await?;
+
+    import_attachments(&mut zip, app_dir, mode, &mut report)?;
+    events::publish_vault_changed();
+    Ok(report)
+}
+
+/// Order task rows so every parent precedes its subtasks; re-parenting can
+/// leave a subtask with a lower rowid than its parent.
+fn parents_first(rows: &mut [Image]) {
+    let parent_of: HashMap<String, String> = rows
+        .iter()
+        .filter_map(|r| Some((r.get("id")?.as_str()?.to_string(), r.get("parent_id")?.as_str()?.to_string())))
+        .collect();
+    let depth = |r: &Image| {
+        let mut d = 0;
+        let mut cur = r.get("id").and_then(Value::as_str);
+        while let Some(p) = cur.and_then(|id| parent_of.get(id)) {
+            d += 1;
+            if d > parent_of.len() {
+                break; // a cycle; leave it to the FK check
+            }
+            cur = Some(p);
+        }
+        d
+    };
+    rows.sort_by_cached_key(depth);
+}
+
+/// `true` if a row with the same key exists and has identical values.
+async fn existing_matches(conn: &mut SqliteConnection, table: &str, pk: &[String], row: &Image) -> Result<bool> {
+    let key: Image = pk.iter().filter_map(|c| row.get(c).map(|v| (c.clone(), v.clone()))).collect();
+    if key.is_empty() || key.len() != pk.len() {
+        return Ok(false);
+    }
+    // None: the clash was on another unique column (e.g. doc.slug)
+    Ok(rows::fetch_image(conn, table, &key)
+        .await?
+        .is_some_and(|local| row.iter().all(|(k, v)| local.get(k) == Some(v))))
+}
+
+fn import_attachments(
+    zip: &mut ZipArchive<File>,
+    app_dir: &Path,
+    mode: ImportMode,
+    report: &mut ImportReport,
+) -> Result<()> {
+    let root = app_dir.join(ATTACHMENTS);
+    if mode == ImportMode::Replace && root.exists() {
+        fs::remove_dir_all(&root)?;
+    }
+
+    for i in 0..zip.len() {
+        let mut file = zip.by_index(i).map_err(zip_err)?;
+        if file.is_dir() {
+            continue;
+        }
+        // enclosed_name() rejects absolute paths and `..`
+        let Some(rel) = file.enclosed_name() else {
+            return Err(archive_err(format!("Unsafe path in archive: {}", file.name())));
+        };
+        let Ok(rel) = rel.strip_prefix(ATTACHMENTS) else {
+            continue;
+        };
+
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)?;
+
+        let target = root.join(rel);
+        if target.exists() {
+            if fs::read(&target)? != bytes {
+                report.conflicts.push(ImportConflict {
+                    table: ATTACHMENTS.into(),
+                    id: rel.to_string_lossy().into_owned(),
+                    reason: "file differs from the local copy; kept local".into(),
+                });
+            }
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&target, &bytes)?;
+        report.attachments += 1;
+    }
+    Ok(())
+}
@@ -0,0 +1,88 @@
+mod common;
+
+use deep_focus_lib::db;
+use deep_focus_lib::services::backup::{self, BackupSettings};
+use deep_focus_lib::services::tasks;
+use deep_focus_lib::ErrorCode;
+
+#[tokio::test]
+async fn snapshot_verify_and_restore() {
+    let dir = tempfile::tempdir().unwrap();
+    let db_path = dir.path().join("deepfocus.db");
+    let pool = db::open_pool(&db_path).await.unwrap();
+
+    let kept = common::task(&pool, "Before backup").await;
+    let snap = backup::create_backup(&pool, dir.path()).await.unwrap();
+    let lost = common::task(&pool, "After backup").await;
+
+    let check = backup::verify_backup(dir.path(), &snap.name).await.unwrap();
+    assert!(check.ok, "{:?}", check.problems);
+
+    let shared = db::SharedPool::new(pool);
+    let pool = backup::restore_backup(&shared, &db_path, dir.path(), &snap.name).await.unwrap();
+    assert!(tasks::get_task(&shared.get(), &kept).await.is_ok());
+    assert_eq!(tasks::get_task(&pool, &lost).await.unwrap_err().code(), ErrorCode::NotFound);
+
+    // the pre-restore snapshot still has the newer task
+    let names: Vec<String> = backup::list_backups(dir.path()).unwrap().into_iter().map(|b| b.name).collect();
+    assert_eq!(names.len(), 2);
+    assert!(names[0].ends_with("-pre-restore.db"));
+}
+
+#[tokio::test]
+async fn failed_restore_keeps_the_live_database() {
+    let dir = tempfile::tempdir().unwrap();
+    let db_path = dir.path().join("deepfocus.db");
+    let shared = db::SharedPool::new(db::open_pool(&db_path).await.unwrap());
+
+    let snap = backup::create_backup(&shared.get(), dir.path()).await.unwrap();
+    let newer = common::task(&shared.get(), "After backup").await;
+
+    // a non-empty directory where the old file is moved aside makes the swap fail
+    let blocker = dir.path().join("deepfocus.db.replaced");
+    std::fs::create_dir_all(blocker.join("x")).unwrap();
+    let err = backup::restore_backup(&shared, &db_path, dir.path(), &snap.name).await.unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Io); // the swap's own error, not a rollback one
+
+    assert!(tasks::get_task(&shared.get(), &newer).await.is_ok());
+    assert!(!dir.path().join("deepfocus.db.restoring").exists());
+}
+
+#[tokio::test]
+async fn rejects_unknown_or_corrupt_backups() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let err = backup::verify_backup(dir.path(), "../deepfocus.db").await.unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Validation);
+
+    let err = backup::verify_backup(dir.path(), "deepfocus-missing.db").await.unwrap_err();
+    assert_eq!(err.code(), ErrorCode::NotFound);
+
+    std::fs::create_dir_all(backup::backups_dir(dir.path())).unwrap();
+    std::fs::write(backup::backups_dir(dir.path()).join("deepfocus-junk.db"), b"not a database at all").unwrap();
+    assert!(backup::verify_backup(dir.path(), "deepfocus-junk.db").await.is_err());
+}
+
+#[tokio::test]
+async fn retention_prunes_oldest() {
+    // VACUUM INTO from an in-memory database writes nowhere, so use a file
+    let dir = tempfile::tempdir().unwrap();
+    let pool = db::open_pool(&dir.path().join("deepfocus.db")).await.unwrap();
+    backup::set_settings(&pool, BackupSettings { enabled: true, interval_hours: 24, keep: 2 }).await.unwrap();
+
+    // a fresh manual snapshot doesn't put the scheduled one off
+    let manual = backup::create_backup(&pool, dir.path()).await.unwrap().name;
+    tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+    let mut made = vec![backup::backup_if_due(&pool, dir.path()).await.unwrap().unwrap().name];
+    for _ in 0..2 {
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        made.push(backup::scheduled_backup(&pool, dir.path()).await.unwrap().name);
+    }
+
+    // manual snapshots don't count toward retention and are never pruned
+    let left: Vec<String> = backup::list_backups(dir.path()).unwrap().into_iter().map(|b| b.name).collect();
+    assert_eq!(left, vec![made[2].clone(), made[1].clone(), manual]);
+
+    // newest is fresh, so the scheduler has nothing to do
+    assert!(backup::backup_if_due(&pool, dir.path()).await.unwrap().is_none());
+}
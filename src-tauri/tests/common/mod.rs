@@ -0,0 +1,49 @@
+#![allow(dead_code)]
+
+use std::str::FromStr;
+
+use deep_focus_lib::db;
+use deep_focus_lib::services::tasks;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+use sqlx::SqlitePool;
+
+/// Fresh in-memory database with the real migrations applied.
+///
+/// A single connection that never idles out, so the in-memory database lives
+/// as long as the pool does.
+pub async fn pool() -> SqlitePool {
+    let opts = SqliteConnectOptions::from_str("sqlite::memory:")
+        .expect("memory url")
+        .foreign_keys(true);
+
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .idle_timeout(None)
+        .max_lifetime(None)
+        .connect_with(opts)
+        .await
+        .expect("connect in-memory sqlite");
+
+    db::MIGRATOR.run(&pool).await.expect("run migrations");
+    pool
+}
+
+/// Seeded by the 20250817 migration.
+pub const PERSONAL_WS: &str = "ws_personal";
+pub const PERSONAL_GENERAL: &str = "prj_personal_general";
+pub const WORK_GENERAL: &str = "prj_work_general";
+
+/// Build a command input from JSON, the same way the frontend payload arrives.
+pub fn input<T: serde::de::DeserializeOwned>(v: serde_json::Value) -> T {
+    serde_json::from_value(v).expect("valid input json")
+}
+
+/// Add a to-do task named `name` to the work project; returns its id.
+pub async fn task(pool: &SqlitePool, name: &str) -> String {
+    tasks::add_task(
+        pool,
+        input(serde_json::json!({ "name": name, "category": "Dev", "status": "todo", "project_id": WORK_GENERAL })),
+    )
+    .await
+    .expect("add task")
+}
@@ -0,0 +1,107 @@
+mod common;
+
+use deep_focus_lib::events::EntityKind;
+use deep_focus_lib::services::dependencies::{self, DependencyInput};
+use deep_focus_lib::services::tasks::{self, StatusRules, TaskFilter, TaskStatus};
+use deep_focus_lib::services::trash;
+use deep_focus_lib::ErrorCode;
+use serde_json::json;
+
+fn dep(task_id: &str, depends_on: &str) -> DependencyInput {
+    DependencyInput { task_id: task_id.into(), depends_on: depends_on.into() }
+}
+
+#[tokio::test]
+async fn blocked_until_every_predecessor_is_completed() {
+    let pool = common::pool().await;
+    let design = common::task(&pool, "Design").await;
+    let build = common::task(&pool, "Build").await;
+    let ship = common::task(&pool, "Ship").await;
+    dependencies::add_dependency(&pool, dep(&build, &design)).await.unwrap();
+    dependencies::add_dependency(&pool, dep(&ship, &build)).await.unwrap();
+
+    let blocked = |rows: &[tasks::TaskRow], id: &str| rows.iter().find(|t| t.id == id).unwrap().blocked;
+    let rows = tasks::list_tasks(&pool, &TaskFilter::default()).await.unwrap();
+    assert!(!blocked(&rows, &design) && blocked(&rows, &build) && blocked(&rows, &ship));
+    let filter = TaskFilter { blocked: Some(true), ..Default::default() };
+    assert_eq!(tasks::list_tasks(&pool, &filter).await.unwrap().len(), 2);
+
+    let detail = tasks::get_task(&pool, &build).await.unwrap();
+    assert!(detail.blocked);
+    assert_eq!(detail.blocked_by[0].id, design);
+    assert_eq!(detail.blocks[0].id, ship);
+
+    tasks::set_task_status(&pool, &design, TaskStatus::Completed, None).await.unwrap();
+    let rows = tasks::list_tasks(&pool, &TaskFilter::default()).await.unwrap();
+    assert!(!blocked(&rows, &build) && blocked(&rows, &ship));
+
+    // a blocked task can be started; it just stays flagged and warns
+    let change = tasks::set_task_status(&pool, &ship, TaskStatus::InProgress, None).await.unwrap();
+    assert_eq!(change.warning.as_deref(), Some("Blocked by Build"));
+    assert!(tasks::get_task(&pool, &ship).await.unwrap().blocked);
+    tasks::set_task_status(&pool, &ship, TaskStatus::Started, None).await.unwrap();
+
+    // unless the rule asks for a note
+    let rules = StatusRules { note_to_start_blocked: true, ..Default::default() };
+    tasks::set_status_rules(&pool, &rules).await.unwrap();
+    let err = tasks::set_task_status(&pool, &ship, TaskStatus::InProgress, None).await.unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Validation);
+    let err = tasks::update_task(&pool, common::input(json!({ "id": ship, "status": "in-progress" }))).await.unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Validation);
+    let change = tasks::set_task_status(&pool, &ship, TaskStatus::InProgress, Some("Docs only".into())).await.unwrap();
+    assert!(change.warning.is_some());
+    let change = tasks::set_task_status(&pool, &build, TaskStatus::InProgress, None).await.unwrap();
+    assert!(change.warning.is_none());
+
+    dependencies::remove_dependency(&pool, dep(&ship, &build)).await.unwrap();
+    assert!(!tasks::get_task(&pool, &ship).await.unwrap().blocked);
+    let err = dependencies::remove_dependency(&pool, dep(&ship, &build)).await.unwrap_err();
+    assert_eq!(err.code(), ErrorCode::NotFound);
+}
+
+#[tokio::test]
+async fn cycles_are_refused() {
+    let pool = common::pool().await;
+    let a = common::task(&pool, "A").await;
+    let b = common::task(&pool, "B").await;
+    let c = common::task(&pool, "C").await;
+    dependencies::add_dependency(&pool, dep(&b, &a)).await.unwrap();
+    dependencies::add_dependency(&pool, dep(&c, &b)).await.unwrap();
+
+    let err = dependencies::add_dependency(&pool, dep(&a, &c)).await.unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Conflict);
+    assert!(err.to_string().contains("A → C → B → A"), "{err}");
+
+    let err = dependencies::add_dependency(&pool, dep(&a, &a)).await.unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Validation);
+    let err = dependencies::add_dependency(&pool, dep(&b, &a)).await.unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Conflict);
+    let err = dependencies::add_dependency(&pool, dep(&a, "nope")).await.unwrap_err();
+    assert_eq!(err.code(), ErrorCode::ForeignKey);
+}
+
+#[tokio::test]
+async fn graph_orders_a_project_by_depth() {
+    let pool = common::pool().await;
+    let a = common::task(&pool, "Spec").await;
+    let b = common::task(&pool, "API").await;
+    let c = common::task(&pool, "UI").await;
+    let d = common::task(&pool, "Release").await;
+    common::task(&pool, "Unrelated").await;
+    for (t, on) in [(&b, &a), (&c, &a), (&c, &b), (&d, &c)] {
+        dependencies::add_dependency(&pool, dep(t, on)).await.unwrap();
+    }
+
+    let g = dependencies::dependency_graph(&pool, common::WORK_GENERAL).await.unwrap();
+    let order: Vec<(&str, u32)> = g.nodes.iter().map(|n| (n.name.as_str(), n.depth)).collect();
+    assert_eq!(order, [("Spec", 0), ("API", 1), ("UI", 2), ("Release", 3)]);
+    assert_eq!(g.edges.len(), 4);
+    assert!(!g.nodes[0].blocked && g.nodes[3].blocked);
+
+    // both ends go with a permanent delete
+    let preview = trash::preview_delete(&pool, std::path::Path::new("/nonexistent"), EntityKind::Task, &c).await.unwrap();
+    assert_eq!(preview.rows["task_dependency"], 3);
+
+    let err = dependencies::dependency_graph(&pool, "nope").await.unwrap_err();
+    assert_eq!(err.code(), ErrorCode::NotFound);
+}
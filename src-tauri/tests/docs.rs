@@ -0,0 +1,142 @@
+mod common;
+
+use base64::Engine;
+use deep_focus_lib::services::docs;
+use deep_focus_lib::ErrorCode;
+use serde_json::json;
+
+async fn seed_doc(pool: &sqlx::SqlitePool, title: &str, slug: &str) -> String {
+    docs::add_doc(
+        pool,
+        common::input(json!({
+            "project_id": common::PERSONAL_GENERAL,
+            "title": title,
+            "slug": slug,
+            "body_md": "# Hello"
+        })),
+    )
+    .await
+    .unwrap()
+}
+
+async fn version_count(pool: &sqlx::SqlitePool, doc_id: &str) -> i64 {
+    sqlx::query_scalar("SELECT COUNT(1) FROM doc_version WHERE doc_id = ?1")
+        .bind(doc_id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn body_updates_are_versioned() {
+    let pool = common::pool().await;
+    let id = seed_doc(&pool, "Deep work notes", "deep-work").await;
+    assert_eq!(version_count(&pool, &id).await, 1);
+
+    docs::update_doc_body(&pool, common::input(json!({ "id": id, "body_md": "# Hello again" })))
+        .await
+        .unwrap();
+
+    let doc = docs::get_doc(&pool, &id).await.unwrap();
+    assert_eq!(doc.body_md, "# Hello again");
+    assert_eq!(doc.status, "draft");
+    assert_eq!(version_count(&pool, &id).await, 2);
+
+    let err = docs::update_doc_body(&pool, common::input(json!({ "id": "ghost", "body_md": "" })))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::NotFound);
+}
+
+#[tokio::test]
+async fn raw_html_is_escaped_and_old_renders_refreshed() {
+    let pool = common::pool().await;
+    let id = docs::add_doc(
+        &pool,
+        common::input(json!({
+            "project_id": common::PERSONAL_GENERAL,
+            "title": "Sneaky",
+            "body_md": "<script>alert(1)</script>\n\nsee <b>this</b>"
+        })),
+    )
+    .await
+    .unwrap();
+    let html = docs::get_doc(&pool, &id).await.unwrap().body_html;
+    assert!(!html.contains("<script>") && !html.contains("<b>"), "{html}");
+    assert!(html.contains("&lt;script&gt;"), "{html}");
+
+    // rows from before rendering kept the Markdown as their HTML
+    sqlx::query("UPDATE doc SET body_html = body_md").execute(&pool).await.unwrap();
+    docs::refresh_html(&pool).await.unwrap();
+    assert_eq!(docs::get_doc(&pool, &id).await.unwrap().body_html, html);
+}
+
+#[tokio::test]
+async fn duplicate_slug_is_a_conflict() {
+    let pool = common::pool().await;
+    seed_doc(&pool, "One", "same").await;
+    let other = seed_doc(&pool, "Two", "other").await;
+
+    let err = docs::update_doc_meta(&pool, common::input(json!({ "id": other, "slug": "same" })))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Conflict);
+    assert_eq!(serde_json::to_value(&err).unwrap()["field"], "slug");
+
+    docs::update_doc_meta(&pool, common::input(json!({ "id": other, "title": "Two!", "status": "published" })))
+        .await
+        .unwrap();
+    let doc = docs::get_doc(&pool, &other).await.unwrap();
+    assert_eq!(doc.title, "Two!");
+    assert_eq!(doc.slug.as_deref(), Some("other"));
+}
+
+#[tokio::test]
+async fn docs_can_be_fetched_by_slug() {
+    let pool = common::pool().await;
+    let id = seed_doc(&pool, "Weekly review", "weekly-review").await;
+
+    assert_eq!(docs::get_doc_by_slug(&pool, "weekly-review").await.unwrap().id, id);
+    assert_eq!(docs::get_doc_by_slug(&pool, &id).await.unwrap().title, "Weekly review");
+    let err = docs::get_doc_by_slug(&pool, "nope").await.unwrap_err();
+    assert_eq!(err.code(), ErrorCode::NotFound);
+}
+
+#[tokio::test]
+async fn list_and_search_are_project_scoped() {
+    let pool = common::pool().await;
+    seed_doc(&pool, "Focus rituals", "focus").await;
+
+    assert_eq!(docs::list_docs(&pool, common::PERSONAL_GENERAL).await.unwrap().len(), 1);
+    assert!(docs::list_docs(&pool, common::WORK_GENERAL).await.unwrap().is_empty());
+    assert_eq!(docs::search_docs(&pool, "rituals", None).await.unwrap().len(), 1);
+    assert!(docs::search_docs(&pool, "rituals", Some(common::WORK_GENERAL)).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn attachments_are_written_under_app_dir() {
+    let pool = common::pool().await;
+    let dir = tempfile::tempdir().unwrap();
+    let id = seed_doc(&pool, "With image", "img").await;
+
+    let bytes = base64::engine::general_purpose::STANDARD.encode(b"png bytes");
+    let path = docs::save_doc_attachment(
+        &pool,
+        dir.path(),
+        common::input(json!({ "doc_id": id, "filename": "a.png", "bytes_base64": bytes })),
+    )
+    .await
+    .unwrap();
+
+    assert!(path.starts_with(dir.path().join("attachments").join(&id).to_str().unwrap()));
+    assert_eq!(std::fs::read(&path).unwrap(), b"png bytes");
+
+    let err = docs::save_doc_attachment(
+        &pool,
+        dir.path(),
+        common::input(json!({ "doc_id": id, "filename": "../escape", "bytes_base64": "" })),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Validation);
+}
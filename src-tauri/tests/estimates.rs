@@ -0,0 +1,87 @@
+mod common;
+
+use deep_focus_lib::services::metrics::{self, AccuracyGroup};
+use deep_focus_lib::services::tasks::{self, TaskStatus};
+use deep_focus_lib::services::time;
+use deep_focus_lib::ErrorCode;
+use serde_json::json;
+
+async fn log(pool: &sqlx::SqlitePool, task_id: &str, start: &str, end: &str) {
+    time::log_time(pool, common::input(json!({ "task_id": task_id, "started_at": start, "ended_at": end })))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn task_detail_compares_estimate_with_logged_time() {
+    let pool = common::pool().await;
+    let root = common::task(&pool, "Feature").await;
+    let sub = |name: &str, estimate: i64| {
+        common::input(json!({ "name": name, "category": "Dev", "status": "todo", "parent_id": root, "estimate_minutes": estimate }))
+    };
+    let a = tasks::add_task(&pool, sub("A", 60)).await.unwrap();
+    let b = tasks::add_task(&pool, sub("B", 30)).await.unwrap();
+
+    log(&pool, &a, "2025-09-01T09:00:00Z", "2025-09-01T10:30:00Z").await;
+    log(&pool, &root, "2025-09-01T11:00:00Z", "2025-09-01T11:15:00Z").await;
+
+    let t = tasks::get_task(&pool, &root).await.unwrap();
+    assert_eq!(t.estimate_minutes, None);
+    assert_eq!(
+        (t.time.estimate_minutes, t.time.actual_minutes, t.time.variance_minutes),
+        (Some(90), 105, Some(15))
+    );
+    assert_eq!(t.subtasks[0].actual_minutes, 90);
+
+    // progress is weighted by estimate once every child has one
+    tasks::set_task_status(&pool, &b, TaskStatus::Completed, None).await.unwrap();
+    assert_eq!(tasks::get_task(&pool, &root).await.unwrap().progress, 33);
+
+    let err = time::log_time(
+        &pool,
+        common::input(json!({ "task_id": a, "started_at": "2025-09-01T10:00:00Z", "ended_at": "2025-09-01T09:00:00Z" })),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Validation);
+    let err = tasks::update_task(&pool, common::input(json!({ "id": a, "estimate_minutes": -5 })))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Validation);
+}
+
+#[tokio::test]
+async fn accuracy_report_groups_completed_tasks() {
+    let pool = common::pool().await;
+    let specs = [
+        ("Design", common::WORK_GENERAL, 60, ("09:00", "11:00")),
+        ("Dev", common::WORK_GENERAL, 60, ("12:00", "13:30")),
+        ("Dev", common::PERSONAL_GENERAL, 60, ("14:00", "14:30")),
+    ];
+    for (category, project, estimate, (start, end)) in specs {
+        let id = tasks::add_task(
+            &pool,
+            common::input(json!({ "name": "x", "category": category, "status": "todo", "project_id": project, "estimate_minutes": estimate })),
+        )
+        .await
+        .unwrap();
+        log(&pool, &id, &format!("2025-09-01T{start}:00Z"), &format!("2025-09-01T{end}:00Z")).await;
+        tasks::set_task_status(&pool, &id, TaskStatus::Completed, None).await.unwrap();
+    }
+    // open tasks don't count
+    tasks::add_task(&pool, common::input(json!({ "name": "y", "category": "Dev", "status": "todo", "estimate_minutes": 10 })))
+        .await
+        .unwrap();
+
+    let by_cat = metrics::estimate_accuracy(&pool, AccuracyGroup::Category, None, None).await.unwrap();
+    let rows: Vec<_> = by_cat.iter().map(|r| (r.label.as_str(), r.tasks, r.estimate_minutes, r.actual_minutes)).collect();
+    assert_eq!(rows, [("Design", 1, 60, 120), ("Dev", 2, 120, 120)]);
+    assert_eq!(by_cat[0].ratio, 2.0);
+
+    let by_project = metrics::estimate_accuracy(&pool, AccuracyGroup::Project, None, None).await.unwrap();
+    assert_eq!(by_project[0].key.as_deref(), Some(common::WORK_GENERAL));
+    assert_eq!(by_project[0].label, "Work/General");
+
+    let future = "2999-01-01T00:00:00Z".parse().ok();
+    assert!(metrics::estimate_accuracy(&pool, AccuracyGroup::Category, future, None).await.unwrap().is_empty());
+}
@@ -0,0 +1,70 @@
+mod common;
+
+use deep_focus_lib::events::{self, ChangeOp, EntityChanged, EntityKind};
+use deep_focus_lib::services::{ideas, tasks};
+use serde_json::json;
+use tokio::sync::broadcast::Receiver;
+
+/// Everything published so far that concerns `ids`; other tests share the bus.
+fn drain(rx: &mut Receiver<EntityChanged>, ids: &[&str]) -> Vec<(EntityKind, String, ChangeOp)> {
+    let mut out = Vec::new();
+    while let Ok(c) = rx.try_recv() {
+        if ids.contains(&c.id.as_str()) {
+            out.push((c.kind, c.id, c.op));
+        }
+    }
+    out
+}
+
+#[tokio::test]
+async fn writes_publish_after_success() {
+    let pool = common::pool().await;
+    let mut rx = events::subscribe();
+
+    let task = tasks::add_task(
+        &pool,
+        common::input(json!({ "name": "Live", "category": "Dev", "status": "todo" })),
+    )
+    .await
+    .unwrap();
+    tasks::update_task(&pool, common::input(json!({ "id": task, "name": "Still live" })))
+        .await
+        .unwrap();
+
+    // failed writes stay quiet
+    tasks::update_task(&pool, common::input(json!({ "id": "missing", "name": "x" })))
+        .await
+        .unwrap_err();
+
+    assert_eq!(
+        drain(&mut rx, &[&task, "missing"]),
+        vec![
+            (EntityKind::Task, task.clone(), ChangeOp::Created),
+            (EntityKind::Task, task.clone(), ChangeOp::Updated),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn child_rows_report_their_parent() {
+    let pool = common::pool().await;
+    let idea = ideas::add_idea(
+        &pool,
+        common::input(json!({ "project_id": common::PERSONAL_GENERAL, "title": "Events" })),
+    )
+    .await
+    .unwrap();
+
+    let mut rx = events::subscribe();
+    ideas::add_idea_note(&pool, common::input(json!({ "idea_id": idea, "body_md": "note" })))
+        .await
+        .unwrap();
+
+    assert_eq!(drain(&mut rx, &[&idea]), vec![(EntityKind::Idea, idea.clone(), ChangeOp::Updated)]);
+}
+
+#[test]
+fn payload_shape() {
+    let v = serde_json::to_value(EntityChanged::vault()).unwrap();
+    assert_eq!(v, json!({ "kind": "vault", "id": "*", "op": "updated" }));
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use deep_focus_lib::events::EntityKind;
+use deep_focus_lib::services::{history, ideas, journal, tasks};
+use deep_focus_lib::ErrorCode;
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn task_updates_log_each_changed_field() {
+    let pool = common::pool().await;
+    let id = tasks::add_task(
+        &pool,
+        common::input(json!({ "name": "Ship", "category": "Dev", "status": "todo", "end_est_at": "2025-09-10T00:00:00Z" })),
+    )
+    .await
+    .unwrap();
+    assert!(history::get_history(&pool, EntityKind::Task, &id).await.unwrap().is_empty());
+
+    tasks::update_task(&pool, common::input(json!({ "id": id, "end_est_at": "2025-09-17T00:00:00Z" })))
+        .await
+        .unwrap();
+    tasks::set_task_status(&pool, &id, common::input(json!("started")), None).await.unwrap();
+
+    let log = history::get_history(&pool, EntityKind::Task, &id).await.unwrap();
+    let fields: Vec<&str> = log.iter().map(|c| c.field.as_str()).collect();
+    assert_eq!(fields, ["end_est_at", "status"]);
+
+    let slip = &log[0];
+    assert!(slip.old_value.as_str().unwrap().starts_with("2025-09-10"));
+    assert!(slip.new_value.as_str().unwrap().starts_with("2025-09-17"));
+    assert_eq!(log[1].old_value, json!("todo"));
+    assert_eq!(log[1].new_value, json!("started"));
+}
+
+#[tokio::test]
+async fn idea_edits_and_undo_are_logged() {
+    let pool = common::pool().await;
+    let id = ideas::add_idea(
+        &pool,
+        common::input(json!({ "project_id": common::PERSONAL_GENERAL, "title": "Offline mode" })),
+    )
+    .await
+    .unwrap();
+
+    ideas::update_idea(&pool, common::input(json!({ "id": id, "priority": 4 })))
+        .await
+        .unwrap();
+    journal::undo(&pool).await.unwrap();
+
+    let log = history::get_history(&pool, EntityKind::Idea, &id).await.unwrap();
+    let priorities: Vec<(Value, Value)> =
+        log.iter().filter(|c| c.field == "priority").map(|c| (c.old_value.clone(), c.new_value.clone())).collect();
+    assert_eq!(priorities, [(json!(2), json!(4)), (json!(4), json!(2))]);
+}
+
+#[tokio::test]
+async fn other_kinds_are_rejected() {
+    let pool = common::pool().await;
+    let err = history::get_history(&pool, EntityKind::Doc, "whatever").await.unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Validation);
+}
@@ -0,0 +1,155 @@
+mod common;
+
+use axum::body::{to_bytes, Body};
+use axum::http::{header, Request, StatusCode};
+use axum::Router;
+use deep_focus_lib::db::SharedPool;
+use deep_focus_lib::http;
+use deep_focus_lib::services::{ideas, tasks};
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+const TOKEN: &str = "test-token";
+
+async fn call(app: &Router, method: &str, uri: &str, token: Option<&str>, body: Option<Value>) -> (StatusCode, Value) {
+    let mut req = Request::builder().method(method).uri(uri);
+    if let Some(t) = token {
+        req = req.header(header::AUTHORIZATION, format!("Bearer {t}"));
+    }
+    let req = match body {
+        Some(b) => req
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(b.to_string())),
+        None => req.body(Body::empty()),
+    }
+    .unwrap();
+
+    let res = app.clone().oneshot(req).await.unwrap();
+    let status = res.status();
+    let bytes = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    let json = if bytes.is_empty() { Value::Null } else { serde_json::from_slice(&bytes).unwrap() };
+    (status, json)
+}
+
+#[tokio::test]
+async fn rejects_missing_or_wrong_token() {
+    let app = http::router(SharedPool::new(common::pool().await), TOKEN.into());
+
+    let (status, body) = call(&app, "GET", "/api/v1/tasks", None, None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["code"], "Unauthorized");
+
+    let (status, _) = call(&app, "GET", "/api/v1/tasks", Some("nope"), None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // health stays open so scripts can probe the port
+    let (status, _) = call(&app, "GET", "/health", None, None).await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn creates_task_through_shared_service() {
+    let pool = common::pool().await;
+    let app = http::router(SharedPool::new(pool.clone()), TOKEN.into());
+
+    let (status, body) = call(
+        &app,
+        "POST",
+        "/api/v1/tasks",
+        Some(TOKEN),
+        Some(json!({ "name": "Write plugin", "category": "Dev", "status": "todo", "project_id": common::WORK_GENERAL })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let id = body["id"].as_str().unwrap();
+    let task = tasks::get_task(&pool, id).await.unwrap();
+    assert_eq!(task.name, "Write plugin");
+
+    let (status, body) = call(&app, "PUT", &format!("/api/v1/tasks/{id}/status"), Some(TOKEN), Some(json!({ "status": "completed" }))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["status"], "completed");
+}
+
+#[tokio::test]
+async fn appends_idea_note() {
+    let pool = common::pool().await;
+    let app = http::router(SharedPool::new(pool.clone()), TOKEN.into());
+    let idea = ideas::add_idea(
+        &pool,
+        common::input(json!({ "project_id": common::PERSONAL_GENERAL, "title": "Focus plugin" })),
+    )
+    .await
+    .unwrap();
+
+    let (status, _) = call(&app, "POST", &format!("/api/v1/ideas/{idea}/notes"), Some(TOKEN), Some(json!({ "body_md": "from vim" }))).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let notes: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM idea_note WHERE idea_id = ?1")
+        .bind(&idea)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(notes, 1);
+
+    let (status, body) = call(&app, "POST", "/api/v1/ideas/missing/notes", Some(TOKEN), Some(json!({ "body_md": "x" }))).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["code"], "ForeignKey");
+}
+
+#[tokio::test]
+async fn maps_errors_to_status_codes() {
+    let app = http::router(SharedPool::new(common::pool().await), TOKEN.into());
+
+    let (status, body) = call(&app, "GET", "/api/v1/tasks/missing", Some(TOKEN), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["code"], "NotFound");
+
+    let (status, body) = call(&app, "POST", "/api/v1/tasks", Some(TOKEN), Some(json!({ "category": "Dev", "status": "todo" }))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "Validation");
+
+    // a bad query string gets the same JSON error shape
+    let (status, body) = call(&app, "GET", "/api/v1/tasks?status=someday", Some(TOKEN), None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "Validation");
+}
+
+#[test]
+fn token_is_created_once_and_rotates() {
+    let dir = tempfile::tempdir().unwrap();
+    let first = http::load_or_create_token(dir.path()).unwrap();
+    assert_eq!(first.len(), 64);
+    assert_eq!(http::load_or_create_token(dir.path()).unwrap(), first);
+
+    let rotated = http::rotate_token(dir.path()).unwrap();
+    assert_ne!(rotated, first);
+    assert_eq!(http::load_or_create_token(dir.path()).unwrap(), rotated);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let meta = std::fs::metadata(dir.path().join("api-token")).unwrap();
+        assert_eq!(meta.permissions().mode() & 0o777, 0o600);
+    }
+}
+
+#[tokio::test]
+async fn follows_a_swapped_pool() {
+    let shared = SharedPool::new(common::pool().await);
+    let app = http::router(shared.clone(), TOKEN.into());
+
+    // what restore_backup does once the new database is open
+    let restored = common::pool().await;
+    shared.set(restored.clone());
+
+    let (status, body) = call(
+        &app,
+        "POST",
+        "/api/v1/tasks",
+        Some(TOKEN),
+        Some(json!({ "name": "After restore", "category": "Dev", "status": "todo" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert!(tasks::get_task(&restored, body["id"].as_str().unwrap()).await.is_ok());
+}
@@ -0,0 +1,154 @@
+mod common;
+
+use deep_focus_lib::events::EntityKind;
+use deep_focus_lib::services::{docs, ideas, tasks, trash};
+use deep_focus_lib::ErrorCode;
+use serde_json::json;
+
+async fn seed_idea(pool: &sqlx::SqlitePool, title: &str) -> String {
+    ideas::add_idea(
+        pool,
+        common::input(json!({ "project_id": common::PERSONAL_GENERAL, "title": title })),
+    )
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn add_uses_defaults_and_lists_by_project() {
+    let pool = common::pool().await;
+    let id = seed_idea(&pool, "Tauri plugin for focus mode").await;
+
+    let idea = ideas::get_idea(&pool, &id).await.unwrap();
+    assert_eq!(idea.status, "inbox");
+    assert_eq!(idea.priority, 2);
+    assert_eq!(idea.effort_pts, 1);
+
+    assert_eq!(ideas::list_ideas(&pool, common::PERSONAL_GENERAL).await.unwrap().len(), 1);
+    assert!(ideas::list_ideas(&pool, common::WORK_GENERAL).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn add_validates_project_and_priority() {
+    let pool = common::pool().await;
+
+    let err = ideas::add_idea(
+        &pool,
+        common::input(json!({ "project_id": "prj_missing", "title": "x" })),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::ForeignKey);
+
+    let err = ideas::add_idea(
+        &pool,
+        common::input(json!({ "project_id": common::PERSONAL_GENERAL, "title": "x", "priority": 9 })),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Validation);
+}
+
+#[tokio::test]
+async fn update_keeps_unset_fields() {
+    let pool = common::pool().await;
+    let id = seed_idea(&pool, "Newsletter").await;
+
+    ideas::update_idea(&pool, common::input(json!({ "id": id, "status": "exploring", "priority": 0 })))
+        .await
+        .unwrap();
+
+    let idea = ideas::get_idea(&pool, &id).await.unwrap();
+    assert_eq!(idea.title, "Newsletter");
+    assert_eq!(idea.status, "exploring");
+    assert_eq!(idea.priority, 0);
+
+    let err = ideas::update_idea(&pool, common::input(json!({ "id": "ghost" })))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::NotFound);
+}
+
+#[tokio::test]
+async fn notes_links_and_doc_links_need_an_idea() {
+    let pool = common::pool().await;
+    let id = seed_idea(&pool, "Podcast").await;
+
+    ideas::add_idea_note(&pool, common::input(json!({ "idea_id": id, "body_md": "# first" })))
+        .await
+        .unwrap();
+    ideas::add_idea_link(
+        &pool,
+        common::input(json!({ "idea_id": id, "kind": "ref", "url": "https://example.com" })),
+    )
+    .await
+    .unwrap();
+
+    let doc_id = docs::add_doc(
+        &pool,
+        common::input(json!({ "project_id": common::PERSONAL_GENERAL, "title": "Outline", "body_md": "" })),
+    )
+    .await
+    .unwrap();
+    ideas::link_idea_doc(&pool, common::input(json!({ "idea_id": id, "doc_id": doc_id })))
+        .await
+        .unwrap();
+
+    let err = ideas::add_idea_note(&pool, common::input(json!({ "idea_id": "ghost", "body_md": "x" })))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::ForeignKey);
+}
+
+#[tokio::test]
+async fn search_matches_title_and_summary() {
+    let pool = common::pool().await;
+    seed_idea(&pool, "Rust desktop timer").await;
+    ideas::add_idea(
+        &pool,
+        common::input(json!({
+            "project_id": common::WORK_GENERAL,
+            "title": "Quarterly review",
+            "summary": "rust migration plan"
+        })),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(ideas::search_ideas(&pool, "rust", None).await.unwrap().len(), 2);
+    let scoped = ideas::search_ideas(&pool, "rust", Some(common::WORK_GENERAL)).await.unwrap();
+    assert_eq!(scoped.len(), 1);
+    assert_eq!(scoped[0].1, "Quarterly review");
+}
+
+#[tokio::test]
+async fn tasks_from_an_idea_keep_their_lineage() {
+    let pool = common::pool().await;
+    let idea = seed_idea(&pool, "Offline sync").await;
+
+    let mut made = Vec::new();
+    for _ in 0..2 {
+        let task = ideas::create_task_from_idea(&pool, common::input(json!({ "idea_id": idea, "category": "Dev" })))
+            .await
+            .unwrap();
+        made.push(task);
+    }
+
+    let origin = tasks::get_task(&pool, &made[0]).await.unwrap().origin_idea.unwrap();
+    assert_eq!((origin.id.as_str(), origin.title.as_str()), (idea.as_str(), "Offline sync"));
+
+    let detail = ideas::get_idea(&pool, &idea).await.unwrap();
+    assert_eq!(detail.tasks.iter().map(|t| &t.id).collect::<Vec<_>>(), made.iter().collect::<Vec<_>>());
+    assert!(!detail.ready_to_ship);
+
+    for task in &made {
+        tasks::set_task_status(&pool, task, common::input(json!("completed")), None).await.unwrap();
+    }
+    assert!(ideas::get_idea(&pool, &idea).await.unwrap().ready_to_ship);
+
+    // purging the idea drops the link but keeps the tasks
+    ideas::delete_idea(&pool, &idea).await.unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    trash::purge(&pool, dir.path(), EntityKind::Idea, &idea).await.unwrap();
+    assert!(tasks::get_task(&pool, &made[0]).await.unwrap().origin_idea.is_none());
+}
@@ -0,0 +1,130 @@
+mod common;
+
+use deep_focus_lib::services::timer::Timer;
+use deep_focus_lib::services::{docs, ideas, journal, tasks};
+use deep_focus_lib::ErrorCode;
+use serde_json::json;
+
+async fn seed_task(pool: &sqlx::SqlitePool) -> String {
+    tasks::add_task(
+        pool,
+        common::input(json!({ "name": "Draft", "category": "Dev", "description": "original", "status": "todo" })),
+    )
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn undo_and_redo_an_edit() {
+    let pool = common::pool().await;
+    let id = seed_task(&pool).await;
+
+    tasks::update_task(&pool, common::input(json!({ "id": id, "description": "oops" })))
+        .await
+        .unwrap();
+
+    let undone = journal::undo(&pool).await.unwrap().unwrap();
+    assert_eq!(undone.label, "Edit task");
+    assert_eq!(tasks::get_task(&pool, &id).await.unwrap().description.as_deref(), Some("original"));
+
+    let state = journal::undo_state(&pool).await.unwrap();
+    assert_eq!(state.undo.unwrap().label, "Add task");
+    assert_eq!(state.redo.unwrap().label, "Edit task");
+
+    journal::redo(&pool).await.unwrap().unwrap();
+    assert_eq!(tasks::get_task(&pool, &id).await.unwrap().description.as_deref(), Some("oops"));
+    assert!(journal::redo(&pool).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn undo_create_removes_row_and_new_op_drops_redo() {
+    let pool = common::pool().await;
+    let id = seed_task(&pool).await;
+
+    journal::undo(&pool).await.unwrap();
+    assert_eq!(tasks::get_task(&pool, &id).await.unwrap_err().code(), ErrorCode::NotFound);
+
+    seed_task(&pool).await;
+    assert!(journal::undo_state(&pool).await.unwrap().redo.is_none());
+}
+
+#[tokio::test]
+async fn undo_idea_conversion_removes_task_and_link() {
+    let pool = common::pool().await;
+    let idea = ideas::add_idea(
+        &pool,
+        common::input(json!({ "project_id": common::PERSONAL_GENERAL, "title": "Dark mode" })),
+    )
+    .await
+    .unwrap();
+    let task = ideas::create_task_from_idea(&pool, common::input(json!({ "idea_id": idea, "category": "UI" })))
+        .await
+        .unwrap();
+
+    journal::undo(&pool).await.unwrap();
+    assert_eq!(tasks::get_task(&pool, &task).await.unwrap_err().code(), ErrorCode::NotFound);
+    assert!(ideas::get_idea(&pool, &idea).await.unwrap().tasks.is_empty());
+
+    journal::undo(&pool).await.unwrap();
+    assert_eq!(ideas::get_idea(&pool, &idea).await.unwrap_err().code(), ErrorCode::NotFound);
+}
+
+#[tokio::test]
+async fn multi_row_ops_revert_together() {
+    let pool = common::pool().await;
+    let doc = docs::add_doc(
+        &pool,
+        common::input(json!({ "project_id": common::PERSONAL_GENERAL, "title": "Notes", "body_md": "v1" })),
+    )
+    .await
+    .unwrap();
+    docs::update_doc_body(&pool, common::input(json!({ "id": doc, "body_md": "v2" })))
+        .await
+        .unwrap();
+
+    let versions = || async {
+        sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM doc_version WHERE doc_id = ?1")
+            .bind(&doc)
+            .fetch_one(&pool)
+            .await
+            .unwrap()
+    };
+    assert_eq!(versions().await, 2);
+
+    journal::undo(&pool).await.unwrap();
+    assert_eq!(docs::get_doc(&pool, &doc).await.unwrap().body_md, "v1");
+    assert_eq!(versions().await, 1);
+}
+
+#[tokio::test]
+async fn refuses_to_clobber_outside_changes() {
+    let pool = common::pool().await;
+    let id = seed_task(&pool).await;
+    tasks::update_task(&pool, common::input(json!({ "id": id, "name": "Journaled" })))
+        .await
+        .unwrap();
+
+    // a write that bypassed the journal
+    sqlx::query("UPDATE tasks SET name = 'Sneaky' WHERE id = ?1")
+        .bind(&id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    assert_eq!(journal::undo(&pool).await.unwrap_err().code(), ErrorCode::Conflict);
+    assert_eq!(tasks::get_task(&pool, &id).await.unwrap().name, "Sneaky");
+}
+
+#[tokio::test]
+async fn refuses_to_undo_a_task_others_now_point_at() {
+    let pool = common::pool().await;
+    let id = seed_task(&pool).await;
+
+    // focus time isn't journaled, so undoing the add would cascade it away for good
+    Timer::default().start(&pool, &id).await.unwrap();
+
+    let err = journal::undo(&pool).await.unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Conflict);
+    assert!(err.to_string().contains("time_entry"), "{err}");
+    assert!(tasks::get_task(&pool, &id).await.is_ok());
+}
@@ -0,0 +1,29 @@
+mod common;
+
+use deep_focus_lib::services::{docs, ideas, metrics};
+use serde_json::json;
+
+#[tokio::test]
+async fn counts_are_per_project() {
+    let pool = common::pool().await;
+
+    for title in ["a", "b"] {
+        ideas::add_idea(
+            &pool,
+            common::input(json!({ "project_id": common::PERSONAL_GENERAL, "title": title })),
+        )
+        .await
+        .unwrap();
+    }
+    docs::add_doc(
+        &pool,
+        common::input(json!({ "project_id": common::WORK_GENERAL, "title": "spec", "body_md": "" })),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(metrics::count_ideas_by_project(&pool, common::PERSONAL_GENERAL).await.unwrap(), 2);
+    assert_eq!(metrics::count_ideas_by_project(&pool, common::WORK_GENERAL).await.unwrap(), 0);
+    assert_eq!(metrics::count_docs_by_project(&pool, common::WORK_GENERAL).await.unwrap(), 1);
+    assert_eq!(metrics::count_tasks_by_project(&pool, common::WORK_GENERAL).await.unwrap(), 0);
+}
@@ -0,0 +1,154 @@
+mod common;
+
+use deep_focus_lib::services::{ideas, projects, tasks};
+use deep_focus_lib::ErrorCode;
+use serde_json::json;
+
+#[tokio::test]
+async fn migrations_seed_default_workspaces() {
+    let pool = common::pool().await;
+
+    let ws = projects::list_workspaces(&pool).await.unwrap();
+    let ids: Vec<_> = ws.iter().map(|w| w.id.as_str()).collect();
+    assert_eq!(ids, ["ws_personal", "ws_work"]);
+
+    let personal = projects::list_projects(&pool, Some(common::PERSONAL_WS), false).await.unwrap();
+    assert_eq!(personal.len(), 1);
+    assert_eq!(personal[0].id, common::PERSONAL_GENERAL);
+    assert_eq!(projects::list_projects(&pool, None, false).await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn create_workspace_and_project() {
+    let pool = common::pool().await;
+
+    let ws = projects::create_workspace(&pool, common::input(json!({ "name": "Side gigs" })))
+        .await
+        .unwrap();
+    assert!(ws.starts_with("ws_"));
+
+    let prj = projects::create_project(
+        &pool,
+        common::input(json!({ "workspace_id": ws, "name": "Blog", "description": "posts" })),
+    )
+    .await
+    .unwrap();
+
+    let rows = projects::list_projects(&pool, Some(&ws), false).await.unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].id, prj);
+    assert_eq!(rows[0].status, "active");
+    assert_eq!(rows[0].workspace_name, "Side gigs");
+}
+
+#[tokio::test]
+async fn create_project_validates_input() {
+    let pool = common::pool().await;
+
+    let err = projects::create_project(
+        &pool,
+        common::input(json!({ "workspace_id": "ws_missing", "name": "Blog" })),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::ForeignKey);
+
+    let err = projects::create_project(
+        &pool,
+        common::input(json!({ "workspace_id": common::PERSONAL_WS, "name": "Blog", "status": "frozen" })),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Validation);
+
+    let err = projects::create_workspace(&pool, common::input(json!({ "name": "" })))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Validation);
+}
+
+#[tokio::test]
+async fn find_project_by_id_or_path() {
+    let pool = common::pool().await;
+
+    assert_eq!(projects::find_project(&pool, "Work/General").await.unwrap().id, common::WORK_GENERAL);
+    assert_eq!(projects::find_project(&pool, "personal/general").await.unwrap().id, common::PERSONAL_GENERAL);
+    assert_eq!(projects::find_project(&pool, common::WORK_GENERAL).await.unwrap().workspace_name, "Work");
+
+    let err = projects::find_project(&pool, "General").await.unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Validation);
+    let err = projects::find_project(&pool, "Work/Nope").await.unwrap_err();
+    assert_eq!(err.code(), ErrorCode::NotFound);
+}
+
+#[tokio::test]
+async fn rename_edit_and_move() {
+    let pool = common::pool().await;
+    projects::update_workspace(&pool, common::input(json!({ "id": common::PERSONAL_WS, "name": "Home" })))
+        .await
+        .unwrap();
+    projects::update_project(
+        &pool,
+        common::input(json!({ "id": common::PERSONAL_GENERAL, "description": "odds and ends", "status": "paused" })),
+    )
+    .await
+    .unwrap();
+
+    let p = projects::find_project(&pool, "Home/General").await.unwrap();
+    assert_eq!((p.description.as_deref(), p.status.as_str()), (Some("odds and ends"), "paused"));
+
+    let task = tasks::add_task(&pool, common::input(json!({ "name": "Chores", "category": "x", "status": "todo" })))
+        .await
+        .unwrap();
+    projects::move_project(&pool, common::input(json!({ "id": common::PERSONAL_GENERAL, "workspace_id": "ws_work" })))
+        .await
+        .unwrap();
+    assert_eq!(projects::list_projects(&pool, Some("ws_work"), false).await.unwrap().len(), 2);
+    assert_eq!(tasks::get_task(&pool, &task).await.unwrap().workspace_id.as_deref(), Some("ws_work"));
+
+    // a trashed project stays where it is
+    projects::delete_project(&pool, common::PERSONAL_GENERAL).await.unwrap();
+    let err = projects::move_project(&pool, common::input(json!({ "id": common::PERSONAL_GENERAL, "workspace_id": common::PERSONAL_WS })))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::NotFound);
+
+    let err = projects::update_project(&pool, common::input(json!({ "id": common::WORK_GENERAL, "status": "done" })))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Validation);
+}
+
+#[tokio::test]
+async fn archived_projects_are_hidden_and_read_only() {
+    let pool = common::pool().await;
+    let idea = ideas::add_idea(&pool, common::input(json!({ "project_id": common::WORK_GENERAL, "title": "Retro" })))
+        .await
+        .unwrap();
+
+    projects::archive_project(&pool, common::WORK_GENERAL).await.unwrap();
+    assert_eq!(projects::list_projects(&pool, None, false).await.unwrap().len(), 1);
+    assert_eq!(projects::list_projects(&pool, None, true).await.unwrap().len(), 2);
+
+    let err = ideas::update_idea(&pool, common::input(json!({ "id": idea, "title": "Retro 2" })))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Conflict);
+    let err = tasks::add_task(
+        &pool,
+        common::input(json!({ "name": "x", "category": "y", "status": "todo", "project_id": common::WORK_GENERAL })),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Conflict);
+    assert_eq!(ideas::delete_idea(&pool, &idea).await.unwrap_err().code(), ErrorCode::Conflict);
+    let err = projects::move_project(&pool, common::input(json!({ "id": common::WORK_GENERAL, "workspace_id": common::PERSONAL_WS })))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Conflict);
+
+    projects::unarchive_project(&pool, common::WORK_GENERAL).await.unwrap();
+    ideas::update_idea(&pool, common::input(json!({ "id": idea, "title": "Retro 2" })))
+        .await
+        .unwrap();
+}
@@ -0,0 +1,186 @@
+mod common;
+
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use deep_focus_lib::services::journal;
+use deep_focus_lib::services::recurrence::{self, SeriesScope};
+use deep_focus_lib::services::rrule::RRule;
+use deep_focus_lib::services::tasks::{self, TaskFilter, TaskRow, TaskStatus};
+use deep_focus_lib::services::stages;
+use deep_focus_lib::ErrorCode;
+use serde_json::json;
+
+fn at(s: &str) -> DateTime<Utc> {
+    s.parse().unwrap()
+}
+
+/// A weekly Monday 09:00 Berlin task, one hour long.
+async fn weekly(pool: &sqlx::SqlitePool, rrule: &str) -> String {
+    tasks::add_task(
+        pool,
+        common::input(json!({
+            "name": "Weekly review", "category": "Admin", "status": "todo",
+            "project_id": common::WORK_GENERAL,
+            "start_at": "2025-03-24T08:00:00Z", "end_est_at": "2025-03-24T09:00:00Z",
+            "recurrence": { "rrule": rrule, "timezone": "Europe/Berlin" },
+        })),
+    )
+    .await
+    .unwrap()
+}
+
+/// Occurrences of a series, in order.
+async fn series(pool: &sqlx::SqlitePool, series_id: &str) -> Vec<(i64, TaskRow)> {
+    let filter = TaskFilter { project_id: Some(common::WORK_GENERAL.into()), ..Default::default() };
+    let mut rows = Vec::new();
+    for t in tasks::list_tasks(pool, &filter).await.unwrap() {
+        if t.series_id.as_deref() == Some(series_id) {
+            let n = tasks::get_task(pool, &t.id).await.unwrap().recurrence.unwrap().occurrence;
+            rows.push((n, t));
+        }
+    }
+    rows.sort_by_key(|(n, _)| *n);
+    rows
+}
+
+#[test]
+fn rules_parse_and_expand_in_local_time() {
+    let berlin: Tz = "Europe/Berlin".parse().unwrap();
+
+    // Mondays 09:00 stay 09:00 across the switch to summer time on 2025-03-30
+    let rule: RRule = "RRULE:FREQ=WEEKLY;BYDAY=MO".parse().unwrap();
+    let dates: Vec<_> = rule.occurrences(berlin, at("2025-03-24T08:00:00Z")).take(3).collect();
+    assert_eq!(dates, [at("2025-03-24T08:00:00Z"), at("2025-03-31T07:00:00Z"), at("2025-04-07T07:00:00Z")]);
+
+    // last Friday of the month; COUNT includes the first
+    let rule: RRule = "freq=monthly;byday=-1FR;count=4".parse().unwrap();
+    assert_eq!(rule.to_string(), "FREQ=MONTHLY;COUNT=4;BYDAY=-1FR");
+    let dates: Vec<_> = rule.occurrences(Tz::UTC, at("2025-01-31T10:00:00Z")).collect();
+    assert_eq!(
+        dates,
+        [at("2025-01-31T10:00:00Z"), at("2025-02-28T10:00:00Z"), at("2025-03-28T10:00:00Z"), at("2025-04-25T10:00:00Z")]
+    );
+
+    // the 31st skips short months; UNTIL is inclusive
+    let rule: RRule = "FREQ=MONTHLY;UNTIL=20250531".parse().unwrap();
+    let dates: Vec<_> = rule.occurrences(Tz::UTC, at("2025-01-31T10:00:00Z")).collect();
+    assert_eq!(dates, [at("2025-01-31T10:00:00Z"), at("2025-03-31T10:00:00Z"), at("2025-05-31T10:00:00Z")]);
+
+    // a time inside the spring-forward gap lands after it
+    let rule: RRule = "FREQ=DAILY;INTERVAL=2".parse().unwrap();
+    assert_eq!(rule.nth(berlin, at("2025-03-28T01:30:00Z"), 2), Some(at("2025-03-30T01:30:00Z")));
+
+    // workdays, then the last one of each month
+    let rule: RRule = "FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1".parse().unwrap();
+    assert_eq!(rule.nth(Tz::UTC, at("2025-05-30T09:00:00Z"), 2), Some(at("2025-06-30T09:00:00Z")));
+
+    // leap days come round every four (sometimes eight) years; impossible dates never
+    let rule: RRule = "FREQ=DAILY;BYMONTH=2;BYMONTHDAY=29".parse().unwrap();
+    let dates: Vec<_> = rule.occurrences(Tz::UTC, at("2025-03-01T09:00:00Z")).skip(1).take(2).collect();
+    assert_eq!(dates, [at("2028-02-29T09:00:00Z"), at("2032-02-29T09:00:00Z")]);
+    let rule: RRule = "FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=29".parse().unwrap();
+    assert_eq!(rule.nth(Tz::UTC, at("2096-02-29T09:00:00Z"), 2), Some(at("2104-02-29T09:00:00Z")));
+    let rule: RRule = "FREQ=DAILY;BYMONTH=2;BYMONTHDAY=30".parse().unwrap();
+    assert_eq!(rule.nth(Tz::UTC, at("2025-03-01T09:00:00Z"), 2), None);
+
+    for bad in ["FREQ=HOURLY", "BYDAY=MO", "FREQ=WEEKLY;BYDAY=1MO", "FREQ=DAILY;COUNT=2;UNTIL=20250101", "FREQ=DAILY;BYHOUR=9"] {
+        let err = bad.parse::<RRule>().unwrap_err();
+        assert_eq!(err.code(), ErrorCode::Validation, "{bad}");
+    }
+}
+
+#[tokio::test]
+async fn finishing_an_occurrence_schedules_the_next() {
+    let pool = common::pool().await;
+    let id = weekly(&pool, "FREQ=WEEKLY;BYDAY=MO;COUNT=3").await;
+    stages::add_stage(&pool, common::input(json!({ "task_id": id, "name": "Inbox zero" }))).await.unwrap();
+
+    let r = tasks::get_task(&pool, &id).await.unwrap().recurrence.unwrap();
+    assert_eq!((r.occurrence, r.next_at), (1, Some(at("2025-03-31T07:00:00Z"))));
+
+    tasks::set_task_status(&pool, &id, TaskStatus::Completed, None).await.unwrap();
+    let occ = series(&pool, &r.series_id).await;
+    assert_eq!(occ.len(), 2);
+    let next = &occ[1].1;
+    assert_eq!((next.status.as_str(), next.name.as_str()), ("todo", "Weekly review"));
+    // same local time after the DST switch, same one-hour span
+    assert_eq!((next.start_at, next.end_est_at), (Some(at("2025-03-31T07:00:00Z")), Some(at("2025-03-31T08:00:00Z"))));
+    assert_eq!(stages::list_stages(&pool, &next.id).await.unwrap()[0].name, "Inbox zero");
+
+    // one undo reopens it and takes the scheduled occurrence back out
+    assert_eq!(journal::undo(&pool).await.unwrap().unwrap().label, "Change task status");
+    assert_eq!(series(&pool, &r.series_id).await.len(), 1);
+    journal::redo(&pool).await.unwrap();
+    assert_eq!(series(&pool, &r.series_id).await[1].1.id, next.id);
+
+    // dropping one skips it; the series ends after COUNT
+    tasks::set_task_status(&pool, &next.id, TaskStatus::Dropped, Some("away".into())).await.unwrap();
+    let third = series(&pool, &r.series_id).await[2].1.id.clone();
+    assert!(tasks::get_task(&pool, &third).await.unwrap().recurrence.unwrap().next_at.is_none());
+    tasks::set_task_status(&pool, &third, TaskStatus::Completed, None).await.unwrap();
+    assert_eq!(series(&pool, &r.series_id).await.len(), 3);
+
+    // a repeating task needs a date to repeat from
+    let err = tasks::add_task(
+        &pool,
+        common::input(json!({
+            "name": "Undated", "category": "x", "status": "todo",
+            "recurrence": { "rrule": "FREQ=DAILY", "timezone": "UTC" },
+        })),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Validation);
+}
+
+#[tokio::test]
+async fn edits_apply_to_this_or_following_occurrences() {
+    let pool = common::pool().await;
+    let first = weekly(&pool, "FREQ=WEEKLY;BYDAY=MO").await;
+    let series_id = tasks::get_task(&pool, &first).await.unwrap().recurrence.unwrap().series_id;
+
+    // "this one": the next occurrence still starts from the series
+    recurrence::update_series_task(&pool, common::input(json!({ "id": first, "name": "Review (short)" })), SeriesScope::This)
+        .await
+        .unwrap();
+    tasks::set_task_status(&pool, &first, TaskStatus::Completed, None).await.unwrap();
+    let second = series(&pool, &series_id).await.remove(1).1;
+    assert_eq!(second.name, "Weekly review");
+
+    // "this and following": renames the series, and moving the time splits it
+    recurrence::update_series_task(
+        &pool,
+        common::input(json!({ "id": second.id, "name": "Planning", "start_at": "2025-03-31T12:00:00Z", "end_est_at": "2025-03-31T13:00:00Z" })),
+        SeriesScope::Following,
+    )
+    .await
+    .unwrap();
+    let r = tasks::get_task(&pool, &second.id).await.unwrap().recurrence.unwrap();
+    assert_ne!(r.series_id, series_id);
+    assert_eq!((r.occurrence, r.next_at), (1, Some(at("2025-04-07T12:00:00Z"))));
+    // the old series ends before the split
+    assert!(tasks::get_task(&pool, &first).await.unwrap().recurrence.unwrap().next_at.is_none());
+
+    tasks::set_task_status(&pool, &second.id, TaskStatus::Completed, None).await.unwrap();
+    let third = &series(&pool, &r.series_id).await[1].1;
+    assert_eq!((third.name.as_str(), third.start_at), ("Planning", Some(at("2025-04-07T12:00:00Z"))));
+
+    // finishing it that way is one op: the edit, the series and the next occurrence
+    recurrence::update_series_task(
+        &pool,
+        common::input(json!({ "id": third.id, "name": "Plan", "status": "completed" })),
+        SeriesScope::Following,
+    )
+    .await
+    .unwrap();
+    let occ = series(&pool, &r.series_id).await;
+    assert_eq!((occ.len(), occ[2].1.name.as_str()), (3, "Plan"));
+    assert_eq!(journal::undo(&pool).await.unwrap().unwrap().label, "Edit following occurrences");
+    let occ = series(&pool, &r.series_id).await;
+    assert_eq!((occ.len(), occ[1].1.name.as_str(), occ[1].1.status.as_str()), (2, "Planning", "todo"));
+
+    // stopping: no further occurrences
+    recurrence::set_task_recurrence(&pool, &third.id, None).await.unwrap();
+    tasks::set_task_status(&pool, &third.id, TaskStatus::Completed, None).await.unwrap();
+    assert_eq!(series(&pool, &r.series_id).await.len(), 2);
+}
@@ -0,0 +1,108 @@
+mod common;
+
+use chrono::{DateTime, Duration, Utc};
+use deep_focus_lib::events::EntityKind;
+use deep_focus_lib::services::reminders::{self, ReminderKind, ReminderSettings};
+use deep_focus_lib::services::tasks::{self, TaskStatus};
+use deep_focus_lib::services::trash;
+use deep_focus_lib::ErrorCode;
+use serde_json::json;
+
+fn at(s: &str) -> DateTime<Utc> {
+    s.parse().unwrap()
+}
+
+async fn due(pool: &sqlx::SqlitePool, name: &str, end_est_at: &str) -> String {
+    tasks::add_task(
+        pool,
+        common::input(json!({ "name": name, "category": "Admin", "status": "todo", "end_est_at": end_est_at })),
+    )
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn reminders_fire_once_at_their_offsets() {
+    let pool = common::pool().await;
+    let id = due(&pool, "Invoice", "2025-09-12T17:00:00Z").await;
+    let done = due(&pool, "Filed", "2025-09-12T17:00:00Z").await;
+    tasks::set_task_status(&pool, &done, TaskStatus::Completed, None).await.unwrap();
+
+    assert!(reminders::run_due(&pool, at("2025-09-11T16:00:00Z")).await.unwrap().is_empty());
+
+    // default offsets: a day and an hour before
+    let fired = reminders::run_due(&pool, at("2025-09-11T17:00:30Z")).await.unwrap();
+    assert_eq!(fired.len(), 1);
+    assert_eq!((fired[0].task_id.as_str(), fired[0].kind, fired[0].offset_minutes), (id.as_str(), ReminderKind::DueSoon, 1440));
+    assert!(!fired[0].missed);
+    assert!(reminders::run_due(&pool, at("2025-09-11T17:01:00Z")).await.unwrap().is_empty());
+
+    // the hour-before one replaces the day-before one
+    let fired = reminders::run_due(&pool, at("2025-09-12T16:00:00Z")).await.unwrap();
+    assert_eq!(fired[0].offset_minutes, 60);
+    let shown = reminders::list_notifications(&pool, at("2025-09-12T16:00:00Z")).await.unwrap();
+    assert_eq!(shown.iter().map(|n| n.offset_minutes).collect::<Vec<_>>(), [60]);
+
+    let fired = reminders::run_due(&pool, at("2025-09-12T17:00:00Z")).await.unwrap();
+    assert_eq!(fired[0].kind, ReminderKind::Overdue);
+
+    // a new due date starts over; finishing the task clears the center
+    tasks::update_task(&pool, common::input(json!({ "id": id, "end_est_at": "2025-09-13T17:00:00Z" }))).await.unwrap();
+    assert!(reminders::list_notifications(&pool, at("2025-09-12T17:01:00Z")).await.unwrap().is_empty());
+    assert_eq!(reminders::run_due(&pool, at("2025-09-13T16:30:00Z")).await.unwrap()[0].offset_minutes, 60);
+    tasks::set_task_status(&pool, &id, TaskStatus::Completed, None).await.unwrap();
+    assert!(reminders::list_notifications(&pool, at("2025-09-13T16:31:00Z")).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn catch_up_shows_only_the_latest_missed_reminder() {
+    let pool = common::pool().await;
+    let id = due(&pool, "Standup notes", "2025-09-12T09:00:00Z").await;
+
+    // app was closed through both offsets and the due time
+    let fired = reminders::run_due(&pool, at("2025-09-12T11:00:00Z")).await.unwrap();
+    assert_eq!(fired.len(), 1);
+    assert_eq!((fired[0].task_id.as_str(), fired[0].kind), (id.as_str(), ReminderKind::Overdue));
+    assert!(fired[0].missed);
+    assert_eq!(reminders::list_notifications(&pool, at("2025-09-12T11:00:00Z")).await.unwrap().len(), 1);
+    // all three are kept, and a permanent delete says so
+    let preview = trash::preview_delete(&pool, std::path::Path::new("/nonexistent"), EntityKind::Task, &id).await.unwrap();
+    assert_eq!(preview.rows["reminder"], 3);
+    assert!(reminders::run_due(&pool, at("2025-09-12T11:01:00Z")).await.unwrap().is_empty());
+
+    // turned off: nothing fires
+    let off = ReminderSettings { enabled: false, ..Default::default() };
+    reminders::set_settings(&pool, off).await.unwrap();
+    due(&pool, "Later", "2025-09-12T12:00:00Z").await;
+    assert!(reminders::run_due(&pool, at("2025-09-12T13:00:00Z")).await.unwrap().is_empty());
+
+    let err = reminders::set_settings(&pool, ReminderSettings { offsets_minutes: vec![0], ..Default::default() })
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Validation);
+}
+
+#[tokio::test]
+async fn snooze_and_dismiss_are_kept() {
+    let pool = common::pool().await;
+    due(&pool, "Review", &(Utc::now() + Duration::minutes(30)).to_rfc3339()).await;
+    let now = Utc::now();
+    let n = reminders::run_due(&pool, now).await.unwrap().remove(0);
+
+    let until = now + Duration::minutes(10);
+    reminders::snooze_notification(&pool, &n.id, until).await.unwrap();
+    assert!(reminders::list_notifications(&pool, now).await.unwrap().is_empty());
+    assert!(reminders::run_due(&pool, now + Duration::minutes(5)).await.unwrap().is_empty());
+
+    // back once the snooze is over, then gone for good
+    let woke = reminders::run_due(&pool, until).await.unwrap();
+    assert_eq!(woke[0].id, n.id);
+    assert_eq!(reminders::list_notifications(&pool, until).await.unwrap().len(), 1);
+    reminders::dismiss_notification(&pool, &n.id).await.unwrap();
+    assert!(reminders::list_notifications(&pool, until).await.unwrap().is_empty());
+
+    let err = reminders::dismiss_notification(&pool, &n.id).await.unwrap_err();
+    assert_eq!(err.code(), ErrorCode::NotFound);
+    let err = reminders::snooze_notification(&pool, "nope", until).await.unwrap_err();
+    assert_eq!(err.code(), ErrorCode::NotFound);
+}
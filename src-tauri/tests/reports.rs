@@ -0,0 +1,155 @@
+mod common;
+
+use deep_focus_lib::services::report_export::{self, ReportFormat};
+use deep_focus_lib::services::reports::{self, ReportInput};
+use deep_focus_lib::services::tasks::{self, TaskStatus};
+use deep_focus_lib::services::{docs, time};
+use deep_focus_lib::ErrorCode;
+use serde_json::json;
+use sqlx::SqlitePool;
+
+const TZ: &str = "America/New_York"; // UTC-4 in September
+
+fn range(from: &str, to: &str, timezone: &str) -> ReportInput {
+    common::input(json!({ "from": from, "to": to, "timezone": timezone }))
+}
+
+async fn completed_at(pool: &SqlitePool, name: &str, at: &str) -> String {
+    let id = tasks::add_task(pool, common::input(json!({ "name": name, "category": "Dev", "status": "todo" })))
+        .await
+        .unwrap();
+    tasks::set_task_status(pool, &id, TaskStatus::Completed, None).await.unwrap();
+    sqlx::query("UPDATE status_reasons SET created_at = ?1 WHERE task_id = ?2")
+        .bind(at.parse::<chrono::DateTime<chrono::Utc>>().unwrap())
+        .bind(&id)
+        .execute(pool)
+        .await
+        .unwrap();
+    id
+}
+
+async fn write(pool: &SqlitePool, doc: &str, body: &str, at: &str) {
+    docs::update_doc_body(pool, common::input(json!({ "id": doc, "body_md": body }))).await.unwrap();
+    sqlx::query("UPDATE doc_version SET created_at = ?1 WHERE doc_id = ?2 AND body_md = ?3")
+        .bind(at.parse::<chrono::DateTime<chrono::Utc>>().unwrap())
+        .bind(doc)
+        .bind(body)
+        .execute(pool)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn report_counts_local_days() {
+    let pool = common::pool().await;
+
+    // 22:00 on Sep 1 in New York; the other lands on Sep 3 there
+    let done = completed_at(&pool, "Ship it", "2025-09-02T02:00:00Z").await;
+    completed_at(&pool, "Later", "2025-09-03T05:00:00Z").await;
+
+    // a focus session across local midnight, and manual time that isn't focus
+    let task = tasks::list_tasks(&pool, &Default::default()).await.unwrap()[0].id.clone();
+    let entry = time::log_time(
+        &pool,
+        common::input(json!({ "task_id": task, "started_at": "2025-09-02T03:30:00Z", "ended_at": "2025-09-02T04:30:00Z" })),
+    )
+    .await
+    .unwrap();
+    sqlx::query("UPDATE time_entry SET source = 'timer' WHERE id = ?1").bind(&entry).execute(&pool).await.unwrap();
+    time::log_time(
+        &pool,
+        common::input(json!({ "task_id": task, "started_at": "2025-09-02T14:00:00Z", "ended_at": "2025-09-02T15:00:00Z" })),
+    )
+    .await
+    .unwrap();
+
+    // words added per save; the version before the range is the baseline
+    let doc = docs::add_doc(
+        &pool,
+        common::input(json!({ "project_id": common::PERSONAL_GENERAL, "title": "Notes", "body_md": "one two three" })),
+    )
+    .await
+    .unwrap();
+    sqlx::query("UPDATE doc_version SET created_at = '2025-08-30T12:00:00+00:00' WHERE doc_id = ?1")
+        .bind(&doc)
+        .execute(&pool)
+        .await
+        .unwrap();
+    write(&pool, &doc, "one two three four five", "2025-09-02T15:00:00Z").await;
+    write(&pool, &doc, "one two", "2025-09-02T16:00:00Z").await;
+    write(&pool, &doc, "a b c d e f g", "2025-09-02T17:00:00Z").await;
+
+    let r = reports::generate_report(&pool, &range("2025-09-01", "2025-09-02", TZ)).await.unwrap();
+    assert_eq!((r.totals.tasks_completed, r.totals.focus_minutes, r.totals.focus_sessions, r.totals.doc_words), (1, 60, 1, 7));
+    assert_eq!(r.completed[0].id, done);
+    let days: Vec<_> = r.days.iter().map(|d| (d.date.to_string(), d.tasks_completed, d.focus_minutes, d.doc_words)).collect();
+    assert_eq!(days, [("2025-09-01".into(), 1, 30, 0), ("2025-09-02".into(), 0, 30, 7)]);
+    assert_eq!((r.docs.len(), r.docs[0].words), (1, 7));
+
+    // the same instants in UTC fall on other days
+    let utc = reports::generate_report(&pool, &range("2025-09-02", "2025-09-02", "UTC")).await.unwrap();
+    assert_eq!((utc.totals.tasks_completed, utc.totals.focus_minutes), (1, 60));
+
+    // a trashed task's focus time leaves with it
+    tasks::delete_task(&pool, &task).await.unwrap();
+    let r = reports::generate_report(&pool, &range("2025-09-02", "2025-09-02", "UTC")).await.unwrap();
+    assert_eq!((r.totals.focus_minutes, r.focus.len()), (0, 0));
+}
+
+#[tokio::test]
+async fn reports_are_saved_for_later() {
+    let pool = common::pool().await;
+    completed_at(&pool, "Ship it", "2025-09-02T12:00:00Z").await;
+
+    let saved = reports::save_report(&pool, &range("2025-09-01", "2025-09-07", TZ)).await.unwrap();
+    let id = saved.id.clone().unwrap();
+
+    // later changes don't touch the saved copy
+    completed_at(&pool, "Another", "2025-09-03T12:00:00Z").await;
+    let again = reports::get_report(&pool, &id).await.unwrap();
+    assert_eq!(again.totals, saved.totals);
+    assert_eq!(again.days.len(), 7);
+
+    let list = reports::list_reports(&pool).await.unwrap();
+    assert_eq!((list.len(), list[0].id.as_str(), list[0].totals.tasks_completed), (1, id.as_str(), 1));
+
+    reports::delete_report(&pool, &id).await.unwrap();
+    assert_eq!(reports::get_report(&pool, &id).await.unwrap_err().code(), ErrorCode::NotFound);
+
+    for bad in [range("2025-09-01", "2025-09-07", "Mars/Olympus"), range("2025-09-07", "2025-09-01", TZ)] {
+        assert_eq!(reports::generate_report(&pool, &bad).await.unwrap_err().code(), ErrorCode::Validation);
+    }
+}
+
+#[tokio::test]
+async fn reports_export_as_markdown_csv_and_html() {
+    let pool = common::pool().await;
+    completed_at(&pool, "Fix | pipes, \"quotes\"", "2025-09-02T12:00:00Z").await;
+    completed_at(&pool, "=SUM(A1:A9)", "2025-09-02T13:00:00Z").await;
+    let report = reports::generate_report(&pool, &range("2025-09-01", "2025-09-02", TZ)).await.unwrap();
+
+    let md = report_export::render(&report, ReportFormat::Markdown);
+    assert!(md.starts_with("# Report 2025-09-01 – 2025-09-02\n"));
+    assert!(md.contains("| Mon 2025-09-01 | 0 | 0m | 0 |"));
+    assert!(md.contains("- Fix \\| pipes, \"quotes\" (Dev) — Tue 2025-09-02 08:00"));
+
+    let csv = report_export::render(&report, ReportFormat::Csv);
+    let tables: Vec<&str> = csv.split("\r\n\r\n").collect();
+    assert_eq!(tables.len(), 4);
+    assert!(tables[0].starts_with("day,tasks_completed,focus_minutes,doc_words\r\n2025-09-01,0,0,0\r\n"));
+    assert!(tables[1].contains(",\"Fix | pipes, \"\"quotes\"\"\",Dev,"));
+    assert!(tables[1].contains(",'=SUM(A1:A9),Dev,"));
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("week.html");
+    report_export::export_report(&report, ReportFormat::Html, &path).unwrap();
+    let html = std::fs::read_to_string(&path).unwrap();
+    assert!(html.starts_with("<!DOCTYPE html>") && html.contains("<style>"));
+    assert!(html.contains("<table>") && html.contains("<li>Fix | pipes"));
+    assert!(!html.contains("<link") && !html.contains("<script"));
+
+    let doc = report_export::report_to_doc(&pool, &report, common::PERSONAL_GENERAL, None).await.unwrap();
+    let doc = docs::get_doc(&pool, &doc).await.unwrap();
+    assert_eq!((doc.title.as_str(), doc.body_md.as_str()), ("Report 2025-09-01 – 2025-09-02", md.as_str()));
+    assert!(doc.body_html.contains("<h2>Summary</h2>"));
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use deep_focus_lib::services::{stages, tasks};
+use deep_focus_lib::ErrorCode;
+use serde_json::json;
+
+async fn task_with_stages(pool: &sqlx::SqlitePool, names: &[&str]) -> (String, Vec<String>) {
+    let task = tasks::add_task(pool, common::input(json!({ "name": "Launch", "category": "Work", "status": "todo" })))
+        .await
+        .unwrap();
+    let mut ids = Vec::new();
+    for name in names {
+        ids.push(
+            stages::add_stage(pool, common::input(json!({ "task_id": task, "name": name })))
+                .await
+                .unwrap(),
+        );
+    }
+    (task, ids)
+}
+
+#[tokio::test]
+async fn advance_walks_stages_in_order() {
+    let pool = common::pool().await;
+    let (task, _) = task_with_stages(&pool, &["Draft", "Review", "Publish", "Promote"]).await;
+
+    let p = stages::advance_stage(&pool, &task).await.unwrap();
+    assert_eq!((p.current_stage.as_deref(), p.percent), (Some("Draft"), 25));
+    let p = stages::advance_stage(&pool, &task).await.unwrap();
+    assert_eq!((p.current_stage.as_deref(), p.reached, p.percent), (Some("Review"), 2, 50));
+
+    let t = tasks::get_task(&pool, &task).await.unwrap();
+    assert_eq!((t.status.as_str(), t.current_stage.as_deref()), ("stage-complete", Some("Review")));
+
+    stages::advance_stage(&pool, &task).await.unwrap();
+    assert_eq!(stages::advance_stage(&pool, &task).await.unwrap().percent, 100);
+    let err = stages::advance_stage(&pool, &task).await.unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Conflict);
+}
+
+#[tokio::test]
+async fn reorder_rename_and_remove_keep_current_stage_valid() {
+    let pool = common::pool().await;
+    let (task, ids) = task_with_stages(&pool, &["A", "B", "C"]).await;
+
+    let err = stages::add_stage(&pool, common::input(json!({ "task_id": task, "name": "B" })))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Conflict);
+    let err = stages::reorder_stages(&pool, &task, &ids[..2]).await.unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Validation);
+
+    stages::reorder_stages(&pool, &task, &[ids[2].clone(), ids[0].clone(), ids[1].clone()])
+        .await
+        .unwrap();
+    let names: Vec<_> = stages::list_stages(&pool, &task).await.unwrap().into_iter().map(|s| s.name).collect();
+    assert_eq!(names, ["C", "A", "B"]);
+
+    stages::advance_stage(&pool, &task).await.unwrap();
+    stages::advance_stage(&pool, &task).await.unwrap(); // current: A
+    stages::update_stage(&pool, common::input(json!({ "id": ids[0], "name": "Alpha" })))
+        .await
+        .unwrap();
+    assert_eq!(tasks::get_task(&pool, &task).await.unwrap().current_stage.as_deref(), Some("Alpha"));
+
+    stages::remove_stage(&pool, &ids[0]).await.unwrap();
+    let p = stages::stage_progress(&pool, &task).await.unwrap();
+    assert_eq!((p.current_stage.as_deref(), p.reached, p.total), (Some("C"), 1, 2));
+    let ords: Vec<_> = stages::list_stages(&pool, &task).await.unwrap().into_iter().map(|s| s.ord).collect();
+    assert_eq!(ords, [0, 1]);
+
+    let err = tasks::update_task(&pool, common::input(json!({ "id": task, "current_stage": "Nope" })))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Validation);
+}
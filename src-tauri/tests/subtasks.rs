@@ -0,0 +1,131 @@
+mod common;
+
+use deep_focus_lib::events::EntityKind;
+use deep_focus_lib::services::{journal, stages, subtasks, tasks, trash};
+use deep_focus_lib::services::tasks::TaskStatus;
+use deep_focus_lib::ErrorCode;
+use serde_json::json;
+
+/// A top-level task in Work/General, or a subtask of `parent`.
+async fn add(pool: &sqlx::SqlitePool, name: &str, parent: Option<&str>) -> String {
+    let mut input = json!({ "name": name, "category": "Dev", "status": "todo" });
+    match parent {
+        Some(p) => input["parent_id"] = json!(p),
+        None => input["project_id"] = json!(common::WORK_GENERAL),
+    }
+    tasks::add_task(pool, common::input(input)).await.unwrap()
+}
+
+#[tokio::test]
+async fn get_task_returns_the_ordered_subtree_with_rollup() {
+    let pool = common::pool().await;
+    let root = add(&pool, "Release", None).await;
+    let docs = add(&pool, "Docs", Some(&root)).await;
+    let code = add(&pool, "Code", Some(&root)).await;
+    let api = add(&pool, "API", Some(&code)).await;
+    let ui = add(&pool, "UI", Some(&code)).await;
+
+    let t = tasks::get_task(&pool, &api).await.unwrap();
+    assert_eq!((t.parent_id.as_deref(), t.project_id.as_deref()), (Some(code.as_str()), Some(common::WORK_GENERAL)));
+
+    tasks::set_task_status(&pool, &api, TaskStatus::Completed, None).await.unwrap();
+    // a leaf with stages counts its stage progress
+    stages::add_stage(&pool, common::input(json!({ "task_id": ui, "name": "Mock" }))).await.unwrap();
+    stages::add_stage(&pool, common::input(json!({ "task_id": ui, "name": "Build" }))).await.unwrap();
+    stages::advance_stage(&pool, &ui).await.unwrap();
+
+    let t = tasks::get_task(&pool, &root).await.unwrap();
+    let names: Vec<_> = t.subtasks.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, ["Docs", "Code"]);
+    assert_eq!(t.subtasks[1].subtasks.len(), 2);
+    assert_eq!(t.subtasks[1].progress, 75); // (100 + 50) / 2
+    assert_eq!(t.progress, 37); // (0 + 75) / 2
+
+    // dropped children don't count
+    tasks::set_task_status(&pool, &docs, TaskStatus::Dropped, Some("folded into code".into())).await.unwrap();
+    assert_eq!(tasks::get_task(&pool, &root).await.unwrap().progress, 75);
+
+    subtasks::reorder_subtasks(&pool, &root, &[code.clone(), docs.clone()]).await.unwrap();
+    assert_eq!(tasks::get_task(&pool, &root).await.unwrap().subtasks[0].id, code);
+}
+
+#[tokio::test]
+async fn nesting_rejects_cycles_and_foreign_projects() {
+    let pool = common::pool().await;
+    let root = add(&pool, "Root", None).await;
+    let child = add(&pool, "Child", Some(&root)).await;
+    let other = tasks::add_task(&pool, common::input(json!({ "name": "Home", "category": "x", "status": "todo" })))
+        .await
+        .unwrap();
+
+    let err = subtasks::set_task_parent(&pool, common::input(json!({ "id": root, "parent_id": child })))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Validation);
+    let err = subtasks::set_task_parent(&pool, common::input(json!({ "id": other, "parent_id": root })))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Validation);
+    let err = tasks::move_task(&pool, common::input(json!({ "id": child, "project_id": common::PERSONAL_GENERAL })))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Validation);
+
+    // moving the root takes the subtree along
+    tasks::move_task(&pool, common::input(json!({ "id": root, "project_id": common::PERSONAL_GENERAL })))
+        .await
+        .unwrap();
+    assert_eq!(tasks::get_task(&pool, &child).await.unwrap().project_id.as_deref(), Some(common::PERSONAL_GENERAL));
+    subtasks::set_task_parent(&pool, common::input(json!({ "id": other, "parent_id": child })))
+        .await
+        .unwrap();
+    assert_eq!(tasks::get_task(&pool, &root).await.unwrap().subtasks[0].subtasks[0].id, other);
+}
+
+#[tokio::test]
+async fn completing_all_children_can_complete_the_parent() {
+    let pool = common::pool().await;
+    let root = add(&pool, "Trip", None).await;
+    let a = add(&pool, "Book", Some(&root)).await;
+    let b = add(&pool, "Pack", Some(&root)).await;
+
+    tasks::set_task_status(&pool, &a, TaskStatus::Completed, None).await.unwrap();
+    tasks::set_task_status(&pool, &b, TaskStatus::Completed, None).await.unwrap();
+    assert_eq!(tasks::get_task(&pool, &root).await.unwrap().status, "todo"); // off by default
+
+    let mut rules = tasks::get_status_rules(&pool).await.unwrap();
+    rules.complete_parent = true;
+    tasks::set_status_rules(&pool, &rules).await.unwrap();
+    tasks::set_task_status(&pool, &b, TaskStatus::InProgress, Some("forgot socks".into())).await.unwrap();
+    tasks::set_task_status(&pool, &b, TaskStatus::Completed, None).await.unwrap();
+
+    let t = tasks::get_task(&pool, &root).await.unwrap();
+    assert_eq!((t.status.as_str(), t.progress), ("completed", 100));
+    assert_eq!(tasks::list_status_reasons(&pool, &root).await.unwrap()[0].note, "All subtasks done");
+
+    // one undo reopens both the subtask and the parent
+    assert_eq!(journal::undo(&pool).await.unwrap().unwrap().label, "Change task status");
+    assert_eq!(tasks::get_task(&pool, &root).await.unwrap().status, "todo");
+    assert_eq!(tasks::get_task(&pool, &b).await.unwrap().status, "in-progress");
+}
+
+#[tokio::test]
+async fn trashing_a_task_takes_its_subtasks_along() {
+    let pool = common::pool().await;
+    let root = add(&pool, "Move", None).await;
+    let child = add(&pool, "Boxes", Some(&root)).await;
+
+    tasks::delete_task(&pool, &root).await.unwrap();
+    assert!(tasks::list_tasks(&pool, &Default::default()).await.unwrap().is_empty());
+    let bin = trash::list_trash(&pool).await.unwrap();
+    assert_eq!(bin.len(), 1);
+    assert_eq!(trash::restore(&pool, EntityKind::Task, &child).await.unwrap_err().code(), ErrorCode::Conflict);
+
+    let preview = trash::preview_delete(&pool, std::path::Path::new("/nonexistent"), EntityKind::Task, &root)
+        .await
+        .unwrap();
+    assert_eq!(preview.rows["tasks"], 2);
+
+    trash::restore(&pool, EntityKind::Task, &root).await.unwrap();
+    assert_eq!(tasks::list_tasks(&pool, &Default::default()).await.unwrap().len(), 2);
+}
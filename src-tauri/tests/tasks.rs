@@ -0,0 +1,301 @@
+mod common;
+
+use deep_focus_lib::services::tasks::{self, TaskStatus};
+use deep_focus_lib::ErrorCode;
+use serde_json::json;
+
+#[tokio::test]
+async fn add_then_get_round_trips() {
+    let pool = common::pool().await;
+
+    let id = tasks::add_task(
+        &pool,
+        common::input(json!({
+            "name": "Fix login",
+            "category": "Work",
+            "short_summary": "session cookie",
+            "status": "todo",
+            "end_est_at": "2025-09-01T10:00:00Z"
+        })),
+    )
+    .await
+    .unwrap();
+
+    let t = tasks::get_task(&pool, &id).await.unwrap();
+    assert_eq!(t.name, "Fix login");
+    assert_eq!(t.status, "todo");
+    assert_eq!(t.short_summary.as_deref(), Some("session cookie"));
+    assert!(t.end_est_at.is_some());
+
+    let rows = tasks::list_tasks(&pool, &Default::default()).await.unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].id, id);
+}
+
+#[tokio::test]
+async fn blank_name_is_a_validation_error() {
+    let pool = common::pool().await;
+
+    let err = tasks::add_task(
+        &pool,
+        common::input(json!({ "name": "  ", "category": "Work", "status": "todo" })),
+    )
+    .await
+    .unwrap_err();
+
+    assert_eq!(err.code(), ErrorCode::Validation);
+    assert_eq!(serde_json::to_value(&err).unwrap()["field"], "name");
+}
+
+#[tokio::test]
+async fn missing_task_is_not_found() {
+    let pool = common::pool().await;
+
+    let err = tasks::get_task(&pool, "nope").await.unwrap_err();
+    assert_eq!(err.code(), ErrorCode::NotFound);
+    assert_eq!(
+        serde_json::to_value(&err).unwrap(),
+        json!({ "code": "NotFound", "message": "task not found", "entity": "task", "id": "nope" })
+    );
+
+    let err = tasks::set_task_status(&pool, "nope", TaskStatus::Completed, None).await.unwrap_err();
+    assert_eq!(err.code(), ErrorCode::NotFound);
+}
+
+#[tokio::test]
+async fn update_merges_with_current_values() {
+    let pool = common::pool().await;
+    let id = tasks::add_task(
+        &pool,
+        common::input(json!({
+            "name": "Write post",
+            "category": "Personal",
+            "description": "draft",
+            "status": "todo"
+        })),
+    )
+    .await
+    .unwrap();
+
+    tasks::update_task(&pool, common::input(json!({ "id": id, "name": "Write blog post" })))
+        .await
+        .unwrap();
+    tasks::set_task_status(&pool, &id, TaskStatus::InProgress, None).await.unwrap();
+
+    let t = tasks::get_task(&pool, &id).await.unwrap();
+    assert_eq!(t.name, "Write blog post");
+    assert_eq!(t.description.as_deref(), Some("draft"));
+    assert_eq!(t.status, "in-progress");
+}
+
+#[tokio::test]
+async fn reasons_require_an_existing_task() {
+    let pool = common::pool().await;
+    let id = tasks::add_task(
+        &pool,
+        common::input(json!({ "name": "Ship", "category": "Work", "status": "todo" })),
+    )
+    .await
+    .unwrap();
+
+    tasks::add_reason(
+        &pool,
+        common::input(json!({ "task_id": id, "status": "dropped", "note": "out of scope" })),
+    )
+    .await
+    .unwrap();
+
+    let err = tasks::add_reason(
+        &pool,
+        common::input(json!({ "task_id": "ghost", "status": "dropped", "note": "x" })),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::ForeignKey);
+}
+
+#[tokio::test]
+async fn project_sets_workspace_too() {
+    let pool = common::pool().await;
+
+    let id = tasks::add_task(
+        &pool,
+        common::input(json!({
+            "name": "Invoice",
+            "category": "Admin",
+            "status": "todo",
+            "project_id": common::WORK_GENERAL
+        })),
+    )
+    .await
+    .unwrap();
+
+    let (project, workspace): (String, String) =
+        sqlx::query_as("SELECT project_id, workspace_id FROM tasks WHERE id = ?1")
+            .bind(&id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(project, common::WORK_GENERAL);
+    assert_eq!(workspace, "ws_work");
+
+    let err = tasks::add_task(
+        &pool,
+        common::input(json!({ "name": "x", "category": "y", "status": "todo", "project_id": "nope" })),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::ForeignKey);
+}
+
+#[tokio::test]
+async fn unscoped_tasks_get_a_home() {
+    let pool = common::pool().await;
+
+    let id = tasks::add_task(&pool, common::input(json!({ "name": "Loose", "category": "Misc", "status": "todo" })))
+        .await
+        .unwrap();
+    let t = tasks::get_task(&pool, &id).await.unwrap();
+    assert_eq!(t.project_id.as_deref(), Some(tasks::DEFAULT_PROJECT));
+    assert_eq!(t.workspace_id.as_deref(), Some(common::PERSONAL_WS));
+
+    let id = tasks::add_task(
+        &pool,
+        common::input(json!({ "name": "Team", "category": "Misc", "status": "todo", "workspace_id": "ws_work" })),
+    )
+    .await
+    .unwrap();
+    let t = tasks::get_task(&pool, &id).await.unwrap();
+    assert_eq!((t.project_id, t.workspace_id.as_deref()), (None, Some("ws_work")));
+
+    let err = tasks::add_task(
+        &pool,
+        common::input(json!({
+            "name": "x", "category": "y", "status": "todo",
+            "project_id": common::WORK_GENERAL, "workspace_id": common::PERSONAL_WS
+        })),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Validation);
+}
+
+#[tokio::test]
+async fn move_keeps_workspace_in_step() {
+    let pool = common::pool().await;
+    let id = tasks::add_task(&pool, common::input(json!({ "name": "Taxes", "category": "Admin", "status": "todo" })))
+        .await
+        .unwrap();
+
+    tasks::move_task(&pool, common::input(json!({ "id": id, "project_id": common::WORK_GENERAL })))
+        .await
+        .unwrap();
+    let t = tasks::get_task(&pool, &id).await.unwrap();
+    assert_eq!(t.project_id.as_deref(), Some(common::WORK_GENERAL));
+    assert_eq!(t.workspace_id.as_deref(), Some("ws_work"));
+
+    let err = tasks::move_task(&pool, common::input(json!({ "id": id, "project_id": "nope" })))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::ForeignKey);
+
+    tasks::delete_task(&pool, &id).await.unwrap();
+    let err = tasks::move_task(&pool, common::input(json!({ "id": id, "project_id": common::PERSONAL_GENERAL })))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::NotFound);
+}
+
+#[tokio::test]
+async fn list_filters_by_scope_status_and_dates() {
+    let pool = common::pool().await;
+    for (name, project, status, due) in [
+        ("a", common::PERSONAL_GENERAL, "todo", "2025-09-01T10:00:00Z"),
+        ("b", common::WORK_GENERAL, "todo", "2025-09-15T10:00:00Z"),
+        ("c", common::WORK_GENERAL, "completed", "2025-10-01T10:00:00Z"),
+    ] {
+        tasks::add_task(
+            &pool,
+            common::input(json!({ "name": name, "category": "x", "status": status, "project_id": project, "end_est_at": due })),
+        )
+        .await
+        .unwrap();
+    }
+
+    let names = |filter: serde_json::Value| {
+        let pool = pool.clone();
+        async move {
+            let mut n: Vec<String> = tasks::list_tasks(&pool, &common::input(filter))
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|t| t.name)
+                .collect();
+            n.sort();
+            n
+        }
+    };
+
+    assert_eq!(names(json!({ "workspace_id": "ws_work" })).await, ["b", "c"]);
+    assert_eq!(names(json!({ "project_id": common::WORK_GENERAL, "status": "todo" })).await, ["b"]);
+    assert_eq!(
+        names(json!({ "from": "2025-09-10T00:00:00Z", "to": "2025-09-30T00:00:00Z" })).await,
+        ["b"]
+    );
+    assert_eq!(names(json!({ "from": "2025-09-10T00:00:00Z" })).await, ["b", "c"]);
+
+    let err = tasks::list_tasks(&pool, &common::input(json!({ "from": "2025-10-01T00:00:00Z", "to": "2025-09-01T00:00:00Z" })))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Validation);
+}
+
+#[tokio::test]
+async fn status_changes_follow_the_state_machine() {
+    let pool = common::pool().await;
+    let id = tasks::add_task(&pool, common::input(json!({ "name": "Deploy", "category": "Ops", "status": "todo" })))
+        .await
+        .unwrap();
+
+    tasks::set_task_status(&pool, &id, TaskStatus::Completed, None).await.unwrap();
+    // reopening needs a reason, and a finished task can't jump to stage-complete
+    let err = tasks::set_task_status(&pool, &id, TaskStatus::Todo, None).await.unwrap_err();
+    assert_eq!(serde_json::to_value(&err).unwrap()["field"], "note");
+    let err = tasks::set_task_status(&pool, &id, TaskStatus::StageComplete, Some("x".into())).await.unwrap_err();
+    assert_eq!(serde_json::to_value(&err).unwrap()["field"], "status");
+    tasks::set_task_status(&pool, &id, TaskStatus::Todo, Some("rollback failed".into())).await.unwrap();
+
+    let err = tasks::update_task(&pool, common::input(json!({ "id": id, "status": "dropped" })))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Validation);
+    tasks::update_task(&pool, common::input(json!({ "id": id, "status": "dropped", "status_note": "not needed" })))
+        .await
+        .unwrap();
+
+    let log = tasks::list_status_reasons(&pool, &id).await.unwrap();
+    let steps: Vec<_> = log.iter().map(|r| (r.from_status.as_deref(), r.status.as_str(), r.note.as_str())).collect();
+    assert_eq!(
+        steps,
+        [
+            (Some("todo"), "completed", ""),
+            (Some("completed"), "todo", "rollback failed"),
+            (Some("todo"), "dropped", "not needed"),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn note_rules_are_configurable() {
+    let pool = common::pool().await;
+    let id = tasks::add_task(&pool, common::input(json!({ "name": "Spike", "category": "Dev", "status": "todo" })))
+        .await
+        .unwrap();
+
+    tasks::set_status_rules(&pool, &common::input(json!({ "require_note": [{ "from": null, "to": "completed" }] })))
+        .await
+        .unwrap();
+    let err = tasks::set_task_status(&pool, &id, TaskStatus::Completed, None).await.unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Validation);
+    tasks::set_task_status(&pool, &id, TaskStatus::Dropped, None).await.unwrap();
+}
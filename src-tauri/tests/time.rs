@@ -0,0 +1,119 @@
+mod common;
+
+use chrono::{DateTime, Utc};
+use deep_focus_lib::services::time::{self, TimeEntry};
+use deep_focus_lib::services::timer::Timer;
+use deep_focus_lib::services::ideas;
+use deep_focus_lib::ErrorCode;
+use serde_json::json;
+
+fn at(hm: &str) -> DateTime<Utc> {
+    format!("2025-09-01T{hm}:00Z").parse().unwrap()
+}
+
+async fn log(pool: &sqlx::SqlitePool, body: serde_json::Value) -> deep_focus_lib::Result<String> {
+    time::log_time(pool, common::input(body)).await
+}
+
+fn spans(entries: &[TimeEntry]) -> Vec<(DateTime<Utc>, Option<DateTime<Utc>>)> {
+    entries.iter().map(|e| (e.started_at, e.ended_at)).collect()
+}
+
+#[tokio::test]
+async fn overlapping_entries_are_rejected_or_trimmed() {
+    let pool = common::pool().await;
+    let (a, b) = (common::task(&pool, "Write").await, common::task(&pool, "Write").await);
+    log(&pool, json!({ "task_id": a, "started_at": at("09:00"), "ended_at": at("10:00") })).await.unwrap();
+
+    // overlaps across tasks too: one person, one clock
+    let err = log(&pool, json!({ "task_id": b, "started_at": at("09:30"), "ended_at": at("10:30") })).await.unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Conflict);
+
+    let id = log(&pool, json!({ "task_id": b, "started_at": at("09:30"), "ended_at": at("10:30"), "overlap": "trim" }))
+        .await
+        .unwrap();
+    let e = time::get_time_entry(&pool, &id).await.unwrap();
+    assert_eq!((e.started_at, e.ended_at, e.minutes), (at("10:00"), Some(at("10:30")), 30));
+
+    // nothing left after trimming, or an entry sitting inside: still rejected
+    for (start, end) in [("09:15", "09:45"), ("08:00", "11:00")] {
+        let body = json!({ "task_id": b, "started_at": at(start), "ended_at": at(end), "overlap": "trim" });
+        assert_eq!(log(&pool, body).await.unwrap_err().code(), ErrorCode::Conflict);
+    }
+
+    // edits are checked the same way, ignoring the entry itself
+    let moved = time::update_time_entry(
+        &pool,
+        common::input(json!({ "id": id, "started_at": at("09:50"), "ended_at": at("10:40"), "overlap": "trim" })),
+    )
+    .await
+    .unwrap();
+    assert_eq!((moved.started_at, moved.ended_at), (at("10:00"), Some(at("10:40"))));
+}
+
+#[tokio::test]
+async fn sessions_link_to_ideas_and_stop() {
+    let pool = common::pool().await;
+    let id = common::task(&pool, "Write").await;
+    let idea = ideas::add_idea(
+        &pool,
+        common::input(json!({ "project_id": common::PERSONAL_GENERAL, "title": "Plugin API" })),
+    )
+    .await
+    .unwrap();
+
+    let s = time::start_session(&pool, common::input(json!({ "task_id": id, "idea_id": idea }))).await.unwrap();
+    assert_eq!((s.ended_at, s.source.as_str(), s.idea_id.as_deref()), (None, "manual", Some(idea.as_str())));
+    assert_eq!(time::running_session(&pool).await.unwrap().map(|e| e.id), Some(s.id.clone()));
+
+    // one clock at a time, the focus timer included
+    let err = time::start_session(&pool, common::input(json!({ "task_id": id }))).await.unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Conflict);
+    let err = Timer::default().start(&pool, &id).await.unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Conflict);
+
+    let stopped = time::stop_session(&pool, &s.id).await.unwrap();
+    assert!(stopped.ended_at.is_some());
+    assert_eq!(time::stop_session(&pool, &s.id).await.unwrap_err().code(), ErrorCode::Validation);
+    assert!(time::running_session(&pool).await.unwrap().is_none());
+
+    let e = time::update_time_entry(&pool, common::input(json!({ "id": s.id, "idea_id": "" }))).await.unwrap();
+    assert_eq!(e.idea_id, None);
+    let err = time::update_time_entry(&pool, common::input(json!({ "id": s.id, "doc_id": "nope" }))).await.unwrap_err();
+    assert_eq!(err.code(), ErrorCode::ForeignKey);
+}
+
+#[tokio::test]
+async fn split_and_merge() {
+    let pool = common::pool().await;
+    let (a, b) = (common::task(&pool, "Write").await, common::task(&pool, "Write").await);
+    let first = log(&pool, json!({ "task_id": a, "started_at": at("09:00"), "ended_at": at("11:00"), "note": "draft" }))
+        .await
+        .unwrap();
+
+    let err = time::split_time_entry(&pool, &first, at("11:00")).await.unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Validation);
+    let second = time::split_time_entry(&pool, &first, at("10:00")).await.unwrap();
+    let entries = time::list_time_entries(&pool, &a).await.unwrap();
+    assert_eq!(spans(&entries), [(at("09:00"), Some(at("10:00"))), (at("10:00"), Some(at("11:00")))]);
+    assert_eq!(entries[1].note.as_deref(), Some("draft"));
+
+    let third = log(&pool, json!({ "task_id": a, "started_at": at("11:30"), "ended_at": at("12:00"), "note": "edit" }))
+        .await
+        .unwrap();
+    let other = log(&pool, json!({ "task_id": b, "started_at": at("13:00"), "ended_at": at("14:00") })).await.unwrap();
+
+    let err = time::merge_time_entries(&pool, &[third.clone(), other.clone()]).await.unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Validation);
+
+    let kept = time::merge_time_entries(&pool, &[third, second, first.clone()]).await.unwrap();
+    assert_eq!(kept, first);
+    let entries = time::list_time_entries(&pool, &a).await.unwrap();
+    assert_eq!(spans(&entries), [(at("09:00"), Some(at("12:00")))]);
+    assert_eq!(entries[0].note.as_deref(), Some("draft\ndraft\nedit"));
+
+    // a gap holding another task's entry can't be merged over
+    let later = log(&pool, json!({ "task_id": a, "started_at": at("15:00"), "ended_at": at("15:30") })).await.unwrap();
+    let err = time::merge_time_entries(&pool, &[first, later]).await.unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Conflict);
+}
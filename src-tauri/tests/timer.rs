@@ -0,0 +1,97 @@
+mod common;
+
+use chrono::{Duration, Utc};
+use deep_focus_lib::services::time;
+use deep_focus_lib::services::timer::{self, Phase, Timer, TimerEvent, TimerSettings};
+use deep_focus_lib::ErrorCode;
+
+#[tokio::test]
+async fn focus_interval_runs_into_a_break_and_is_logged() {
+    let pool = common::pool().await;
+    let id = common::task(&pool, "Write").await;
+    let settings = TimerSettings { focus_minutes: 1, long_break_every: 2, ..Default::default() };
+    timer::set_settings(&pool, settings).await.unwrap();
+
+    let t = Timer::default();
+    let mut rx = t.subscribe();
+    let s = t.start(&pool, &id).await.unwrap();
+    assert_eq!((s.phase, s.running, s.task_id.as_deref()), (Phase::Focus, true, Some(id.as_str())));
+    assert!(matches!(rx.recv().await.unwrap(), TimerEvent::State(_)));
+
+    let ends_at = s.ends_at.unwrap();
+    t.tick(&pool, ends_at).await.unwrap();
+    let s = t.state().await;
+    assert_eq!((s.phase, s.running, s.focus_done), (Phase::ShortBreak, true, 1));
+    let ended = std::iter::from_fn(|| rx.try_recv().ok()).find_map(|e| match e {
+        TimerEvent::PhaseEnded(p) => Some(p),
+        _ => None,
+    });
+    assert_eq!(ended.map(|p| (p.phase, p.completed, p.next)), Some((Phase::Focus, true, Phase::ShortBreak)));
+
+    let entries = time::list_time_entries(&pool, &id).await.unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!((entries[0].source.as_str(), entries[0].minutes), ("timer", 1));
+    assert_eq!(entries[0].ended_at, Some(ends_at));
+
+    // the break ends; focus waits to be resumed, then the second one earns a long break
+    t.tick(&pool, s.ends_at.unwrap()).await.unwrap();
+    let s = t.state().await;
+    assert_eq!((s.phase, s.running, s.remaining_secs), (Phase::Focus, false, 60));
+    let s = t.resume(&pool).await.unwrap();
+    t.tick(&pool, s.ends_at.unwrap()).await.unwrap();
+    assert_eq!(t.state().await.phase, Phase::LongBreak);
+    assert_eq!(time::list_time_entries(&pool, &id).await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn pause_skip_and_stop() {
+    let pool = common::pool().await;
+    let id = common::task(&pool, "Write").await;
+    let t = Timer::default();
+
+    let err = t.resume(&pool).await.unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Validation);
+    let err = t.start(&pool, "nope").await.unwrap_err();
+    assert_eq!(err.code(), ErrorCode::ForeignKey);
+
+    t.start(&pool, &id).await.unwrap();
+    let s = t.pause(&pool).await.unwrap();
+    assert!(!s.running && s.ends_at.is_none() && s.entry_id.is_none());
+    let entries = time::list_time_entries(&pool, &id).await.unwrap();
+    assert!(entries[0].ended_at.is_some());
+
+    t.resume(&pool).await.unwrap();
+    let s = t.skip(&pool).await.unwrap();
+    assert_eq!((s.phase, s.focus_done), (Phase::ShortBreak, 0)); // skipped focus doesn't count
+    assert_eq!(time::list_time_entries(&pool, &id).await.unwrap().len(), 2);
+
+    let s = t.stop(&pool).await.unwrap();
+    assert_eq!(s, Default::default());
+}
+
+#[tokio::test]
+async fn state_is_recovered_after_a_restart() {
+    let pool = common::pool().await;
+    let id = common::task(&pool, "Write").await;
+
+    let before = Timer::default();
+    let s = before.start(&pool, &id).await.unwrap();
+    drop(before);
+
+    // still running: picked up as is
+    let after = Timer::default();
+    let r = after.load(&pool).await.unwrap();
+    assert_eq!((r.phase, r.running, r.ends_at, r.entry_id.clone()), (Phase::Focus, true, s.ends_at, s.entry_id));
+
+    // ran out while the app was closed: logged up to the end, break left paused
+    let late = s.ends_at.unwrap() + Duration::minutes(30);
+    after.tick(&pool, late).await.unwrap();
+    let r = after.state().await;
+    assert_eq!((r.phase, r.running, r.focus_done), (Phase::ShortBreak, false, 1));
+    let entries = time::list_time_entries(&pool, &id).await.unwrap();
+    assert_eq!((entries.len(), entries[0].minutes), (1, 25));
+    assert!(entries[0].ended_at.unwrap() < Utc::now() + Duration::minutes(26));
+
+    let again = Timer::default();
+    assert_eq!(again.load(&pool).await.unwrap(), r);
+}
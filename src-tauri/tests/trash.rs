@@ -0,0 +1,219 @@
+mod common;
+
+use deep_focus_lib::events::EntityKind;
+use deep_focus_lib::services::tasks::TaskStatus;
+use deep_focus_lib::services::{docs, ideas, metrics, projects, stages, tasks, trash};
+use deep_focus_lib::ErrorCode;
+use serde_json::json;
+
+async fn seed_project(pool: &sqlx::SqlitePool) -> String {
+    projects::create_project(
+        pool,
+        common::input(json!({ "workspace_id": common::PERSONAL_WS, "name": "Side project" })),
+    )
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn deleted_tasks_leave_listings_and_metrics_until_restored() {
+    let pool = common::pool().await;
+    let id = tasks::add_task(
+        &pool,
+        common::input(json!({ "name": "Old", "category": "Dev", "status": "todo", "project_id": common::PERSONAL_GENERAL })),
+    )
+    .await
+    .unwrap();
+    let stage = stages::add_stage(&pool, common::input(json!({ "task_id": id, "name": "Draft" }))).await.unwrap();
+
+    tasks::delete_task(&pool, &id).await.unwrap();
+    assert!(tasks::list_tasks(&pool, &Default::default()).await.unwrap().is_empty());
+    assert_eq!(metrics::count_tasks_by_project(&pool, common::PERSONAL_GENERAL).await.unwrap(), 0);
+
+    // and take no edits while they are there
+    let err = tasks::set_task_status(&pool, &id, TaskStatus::InProgress, None).await.unwrap_err();
+    assert_eq!(err.code(), ErrorCode::NotFound);
+    let err = tasks::update_task(&pool, common::input(json!({ "id": id, "estimate_minutes": 30 }))).await.unwrap_err();
+    assert_eq!(err.code(), ErrorCode::NotFound);
+    assert_eq!(stages::advance_stage(&pool, &id).await.unwrap_err().code(), ErrorCode::NotFound);
+    assert_eq!(stages::remove_stage(&pool, &stage).await.unwrap_err().code(), ErrorCode::NotFound);
+    let err = stages::add_stage(&pool, common::input(json!({ "task_id": id, "name": "Edit" }))).await.unwrap_err();
+    assert_eq!(err.code(), ErrorCode::ForeignKey);
+
+    let bin = trash::list_trash(&pool).await.unwrap();
+    assert_eq!(bin.len(), 1);
+    assert_eq!((bin[0].kind, bin[0].id.as_str()), (EntityKind::Task, id.as_str()));
+
+    trash::restore(&pool, EntityKind::Task, &id).await.unwrap();
+    assert_eq!(tasks::list_tasks(&pool, &Default::default()).await.unwrap().len(), 1);
+    assert!(trash::list_trash(&pool).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn project_takes_its_contents_in_and_out_of_the_trash() {
+    let pool = common::pool().await;
+    let project = seed_project(&pool).await;
+    let idea = ideas::add_idea(&pool, common::input(json!({ "project_id": project, "title": "Plugin API" })))
+        .await
+        .unwrap();
+    let doc = docs::add_doc(&pool, common::input(json!({ "project_id": project, "title": "Spec", "body_md": "x" })))
+        .await
+        .unwrap();
+
+    projects::delete_project(&pool, &project).await.unwrap();
+    assert!(projects::list_projects(&pool, None, false).await.unwrap().iter().all(|p| p.id != project));
+    assert!(ideas::list_ideas(&pool, &project).await.unwrap().is_empty());
+    assert!(ideas::search_ideas(&pool, "plugin", None).await.unwrap().is_empty());
+
+    // only the project is listed; its contents went in with it
+    let bin = trash::list_trash(&pool).await.unwrap();
+    assert_eq!(bin.len(), 1);
+    assert_eq!(bin[0].kind, EntityKind::Project);
+
+    let err = trash::restore(&pool, EntityKind::Doc, &doc).await.unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Conflict);
+
+    trash::restore(&pool, EntityKind::Project, &project).await.unwrap();
+    assert_eq!(ideas::list_ideas(&pool, &project).await.unwrap()[0].id, idea);
+    assert_eq!(docs::list_docs(&pool, &project).await.unwrap()[0].id, doc);
+}
+
+#[tokio::test]
+async fn purge_removes_rows_and_owned_files() {
+    let pool = common::pool().await;
+    let dir = tempfile::tempdir().unwrap();
+    let doc = docs::add_doc(
+        &pool,
+        common::input(json!({ "project_id": common::PERSONAL_GENERAL, "title": "Notes", "body_md": "v1" })),
+    )
+    .await
+    .unwrap();
+    let path = docs::save_doc_attachment(
+        &pool,
+        dir.path(),
+        common::input(json!({ "doc_id": doc, "filename": "a.txt", "bytes_base64": "aGk=" })),
+    )
+    .await
+    .unwrap();
+
+    let err = trash::purge(&pool, dir.path(), EntityKind::Doc, &doc).await.unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Conflict);
+
+    docs::delete_doc(&pool, &doc).await.unwrap();
+    assert!(docs::search_docs(&pool, "Notes", None).await.unwrap().is_empty());
+
+    let report = trash::empty_trash(&pool, dir.path()).await.unwrap();
+    assert_eq!((report.purged, report.files_removed), (1, 1));
+    assert!(!std::path::Path::new(&path).exists());
+    assert_eq!(docs::get_doc(&pool, &doc).await.unwrap_err().code(), ErrorCode::NotFound);
+
+    let versions: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM doc_version WHERE doc_id = ?1")
+        .bind(&doc)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(versions, 0);
+}
+
+#[tokio::test]
+async fn empty_trash_skips_rows_already_purged_with_their_project() {
+    let pool = common::pool().await;
+    let dir = tempfile::tempdir().unwrap();
+    let project = seed_project(&pool).await;
+    let task = tasks::add_task(
+        &pool,
+        common::input(json!({ "name": "Early", "category": "Dev", "status": "todo", "project_id": project })),
+    )
+    .await
+    .unwrap();
+
+    // the task goes in first, so its project is purged before it
+    tasks::delete_task(&pool, &task).await.unwrap();
+    projects::delete_project(&pool, &project).await.unwrap();
+    assert_eq!(trash::list_trash(&pool).await.unwrap().len(), 2);
+
+    let report = trash::empty_trash(&pool, dir.path()).await.unwrap();
+    assert_eq!(report.purged, 1);
+    assert!(trash::list_trash(&pool).await.unwrap().is_empty());
+    assert_eq!(tasks::get_task(&pool, &task).await.unwrap_err().code(), ErrorCode::NotFound);
+}
+
+#[tokio::test]
+async fn purge_leaves_files_outside_attachments_alone() {
+    let pool = common::pool().await;
+    let dir = tempfile::tempdir().unwrap();
+    let outside = dir.path().join("outside.txt");
+    let db_file = dir.path().join("deepfocus.db");
+    std::fs::write(&outside, "keep").unwrap();
+    std::fs::write(&db_file, "keep").unwrap();
+
+    let idea = ideas::add_idea(&pool, common::input(json!({ "project_id": common::PERSONAL_GENERAL, "title": "Links" })))
+        .await
+        .unwrap();
+    let climbing = dir.path().join("attachments").join("x").join("..").join("..").join("outside.txt");
+    for path in [climbing, db_file.clone()] {
+        ideas::add_idea_attachment(
+            &pool,
+            common::input(json!({ "idea_id": idea, "filename": "f", "path": path.to_string_lossy() })),
+        )
+        .await
+        .unwrap();
+    }
+
+    let preview = trash::preview_delete(&pool, dir.path(), EntityKind::Idea, &idea).await.unwrap();
+    assert_eq!(preview.files, 0);
+    ideas::delete_idea(&pool, &idea).await.unwrap();
+    let report = trash::empty_trash(&pool, dir.path()).await.unwrap();
+    assert_eq!(report.files_removed, 0);
+    assert!(outside.exists() && db_file.exists());
+}
+
+#[tokio::test]
+async fn workspace_delete_needs_a_fresh_preview_token() {
+    let pool = common::pool().await;
+    let dir = tempfile::tempdir().unwrap();
+    let ws = projects::create_workspace(&pool, common::input(json!({ "name": "Client" }))).await.unwrap();
+    let project = projects::create_project(&pool, common::input(json!({ "workspace_id": ws, "name": "Site" })))
+        .await
+        .unwrap();
+    let doc = docs::add_doc(&pool, common::input(json!({ "project_id": project, "title": "Brief", "body_md": "v1" })))
+        .await
+        .unwrap();
+    docs::save_doc_attachment(
+        &pool,
+        dir.path(),
+        common::input(json!({ "doc_id": doc, "filename": "a.txt", "bytes_base64": "aGk=" })),
+    )
+    .await
+    .unwrap();
+
+    let preview = trash::preview_delete(&pool, dir.path(), EntityKind::Workspace, &ws).await.unwrap();
+    assert_eq!(preview.rows["project"], 1);
+    assert_eq!(preview.rows["doc"], 1);
+    assert_eq!(preview.rows["doc_attachment"], 1);
+    assert_eq!((preview.files, preview.file_bytes), (1, 2));
+
+    // a task added after the preview invalidates its token
+    tasks::add_task(&pool, common::input(json!({ "name": "Call", "category": "x", "status": "todo", "project_id": project })))
+        .await
+        .unwrap();
+    let err = trash::delete_permanently(&pool, dir.path(), EntityKind::Workspace, &ws, &preview.token)
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Conflict);
+
+    let preview = trash::preview_delete(&pool, dir.path(), EntityKind::Workspace, &ws).await.unwrap();
+    assert_eq!(preview.rows["tasks"], 1);
+    let report = trash::delete_permanently(&pool, dir.path(), EntityKind::Workspace, &ws, &preview.token)
+        .await
+        .unwrap();
+    assert_eq!(report.files_removed, 1);
+    assert!(tasks::list_tasks(&pool, &Default::default()).await.unwrap().is_empty());
+    assert_eq!(docs::get_doc(&pool, &doc).await.unwrap_err().code(), ErrorCode::NotFound);
+
+    let preview = trash::preview_delete(&pool, dir.path(), EntityKind::Workspace, common::PERSONAL_WS).await.unwrap();
+    let err = trash::delete_permanently(&pool, dir.path(), EntityKind::Workspace, common::PERSONAL_WS, &preview.token)
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Conflict);
+}
@@ -0,0 +1,173 @@
+mod common;
+
+use deep_focus_lib::services::vault::{self, ImportMode};
+use deep_focus_lib::services::{docs, ideas, tasks};
+use deep_focus_lib::ErrorCode;
+use serde_json::json;
+
+/// A task, an idea with a note, and a doc with an attachment on disk.
+async fn seed(pool: &sqlx::SqlitePool, app_dir: &std::path::Path) -> (String, String, String) {
+    let task = tasks::add_task(
+        pool,
+        common::input(json!({ "name": "Ship export", "category": "Dev", "status": "todo" })),
+    )
+    .await
+    .unwrap();
+
+    let idea = ideas::add_idea(
+        pool,
+        common::input(json!({ "project_id": common::PERSONAL_GENERAL, "title": "Vault sync" })),
+    )
+    .await
+    .unwrap();
+    ideas::add_idea_note(pool, common::input(json!({ "idea_id": idea, "body_md": "zip it" })))
+        .await
+        .unwrap();
+
+    let doc = docs::add_doc(
+        pool,
+        common::input(json!({ "project_id": common::PERSONAL_GENERAL, "title": "Handbook", "body_md": "# Hi" })),
+    )
+    .await
+    .unwrap();
+    docs::save_doc_attachment(
+        pool,
+        app_dir,
+        common::input(json!({ "doc_id": doc, "filename": "a.txt", "bytes_base64": "aGVsbG8=" })),
+    )
+    .await
+    .unwrap();
+
+    (task, idea, doc)
+}
+
+#[tokio::test]
+async fn round_trips_into_an_empty_vault() {
+    let src_dir = tempfile::tempdir().unwrap();
+    let dst_dir = tempfile::tempdir().unwrap();
+    let archive = src_dir.path().join("vault.zip");
+
+    let src = common::pool().await;
+    let (task, idea, doc) = seed(&src, src_dir.path()).await;
+    let manifest = vault::export_vault(&src, src_dir.path(), &archive).await.unwrap();
+    assert_eq!(manifest.counts["idea_note"], 1);
+    assert_eq!(manifest.attachments, 1);
+
+    let dst = common::pool().await;
+    let report = vault::import_vault(&dst, dst_dir.path(), &archive, ImportMode::Merge).await.unwrap();
+    // only the seeded workspaces/projects can clash (their timestamps differ per database)
+    assert!(report.conflicts.iter().all(|c| c.table == "workspace" || c.table == "project"));
+    assert_eq!(report.inserted["tasks"], 1);
+    assert_eq!(report.attachments, 1);
+
+    assert_eq!(tasks::get_task(&dst, &task).await.unwrap().name, "Ship export");
+    assert_eq!(ideas::get_idea(&dst, &idea).await.unwrap().title, "Vault sync");
+    assert_eq!(docs::get_doc(&dst, &doc).await.unwrap().title, "Handbook");
+
+    // attachment rows point into the new app dir
+    let path: String = sqlx::query_scalar("SELECT path FROM doc_attachment WHERE doc_id = ?1")
+        .bind(&doc)
+        .fetch_one(&dst)
+        .await
+        .unwrap();
+    assert!(path.starts_with(&*dst_dir.path().to_string_lossy()));
+    assert_eq!(std::fs::read_to_string(path).unwrap(), "hello");
+}
+
+#[tokio::test]
+async fn replace_swaps_out_the_local_vault() {
+    let src_dir = tempfile::tempdir().unwrap();
+    let dst_dir = tempfile::tempdir().unwrap();
+    let archive = src_dir.path().join("vault.zip");
+
+    let src = common::pool().await;
+    let (task, idea, _) = seed(&src, src_dir.path()).await;
+    vault::export_vault(&src, src_dir.path(), &archive).await.unwrap();
+
+    let dst = common::pool().await;
+    let (local_task, local_idea, _) = seed(&dst, dst_dir.path()).await;
+
+    let report = vault::import_vault(&dst, dst_dir.path(), &archive, ImportMode::Replace).await.unwrap();
+    assert!(report.conflicts.is_empty());
+
+    assert_eq!(tasks::get_task(&dst, &local_task).await.unwrap_err().code(), ErrorCode::NotFound);
+    assert_eq!(ideas::get_idea(&dst, &local_idea).await.unwrap_err().code(), ErrorCode::NotFound);
+    assert_eq!(tasks::get_task(&dst, &task).await.unwrap().name, "Ship export");
+    assert_eq!(ideas::get_idea(&dst, &idea).await.unwrap().title, "Vault sync");
+}
+
+#[tokio::test]
+async fn merge_keeps_local_rows_and_reports_conflicts() {
+    let dir = tempfile::tempdir().unwrap();
+    let archive = dir.path().join("vault.zip");
+
+    let pool = common::pool().await;
+    let (task, _, _) = seed(&pool, dir.path()).await;
+    vault::export_vault(&pool, dir.path(), &archive).await.unwrap();
+
+    tasks::update_task(&pool, common::input(json!({ "id": task, "name": "Renamed locally" })))
+        .await
+        .unwrap();
+
+    let report = vault::import_vault(&pool, dir.path(), &archive, ImportMode::Merge).await.unwrap();
+    assert!(report.inserted.is_empty());
+    assert!(report.unchanged > 0);
+    assert_eq!(report.conflicts.len(), 1);
+    assert_eq!(report.conflicts[0].table, "tasks");
+    assert_eq!(report.conflicts[0].id, task);
+    assert_eq!(tasks::get_task(&pool, &task).await.unwrap().name, "Renamed locally");
+}
+
+#[tokio::test]
+async fn rejects_foreign_archives() {
+    let dir = tempfile::tempdir().unwrap();
+    let archive = dir.path().join("other.zip");
+
+    let mut zip = zip::ZipWriter::new(std::fs::File::create(&archive).unwrap());
+    zip.start_file("manifest.json", zip::write::SimpleFileOptions::default()).unwrap();
+    std::io::Write::write_all(
+        &mut zip,
+        br#"{"format":"deepfocus-vault","format_version":99,"schema_version":0,"exported_at":"2025-09-01T00:00:00Z","counts":{},"attachments":0}"#,
+    )
+    .unwrap();
+    zip.finish().unwrap();
+
+    let pool = common::pool().await;
+    let err = vault::import_vault(&pool, dir.path(), &archive, ImportMode::Merge).await.unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Validation);
+}
+
+#[tokio::test]
+async fn refuses_attachment_paths_that_escape() {
+    let src_dir = tempfile::tempdir().unwrap();
+    let dst_dir = tempfile::tempdir().unwrap();
+    let linked = tempfile::tempdir().unwrap();
+    let src = common::pool().await;
+    let idea = ideas::add_idea(&src, common::input(json!({ "project_id": common::PERSONAL_GENERAL, "title": "Links" })))
+        .await
+        .unwrap();
+    let attach = |path: std::path::PathBuf| {
+        common::input(json!({ "idea_id": idea, "filename": "f", "path": path.to_string_lossy() }))
+    };
+
+    // a file linked from elsewhere stays a link
+    let outside = linked.path().join("notes.txt");
+    ideas::add_idea_attachment(&src, attach(outside.clone())).await.unwrap();
+    let archive = src_dir.path().join("ok.zip");
+    vault::export_vault(&src, src_dir.path(), &archive).await.unwrap();
+    let dst = common::pool().await;
+    vault::import_vault(&dst, dst_dir.path(), &archive, ImportMode::Merge).await.unwrap();
+    let path: String = sqlx::query_scalar("SELECT path FROM idea_attachment").fetch_one(&dst).await.unwrap();
+    assert_eq!(path, outside.to_string_lossy());
+
+    // exported relative to the app dir, this one climbs out of attachments/
+    let climbing = src_dir.path().join("attachments").join("..").join("..").join("victim.txt");
+    ideas::add_idea_attachment(&src, attach(climbing)).await.unwrap();
+    let archive = src_dir.path().join("bad.zip");
+    vault::export_vault(&src, src_dir.path(), &archive).await.unwrap();
+    let dst = common::pool().await;
+    let err = vault::import_vault(&dst, dst_dir.path(), &archive, ImportMode::Merge).await.unwrap_err();
+    assert_eq!(err.code(), ErrorCode::Validation);
+    let rows: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM idea_attachment").fetch_one(&dst).await.unwrap();
+    assert_eq!(rows, 0);
+}
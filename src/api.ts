@@ -1,5 +1,5 @@
 import { invoke } from "@tauri-apps/api/core";
-import type { TaskDetail, TaskInput, TaskRow, TaskStatus,  IdeaDetail,
+import type { StatusChange, TaskDetail, TaskInput, TaskRow, TaskStatus,  IdeaDetail,
   IdeaRow,
   IdeaStatus,
   DocDetail,
@@ -16,11 +16,11 @@ export const api = {
   async addTask(input: TaskInput): Promise<string> {
     return invoke<string>("add_task", { input });
   },
-  async updateTask(input: Partial<TaskInput> & { id: string }): Promise<void> {
-    return invoke("update_task", { input });
+  async updateTask(input: Partial<TaskInput> & { id: string }): Promise<StatusChange> {
+    return invoke<StatusChange>("update_task", { input });
   },
-  async setTaskStatus(id: string, status: TaskStatus): Promise<void> {
-    return invoke("set_task_status", { id, status });
+  async setTaskStatus(id: string, status: TaskStatus): Promise<StatusChange> {
+    return invoke<StatusChange>("set_task_status", { id, status });
   },
   async addReason(taskId: string, status: TaskStatus, note: string): Promise<void> {
     return invoke("add_reason", { input: { taskId, status, note } });
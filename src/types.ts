@@ -15,6 +15,24 @@ export type TaskRow = {
   start_at?: string | null; // ISO
   end_est_at?: string | null; // ISO
   updated_at: string; // ISO
+  project_id: string | null;
+  workspace_id: string | null;
+  parent_id: string | null;
+  estimate_minutes: number | null;
+  series_id: string | null; // set on occurrences of a recurring task
+  blocked: boolean; // some predecessor isn't completed yet
+};
+
+/** A node of `TaskDetail.subtasks`; `progress` is 0..100, rolled up. */
+export type Subtask = {
+  id: string;
+  name: string;
+  status: TaskStatus;
+  ord: number;
+  progress: number;
+  estimate_minutes: number | null; // own, else the sum of its subtasks'
+  actual_minutes: number; // logged on it and its subtasks
+  subtasks: Subtask[];
 };
 
 export type TaskDetail = {
@@ -29,8 +47,69 @@ export type TaskDetail = {
   end_est_at?: string | null;
   created_at: string; // ISO
   updated_at: string; // ISO
+  project_id: string | null;
+  workspace_id: string | null;
+  origin_idea: { id: string; title: string } | null;
+  parent_id: string | null;
+  progress: number; // 0..100, from subtasks (or stages for a leaf)
+  subtasks: Subtask[];
+  estimate_minutes: number | null; // own estimate, editable
+  time: {
+    estimate_minutes: number | null; // own, else the sum of its subtasks'
+    actual_minutes: number;
+    variance_minutes: number | null; // actual - estimate; positive = over
+  };
+  recurrence: Recurrence | null;
+  blocked: boolean;
+  blocked_by: LinkedTask[]; // predecessors, finished or not
+  blocks: LinkedTask[];
+};
+
+/** What a status change has to say besides succeeding. */
+export type StatusChange = {
+  warning: string | null; // e.g. started while still blocked
+};
+
+/** The other end of a task dependency. */
+export type LinkedTask = {
+  id: string;
+  name: string;
+  status: TaskStatus;
+  project_id: string | null;
+};
+
+/** `add_dependency` / `remove_dependency`: `task_id` waits for `depends_on`. */
+export type DependencyInput = {
+  task_id: string;
+  depends_on: string;
+};
+
+/** `dependency_graph(project_id)`; edges point from a predecessor to the task it blocks. */
+export type DependencyGraph = {
+  nodes: GraphNode[]; // by depth, then name
+  edges: { from: string; to: string }[];
+};
+
+export type GraphNode = LinkedTask & {
+  blocked: boolean;
+  depth: number; // longest chain of predecessors; 0 = nothing before it
+};
+
+/** RFC 5545 RRULE value, expanded in `timezone` local time. */
+export type RecurrenceInput = {
+  rrule: string; // e.g. "FREQ=WEEKLY;BYDAY=MO", "FREQ=MONTHLY;BYDAY=-1FR"
+  timezone: string; // IANA, e.g. "Europe/Berlin"
+};
+
+export type Recurrence = RecurrenceInput & {
+  series_id: string;
+  occurrence: number; // 1-based
+  next_at: string | null; // null once the series ends
 };
 
+/** `update_series_task`: edit just this occurrence, or it and the ones after. */
+export type SeriesScope = "this" | "following";
+
 export type TaskInput = {
   name: string;
   category: string;
@@ -40,6 +119,26 @@ export type TaskInput = {
   current_stage?: string;
   start_at?: string | null;
   end_est_at?: string | null;
+  project_id?: string | null; // defaults to Personal / General
+  workspace_id?: string | null; // workspace-level task when project_id is unset
+  parent_id?: string | null; // subtask; project comes from the parent
+  estimate_minutes?: number | null;
+  recurrence?: RecurrenceInput | null; // needs start_at or end_est_at
+};
+
+/** `list_tasks` filter; `from`/`to` match tasks whose start..due window overlaps. */
+export type TaskFilter = {
+  project_id?: string;
+  workspace_id?: string;
+  status?: TaskStatus;
+  from?: string; // ISO
+  to?: string; // ISO
+  blocked?: boolean;
+};
+
+export type MoveTaskInput = {
+  id: string;
+  project_id: string;
 };
 
 export type IdeaStatus =
@@ -70,6 +169,8 @@ export type IdeaDetail = {
   impact_pts: number;
   created_at: string;
   updated_at: string;
+  tasks: { id: string; name: string; status: TaskStatus }[];
+  ready_to_ship: boolean; // all linked tasks completed: offer "Mark shipped"
 };
 
 export type DocRow = {
@@ -111,4 +212,297 @@ export type WorkspaceOption = {
   kind: "personal" | "work" | "custom" | string;
   created_at: string;
   updated_at: string;
-};
\ No newline at end of file
+};
+// Rejection payload of every Tauri command (see src-tauri/src/error.rs)
+export type AppErrorCode =
+  | "NotFound"
+  | "Validation"
+  | "Conflict"
+  | "ForeignKey"
+  | "Io"
+  | "Db"
+  | "Other";
+
+export type AppError = {
+  code: AppErrorCode;
+  message: string;
+  entity?: string;
+  field?: string;
+  id?: string;
+};
+
+export type ApiSettings = {
+  enabled: boolean;
+  port: number;
+};
+
+export type ApiStatus = ApiSettings & {
+  running: boolean;
+  base_url: string | null;
+  token: string;
+};
+
+export type BackupSettings = {
+  enabled: boolean;
+  interval_hours: number;
+  keep: number;
+};
+
+export type BackupInfo = {
+  name: string;
+  path: string;
+  size_bytes: number;
+  created_at: string;
+};
+
+export type BackupCheck = {
+  name: string;
+  ok: boolean;
+  problems: string[];
+};
+
+export type VaultManifest = {
+  format: string;
+  format_version: number;
+  schema_version: number;
+  exported_at: string;
+  counts: Record<string, number>;
+  attachments: number;
+};
+
+export type ImportMode = "merge" | "replace";
+
+export type ImportReport = {
+  inserted: Record<string, number>;
+  unchanged: number;
+  conflicts: { table: string; id: string; reason: string }[];
+  attachments: number;
+};
+
+/** Payload of the `entity-changed` event emitted after every successful write. */
+export type EntityChanged = {
+  kind: "task" | "idea" | "doc" | "project" | "workspace" | "vault";
+  id: string; // "*" for kind "vault": refetch everything
+  op: "created" | "updated" | "deleted";
+};
+
+export type OpSummary = {
+  id: number;
+  label: string;
+  created_at: string;
+};
+
+/** Next undo and redo for the Edit menu; null when there is none. */
+export type UndoState = {
+  undo: OpSummary | null;
+  redo: OpSummary | null;
+};
+
+/** One field change from `get_history`; values are raw JSON (null = unset). */
+export type FieldChange = {
+  id: number;
+  field: string;
+  old_value: unknown;
+  new_value: unknown;
+  changed_at: string;
+};
+
+export type TrashItem = {
+  kind: "task" | "idea" | "doc" | "project";
+  id: string;
+  title: string;
+  project_id: string | null;
+  deleted_at: string;
+};
+
+export type PurgeReport = {
+  purged: number;
+  files_removed: number;
+};
+
+/** `preview_delete`: pass `token` back to `delete_permanently` to confirm. */
+export type DeletePreview = {
+  kind: "workspace" | "project" | "idea" | "doc" | "task";
+  id: string;
+  rows: Record<string, number>; // table -> rows removed
+  files: number;
+  file_bytes: number;
+  token: string;
+};
+
+export type Stage = {
+  id: string;
+  task_id: string;
+  name: string;
+  summary?: string | null;
+  ord: number;
+};
+
+/** Returned by `advance_stage`; `percent` is `reached / total`, rounded down. */
+export type StageProgress = {
+  task_id: string;
+  current_stage: string | null;
+  reached: number;
+  total: number;
+  percent: number;
+};
+
+/** One entry of `list_status_reasons`; `from_status` is null for plain notes. */
+export type StatusReason = {
+  id: string;
+  task_id: string;
+  from_status: TaskStatus | null;
+  status: TaskStatus;
+  note: string;
+  created_at: string;
+};
+
+/** Transitions that need a note; a null end matches any status. */
+export type StatusRules = {
+  require_note: { from: TaskStatus | null; to: TaskStatus | null }[];
+  complete_parent: boolean; // complete a task once all its subtasks are done
+  note_to_start_blocked: boolean; // starting a task with open predecessors takes a note
+};
+
+export type TimeEntry = {
+  id: string;
+  task_id: string;
+  idea_id: string | null;
+  doc_id: string | null;
+  started_at: string;
+  ended_at: string | null; // null while running
+  minutes: number;
+  source: "manual" | "timer" | string;
+  note: string | null;
+  created_at: string;
+};
+
+/** Overlapping writes are rejected unless "trim" shrinks them to the free time. */
+export type OverlapMode = "reject" | "trim";
+
+export type LogTimeInput = {
+  task_id: string;
+  idea_id?: string | null;
+  doc_id?: string | null;
+  started_at: string;
+  ended_at: string;
+  note?: string | null;
+  overlap?: OverlapMode;
+};
+
+export type StartSessionInput = {
+  task_id: string;
+  idea_id?: string | null;
+  doc_id?: string | null;
+  note?: string | null;
+};
+
+/** Omitted fields stay; "" for idea_id / doc_id unlinks. */
+export type UpdateTimeEntryInput = {
+  id: string;
+  task_id?: string;
+  idea_id?: string;
+  doc_id?: string;
+  started_at?: string;
+  ended_at?: string;
+  note?: string;
+  overlap?: OverlapMode;
+};
+
+/** `estimate_accuracy` row, sorted most underestimated (highest ratio) first. */
+export type EstimateAccuracy = {
+  key: string | null; // category, or project id
+  label: string;
+  tasks: number;
+  estimate_minutes: number;
+  actual_minutes: number;
+  ratio: number; // actual / estimate
+};
+
+export type TimerPhase = "idle" | "focus" | "short_break" | "long_break";
+
+/** `get_timer` and the `timer` event (every second while running). */
+export type TimerState = {
+  phase: TimerPhase;
+  task_id: string | null;
+  running: boolean;
+  ends_at: string | null; // set while running
+  remaining_secs: number;
+  focus_done: number; // completed focus intervals since the timer started
+  entry_id: string | null; // open TimeEntry of a running focus
+};
+
+/** Payload of the `timer-phase` event. */
+export type TimerPhaseEnded = {
+  phase: TimerPhase;
+  completed: boolean; // false when skipped
+  next: TimerPhase;
+  task_id: string | null;
+};
+
+export type TimerSettings = {
+  focus_minutes: number;
+  short_break_minutes: number;
+  long_break_minutes: number;
+  long_break_every: number;
+  auto_start_breaks: boolean;
+  auto_start_focus: boolean;
+};
+
+/** `generate_report` / `save_report` input; dates are local to `timezone`. */
+export type ReportInput = {
+  from: string; // YYYY-MM-DD
+  to: string; // YYYY-MM-DD, inclusive
+  timezone: string; // IANA, e.g. "Europe/Berlin"
+};
+
+export type ReportTotals = {
+  tasks_completed: number;
+  focus_minutes: number;
+  focus_sessions: number;
+  doc_words: number;
+};
+
+export type Report = {
+  id: string | null; // set once saved
+  from: string;
+  to: string;
+  timezone: string;
+  generated_at: string;
+  totals: ReportTotals;
+  days: { date: string; tasks_completed: number; focus_minutes: number; doc_words: number }[];
+  completed: { id: string; name: string; category: string; project_id: string | null; completed_at: string }[];
+  focus: { task_id: string; name: string; minutes: number; sessions: number }[];
+  docs: { doc_id: string; title: string; words: number }[];
+};
+
+export type ReportSummary = {
+  id: string;
+  from: string;
+  to: string;
+  timezone: string;
+  totals: ReportTotals;
+  created_at: string;
+};
+
+/** `render_report` / `export_report` / `GET /reports/{id}/export?format=` */
+export type ReportFormat = "markdown" | "csv" | "html";
+
+export type ReminderSettings = {
+  enabled: boolean;
+  offsets_minutes: number[]; // before end_est_at, longest first
+  overdue: boolean; // also remind once the due time has passed
+};
+
+/** `list_notifications` entry and payload of the `notification` event. */
+export type Notification = {
+  id: string;
+  task_id: string;
+  task_name: string;
+  kind: "due_soon" | "overdue";
+  offset_minutes: number; // 0 for overdue
+  due_at: string;
+  fire_at: string;
+  snoozed_until: string | null;
+  missed: boolean; // shown late, e.g. caught up after a restart
+};
@@ -47,7 +47,8 @@ export function TasksList() {
   }, [rows]);
 
   async function handleStatusChange(id: string, next: TaskStatus) {
-    await api.setTaskStatus(id, next);
+    const { warning } = await api.setTaskStatus(id, next);
+    if (warning) alert(warning);
     setRows(await api.listTasks());
   }
 
@@ -47,7 +47,8 @@ export function TaskShow() {
 
   async function changeStatus(next: TaskStatus) {
     if (!task) return;
-    await api.setTaskStatus(task.id, next);
+    const { warning } = await api.setTaskStatus(task.id, next);
+    if (warning) alert(warning);
     setTask(await api.getTask(task.id));
   }
 